@@ -0,0 +1,419 @@
+// =============================================================================
+// affiliates.rs — ONE BANKRUPTCY, THIRTY-SEVEN PETITIONS
+// =============================================================================
+//
+// When a carrier with a holding company, a leasing company, a brokerage arm
+// and a subsidiary per terminal goes under, every one of them files its own
+// petition, usually within the same hour, and the court orders them "jointly
+// administered" under the lead debtor's case number. PACER's RSS feed lists
+// each one. Without this module, so did we: thirty-seven events, one
+// bankruptcy, and an unhappy on-call shipper.
+//
+// Filings that say "Jointly Administered under Case No. 24-10123" or "Lead
+// Case No. 24-10123" are held in a group keyed by court + lead case for
+// `FREIGHT_DOOM_AFFILIATE_WINDOW`. When the window closes, the group is
+// published as a single event for the lead debtor, with every other debtor
+// and its case number listed in `affiliates`.
+//
+// Once a group is open, later affiliates join it without passing the
+// freight relevance check — "Acme Leasing Holdings II, LLC" doesn't mention
+// trucks, but its lead debtor does.
+//
+// The lead debtor's petition rarely says "lead case", so it often goes out
+// on its own before the first affiliate names its case. A family that
+// closes without its lead, for a case that's already been published, is
+// not a new bankruptcy: it goes out as an `AffiliatesJoined` update to that
+// case, not as a second detection with an affiliate standing in as debtor.
+//
+// Groups live in memory only. So held filings aren't claimed in the dedup
+// backend until their family is published: if the process dies while a
+// family is collecting, the filings are still new to dedup when the feed
+// serves them again, and the family gets collected from scratch.
+// =============================================================================
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use portable_atomic::{AtomicU64, Ordering};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::lifecycle;
+use crate::models::{AffiliateDebtor, BankruptcyEvent};
+
+/// Filings collected for one lead case.
+struct Group {
+    opened_at: Instant,
+    /// The lead case number as the first filing wrote it.
+    lead_case_number: String,
+    /// Scanner that opened the group, credited when it's published.
+    scanner: String,
+    /// Each filing's dedup key, and the filing.
+    members: Vec<(String, BankruptcyEvent)>,
+}
+
+/// A family whose window has closed, taken out of the grouper.
+pub struct Family {
+    /// Scanner that opened the group, credited when it's published.
+    pub scanner: String,
+    key: String,
+    lead_case_number: String,
+    /// Each filing's dedup key, and the filing. Drop the ones that were
+    /// published already before calling `into_event`.
+    pub members: Vec<(String, BankruptcyEvent)>,
+}
+
+impl Family {
+    /// The case key of the family's lead case.
+    pub fn lead_case_key(&self) -> &str {
+        &self.key
+    }
+
+    /// Whether the lead debtor's own petition is among the members.
+    pub fn has_lead(&self) -> bool {
+        self.members.iter().any(|(_, event)| own_case_key(event).as_deref() == Some(self.key.as_str()))
+    }
+
+    /// One event for the whole family, or `None` if no members are left.
+    pub fn into_event(self) -> Option<BankruptcyEvent> {
+        if self.members.is_empty() {
+            return None;
+        }
+        let members = self.members.into_iter().map(|(_, event)| event).collect();
+        Some(parent_event(&self.key, self.lead_case_number, members))
+    }
+
+    /// The members as affiliates of a lead case that went out without them:
+    /// the first member's event, moved to the lead case, with every member
+    /// in `affiliates`. `None` if no members are left.
+    pub fn into_joined(self) -> Option<BankruptcyEvent> {
+        let mut event = self.members.first()?.1.clone();
+        event.case_number = Some(self.lead_case_number.clone());
+        event.lead_case_number = Some(self.lead_case_number);
+        event.affiliates = affiliate_list(self.members.into_iter().map(|(_, member)| member));
+        Some(event)
+    }
+}
+
+/// Statistics for metrics.
+#[derive(Default)]
+pub struct AffiliateStats {
+    pub groups_published: AtomicU64,
+    pub filings_grouped: AtomicU64,
+}
+
+/// A serializable snapshot of the grouper's statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct AffiliateSnapshot {
+    pub groups_open: usize,
+    pub groups_published: u64,
+    pub filings_grouped: u64,
+}
+
+/// Collects jointly administered filings and turns each family into one
+/// event for its lead debtor.
+pub struct AffiliateGrouper {
+    window: Duration,
+    groups: Mutex<HashMap<String, Group>>,
+    pub stats: AffiliateStats,
+}
+
+impl AffiliateGrouper {
+    /// A grouper that holds each family for `window`. A zero window turns
+    /// grouping off.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            groups: Mutex::new(HashMap::new()),
+            stats: AffiliateStats::default(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.affiliate_window)
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Whether `event` belongs to a family being collected right now.
+    pub fn is_collecting(&self, event: &BankruptcyEvent) -> bool {
+        let groups = self.groups.lock();
+        group_key(event, &groups).is_some_and(|key| groups.contains_key(&key))
+    }
+
+    /// Hold `event` if it's part of a joint administration, or hand it
+    /// back to be published on its own. A filing already held under the
+    /// same `dedup_key` (the next poll of the same feed) isn't held twice.
+    /// Nothing is counted until the family is published.
+    pub fn hold(&self, scanner: &str, dedup_key: &str, event: BankruptcyEvent) -> Option<BankruptcyEvent> {
+        if self.window.is_zero() {
+            return Some(event);
+        }
+        let mut groups = self.groups.lock();
+        let Some(key) = group_key(&event, &groups) else {
+            return Some(event);
+        };
+
+        let group = groups.entry(key).or_insert_with(|| Group {
+            opened_at: Instant::now(),
+            lead_case_number: event.lead_case_number.clone().unwrap_or_default(),
+            scanner: scanner.to_string(),
+            members: Vec::new(),
+        });
+        if group.members.iter().all(|(key, _)| key != dedup_key) {
+            group.members.push((dedup_key.to_string(), event));
+        }
+        None
+    }
+
+    /// Count a family that went out, with the `filings` in it that weren't
+    /// published before.
+    pub fn record_published(&self, filings: usize) {
+        self.stats.groups_published.fetch_add(1, Ordering::Relaxed);
+        self.stats.filings_grouped.fetch_add(filings as u64, Ordering::Relaxed);
+    }
+
+    /// Remove every family whose window has closed by `now`.
+    pub fn take_due(&self, now: Instant) -> Vec<Family> {
+        self.take(|group| now.saturating_duration_since(group.opened_at) >= self.window)
+    }
+
+    /// Remove every family, window or not. For shutdown.
+    pub fn take_all(&self) -> Vec<Family> {
+        self.take(|_| true)
+    }
+
+    fn take(&self, due: impl Fn(&Group) -> bool) -> Vec<Family> {
+        let mut groups = self.groups.lock();
+        let keys: Vec<String> = groups
+            .iter()
+            .filter(|(_, group)| due(group))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let group = groups.remove(&key)?;
+                Some(Family {
+                    scanner: group.scanner,
+                    key,
+                    lead_case_number: group.lead_case_number,
+                    members: group.members,
+                })
+            })
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> AffiliateSnapshot {
+        AffiliateSnapshot {
+            groups_open: self.groups.lock().len(),
+            groups_published: self.stats.groups_published.load(Ordering::Relaxed),
+            filings_grouped: self.stats.filings_grouped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The family `event` belongs to: the lead case it names, or its own case
+/// if that's the lead of a family already being collected (the lead
+/// debtor's own petition rarely says "lead case").
+fn group_key(event: &BankruptcyEvent, groups: &HashMap<String, Group>) -> Option<String> {
+    let court = event.court.as_deref()?;
+    if let Some(lead) = event.lead_case_number.as_deref() {
+        return lifecycle::case_key(court, lead);
+    }
+    lifecycle::case_key(court, event.case_number.as_deref()?).filter(|key| groups.contains_key(key))
+}
+
+/// One event for the whole family. It's the lead debtor's own event if we
+/// saw it; otherwise the most confident affiliate stands in, with the case
+/// number switched to the lead case so docket entries there find it.
+fn parent_event(key: &str, lead_case_number: String, mut members: Vec<BankruptcyEvent>) -> BankruptcyEvent {
+    let lead = members.iter().position(|m| own_case_key(m).as_deref() == Some(key));
+    let parent_index = lead.unwrap_or_else(|| {
+        members
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.confidence_score.total_cmp(&b.confidence_score))
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    });
+    let mut parent = members.remove(parent_index);
+
+    if lead.is_none() {
+        members.insert(0, parent.clone());
+        parent.case_number = Some(lead_case_number.clone());
+    }
+    parent.lead_case_number = Some(lead_case_number);
+
+    for member in &members {
+        if member.confidence_score > parent.confidence_score {
+            parent.confidence_score = member.confidence_score;
+            parent.classification = member.classification.clone();
+            parent.classifications = member.classifications.clone();
+            parent.score_breakdown = member.score_breakdown.clone();
+        }
+        parent.dot_number = parent.dot_number.take().or_else(|| member.dot_number.clone());
+        parent.mc_number = parent.mc_number.take().or_else(|| member.mc_number.clone());
+        parent.filing_date = match (parent.filing_date, member.filing_date) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    parent.affiliates = affiliate_list(members);
+    parent
+}
+
+/// The key of the case `event` was filed in.
+fn own_case_key(event: &BankruptcyEvent) -> Option<String> {
+    lifecycle::case_key(event.court.as_deref()?, event.case_number.as_deref()?)
+}
+
+/// Each member as an affiliate debtor, once.
+fn affiliate_list(members: impl IntoIterator<Item = BankruptcyEvent>) -> Vec<AffiliateDebtor> {
+    let mut affiliates: Vec<AffiliateDebtor> = Vec::new();
+    for member in members {
+        let affiliate = AffiliateDebtor {
+            company_name: member.company_name,
+            case_number: member.case_number,
+        };
+        if !affiliates.contains(&affiliate) {
+            affiliates.push(affiliate);
+        }
+    }
+    affiliates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CompanyClassification, Source};
+
+    fn filing(name: &str, case: &str, lead: Option<&str>, confidence: f64) -> BankruptcyEvent {
+        let mut event = BankruptcyEvent::new(name.into(), Source::Pacer, confidence);
+        event.court = Some("Delaware".into());
+        event.case_number = Some(case.into());
+        event.lead_case_number = lead.map(str::to_string);
+        event
+    }
+
+    /// Hold under the key the PACER scanner would give the filing.
+    fn hold(grouper: &AffiliateGrouper, event: BankruptcyEvent) -> Option<BankruptcyEvent> {
+        let key = format!("pacer:case:{}", event.case_number.as_deref().unwrap_or_default());
+        grouper.hold("pacer", &key, event)
+    }
+
+    fn grouper() -> AffiliateGrouper {
+        AffiliateGrouper::new(Duration::from_secs(60))
+    }
+
+    #[test]
+    fn test_family_becomes_one_event_for_the_lead_debtor() {
+        let grouper = grouper();
+        let mut lead = filing("Acme Freight Holdings, Inc.", "24-10123", Some("24-10123"), 0.6);
+        lead.classification = CompanyClassification::Carrier;
+        assert!(hold(&grouper, lead).is_none());
+        assert!(hold(&grouper, filing("Acme Leasing LLC", "24-10124", Some("24-10123"), 0.0)).is_none());
+        assert!(hold(&grouper, filing("Acme Brokerage LLC", "24-10125-KBO", Some("1:24-bk-10123"), 0.9)).is_none());
+
+        assert!(grouper.take_due(Instant::now()).is_empty());
+        let published = grouper.take_due(Instant::now() + Duration::from_secs(61));
+        assert_eq!(published.len(), 1);
+
+        let family = published.into_iter().next().unwrap();
+        assert_eq!(family.scanner, "pacer");
+        let parent = family.into_event().unwrap();
+        assert_eq!(parent.company_name, "Acme Freight Holdings, Inc.");
+        assert_eq!(parent.case_number.as_deref(), Some("24-10123"));
+        assert_eq!(parent.confidence_score, 0.9);
+        let affiliates: Vec<(&str, Option<&str>)> = parent
+            .affiliates
+            .iter()
+            .map(|a| (a.company_name.as_str(), a.case_number.as_deref()))
+            .collect();
+        assert_eq!(
+            affiliates,
+            vec![("Acme Leasing LLC", Some("24-10124")), ("Acme Brokerage LLC", Some("24-10125-KBO"))]
+        );
+        assert_eq!(grouper.snapshot().groups_open, 0);
+    }
+
+    #[test]
+    fn test_lead_petition_without_the_words_joins_an_open_family() {
+        let grouper = grouper();
+        let lead = filing("Acme Freight Holdings, Inc.", "24-10123", None, 0.7);
+        let sibling = filing("Acme Trucking LLC", "24-10125", Some("24-10123"), 0.0);
+        assert!(!grouper.is_collecting(&lead));
+        assert!(!grouper.is_collecting(&sibling));
+        assert!(hold(&grouper, filing("Acme Leasing LLC", "24-10124", Some("24-10123"), 0.5)).is_none());
+        assert!(grouper.is_collecting(&lead));
+        assert!(grouper.is_collecting(&sibling));
+        assert!(hold(&grouper, lead).is_none());
+
+        let family = grouper.take_all().pop().unwrap();
+        assert!(family.has_lead());
+        let parent = family.into_event().unwrap();
+        assert_eq!(parent.company_name, "Acme Freight Holdings, Inc.");
+        assert_eq!(parent.affiliates.len(), 1);
+    }
+
+    #[test]
+    fn test_affiliate_stands_in_when_the_lead_never_shows() {
+        let grouper = grouper();
+        hold(&grouper, filing("Acme Leasing LLC", "24-10124", Some("24-10123"), 0.5));
+        hold(&grouper, filing("Acme Trucking LLC", "24-10125", Some("24-10123"), 0.8));
+
+        let family = grouper.take_all().pop().unwrap();
+        assert!(!family.has_lead());
+        assert_eq!(family.lead_case_key(), "deb:24-10123");
+        let parent = family.into_event().unwrap();
+        assert_eq!(parent.company_name, "Acme Trucking LLC");
+        assert_eq!(parent.case_number.as_deref(), Some("24-10123"));
+        assert_eq!(parent.affiliates.len(), 2);
+    }
+
+    #[test]
+    fn test_same_filing_polled_twice_is_held_once() {
+        let grouper = grouper();
+        for _ in 0..2 {
+            assert!(hold(&grouper, filing("Acme Leasing LLC", "24-10124", Some("24-10123"), 0.5)).is_none());
+        }
+
+        let mut family = grouper.take_all().pop().unwrap();
+        let keys: Vec<&str> = family.members.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["pacer:case:24-10124"]);
+
+        // Every member already published: nothing left to say, and
+        // nothing counted.
+        family.members.clear();
+        assert!(family.into_event().is_none());
+        assert_eq!(grouper.snapshot().groups_published, 0);
+        assert_eq!(grouper.snapshot().filings_grouped, 0);
+    }
+
+    #[test]
+    fn test_late_affiliates_join_the_lead_case() {
+        let grouper = grouper();
+        hold(&grouper, filing("Acme Leasing LLC", "24-10124", Some("24-10123"), 0.5));
+        hold(&grouper, filing("Acme Trucking LLC", "24-10125", Some("1:24-bk-10123"), 0.8));
+
+        let joined = grouper.take_all().pop().unwrap().into_joined().unwrap();
+        assert_eq!(joined.company_name, "Acme Leasing LLC");
+        assert_eq!(joined.case_number.as_deref(), Some("24-10123"));
+        let names: Vec<&str> = joined.affiliates.iter().map(|a| a.company_name.as_str()).collect();
+        assert_eq!(names, vec!["Acme Leasing LLC", "Acme Trucking LLC"]);
+    }
+
+    #[test]
+    fn test_standalone_filings_pass_straight_through() {
+        let grouper = grouper();
+        let event = filing("Lonely Carrier LLC", "24-20001", None, 0.8);
+        assert!(hold(&grouper, event).is_some());
+
+        let off = AffiliateGrouper::new(Duration::ZERO);
+        assert!(hold(&off, filing("Acme Leasing LLC", "24-10124", Some("24-10123"), 0.5)).is_some());
+    }
+}
@@ -0,0 +1,82 @@
+// =============================================================================
+// freight_doom_train — SCHOOL FOR THE ENGINE
+// =============================================================================
+//
+// Trains the relevance model offline from a labelled JSONL corpus and writes
+// the model file the engine loads via FREIGHT_DOOM_RELEVANCE_MODEL:
+//
+//   freight_doom_train corpus.jsonl relevance.json [--ngrams 2] [--min-count 2] [--smoothing 1.0]
+//
+// One JSON object per line: {"text": "...", "label": true | false | "<status>"}
+// where a status is the dashboard's triage (confirmed, resolved, false_alarm).
+// See `text_scanner::classifier` for the model itself.
+// =============================================================================
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{bail, Context, Result};
+
+use freight_doom_engine::text_scanner::classifier::{read_corpus, RelevanceModel, TrainOptions};
+
+const USAGE: &str =
+    "usage: freight_doom_train <corpus.jsonl> <model.json> [--ngrams N] [--min-count N] [--smoothing X]";
+
+struct Args {
+    corpus: PathBuf,
+    model: PathBuf,
+    options: TrainOptions,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args> {
+    let mut paths = Vec::new();
+    let mut options = TrainOptions::default();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().with_context(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--ngrams" => options.ngrams = value("--ngrams")?.parse().context("--ngrams")?,
+            "--min-count" => options.min_count = value("--min-count")?.parse().context("--min-count")?,
+            "--smoothing" => options.smoothing = value("--smoothing")?.parse().context("--smoothing")?,
+            flag if flag.starts_with("--") => bail!("unknown option {}", flag),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    let [corpus, model] = <[PathBuf; 2]>::try_from(paths).ok().context(USAGE)?;
+    if !options.smoothing.is_finite() || options.smoothing <= 0.0 {
+        bail!("--smoothing must be above zero");
+    }
+    Ok(Args { corpus, model, options })
+}
+
+fn run() -> Result<()> {
+    let args = parse_args(std::env::args().skip(1))?;
+    let examples = read_corpus(&args.corpus)?;
+    let model = RelevanceModel::train(&examples, &args.options)?;
+
+    let correct = examples
+        .iter()
+        .filter(|e| (model.predict(&e.text).probability >= 0.5) == e.relevant)
+        .count();
+    model.save(&args.model)?;
+
+    println!(
+        "🧠 Trained on {} texts ({} relevant, {} not): {} n-grams, {:.1}% right on its own training data",
+        examples.len(),
+        model.relevant_examples,
+        model.irrelevant_examples,
+        model.vocabulary(),
+        100.0 * correct as f64 / examples.len() as f64,
+    );
+    println!("💾 Wrote {}", args.model.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("freight_doom_train: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
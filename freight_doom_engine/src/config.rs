@@ -16,6 +16,7 @@
 // =============================================================================
 
 use std::env;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// The Grand Configuration Struct. Every tunable parameter in the entire
@@ -42,6 +43,76 @@ pub struct Config {
     /// Because pub/sub is fire-and-forget, and we don't want to forget.
     pub redis_sorted_set: String,
 
+    /// The Redis stream the `redis_stream` sink XADDs events to.
+    pub redis_stream: String,
+
+    /// Approximate cap on the stream length (XADD MAXLEN ~).
+    /// Default: 100,000 — plenty of history for a consumer to replay.
+    pub redis_stream_maxlen: usize,
+
+    /// How long the Redis sinks wait for a connection or a reply before
+    /// treating Redis as down. A Redis that stops answering, rather than
+    /// refusing, would otherwise hold up the publisher stage indefinitely.
+    /// Default: 5s
+    pub redis_timeout: Duration,
+
+    // =========================================================================
+    // SINKS
+    // =========================================================================
+
+    /// Where detected events go: any of redis, redis_stream, jsonl, webhook,
+    /// stdout. Every batch goes to all of them.
+    /// Default: redis
+    pub sinks: Vec<String>,
+
+    /// How long a sink waits before its first retry after a failure.
+    /// Doubles on every failure after that. Default: 1 second
+    pub sink_retry_base_delay: Duration,
+
+    /// Longest a sink ever waits between retries. Default: 60 seconds
+    pub sink_retry_max_delay: Duration,
+
+    /// Most events a failing sink may hold before the oldest are dropped.
+    /// Default: 10,000
+    pub sink_max_backlog: usize,
+
+    /// Directory for the `jsonl` sink's files. Default: ./events
+    pub jsonl_dir: PathBuf,
+
+    /// Size at which the `jsonl` sink starts a new file. Default: 64 MiB
+    pub jsonl_max_bytes: u64,
+
+    /// How many `jsonl` files to keep. 0 keeps everything. Default: 30
+    pub jsonl_max_files: usize,
+
+    /// Where the `webhook` sink POSTs events.
+    pub webhook_url: Option<String>,
+
+    /// Shared secret for the `webhook` sink's HMAC-SHA256 signature.
+    pub webhook_secret: Option<String>,
+
+    /// Request timeout for the `webhook` sink. Default: 10 seconds
+    pub webhook_timeout: Duration,
+
+    // =========================================================================
+    // OUTBOX
+    // =========================================================================
+
+    /// Directory for the on-disk outboxes in front of the Redis sinks. Each
+    /// sink gets its own subdirectory. `None` (FREIGHT_DOOM_OUTBOX_DIR set
+    /// to "") publishes straight to Redis, for people who enjoy living
+    /// dangerously.
+    /// Default: ./outbox
+    pub outbox_dir: Option<PathBuf>,
+
+    /// Size at which the active outbox segment is sealed and a new one started.
+    /// Default: 8 MiB
+    pub outbox_segment_bytes: u64,
+
+    /// How long to wait before retrying delivery after Redis fails.
+    /// Default: 5 seconds
+    pub outbox_retry_interval: Duration,
+
     // =========================================================================
     // POLLING CONFIGURATION
     // Because checking once per second is barely adequate, but checking
@@ -66,6 +137,11 @@ pub struct Config {
     /// They're a non-profit. Let's be nice to their servers.
     pub court_listener_poll_interval: Duration,
 
+    /// Which scanners to run, by name. Default: all four.
+    /// Set FREIGHT_DOOM_SCANNERS=pacer,edgar to give FMCSA and
+    /// CourtListener the day off.
+    pub enabled_scanners: Vec<String>,
+
     // =========================================================================
     // API ENDPOINTS
     // These are REAL public government URLs. No mocks. No fakes.
@@ -94,7 +170,9 @@ pub struct Config {
     // For when "probably unique" is good enough.
     // =========================================================================
 
-    /// Expected number of items in the bloom filter before rotation.
+    /// Expected number of new items per bloom filter generation. A
+    /// generation that takes this many is rotated early, however young it
+    /// is, so the filter never holds more than it was sized for.
     /// Higher = more memory, fewer false positives.
     /// Lower = less memory, more false positives.
     /// Default: 100_000 because we're optimists about the volume of
@@ -107,8 +185,11 @@ pub struct Config {
     /// Given how many there are, this is acceptable.
     pub bloom_false_positive_rate: f64,
 
-    /// How often to rotate the bloom filter (in seconds).
+    /// Minimum time between age-based bloom filter rotations (in seconds).
     /// Rotation prevents the filter from saturating and rejecting everything.
+    /// The dedup engine stretches this to the longest dedup TTL, so with the
+    /// default TTLs it only matters if you set it above 90 days. Filling up
+    /// (`bloom_expected_items`) rotates sooner.
     /// Default: 3600 (1 hour)
     pub bloom_rotation_interval: Duration,
 
@@ -116,6 +197,84 @@ pub struct Config {
     /// The LRU cache catches what the bloom filter might miss.
     pub lru_cache_size: usize,
 
+    /// Where the dedup state (Bloom bits, LRU keys, rotation time) is saved
+    /// so a restart doesn't re-emit every filing still sitting in the feeds.
+    /// `None` (FREIGHT_DOOM_DEDUP_SNAPSHOT set to "") keeps it in memory only.
+    /// Default: ./dedup.snapshot
+    pub dedup_snapshot_path: Option<PathBuf>,
+
+    /// How often the dedup state is saved, on top of the save at shutdown.
+    /// Default: 60 seconds
+    pub dedup_snapshot_interval: Duration,
+
+    /// Where dedup decisions are made: "local" (this process only) or
+    /// "redis" (shared with every engine pointed at the same Redis, so
+    /// replicas don't all publish the same filing).
+    /// Default: local
+    pub dedup_backend: String,
+
+    /// How long a dedup key is suppressed after it's published, for sources
+    /// without their own entry in `dedup_source_ttls`. Also the expiry on
+    /// keys claimed in the shared Redis backend.
+    /// Default: 30 days, comfortably longer than filings linger in the feeds
+    pub dedup_default_ttl: Duration,
+
+    /// Per-source TTLs, keyed by dedup key prefix (pacer, edgar, fmcsa, cl).
+    /// FREIGHT_DOOM_DEDUP_SOURCE_TTLS=fmcsa=90d,edgar=7d
+    /// Default: fmcsa=90d, because a carrier can lose its authority, get it
+    /// back, and lose it again, and each of those is news
+    pub dedup_source_ttls: Vec<(String, Duration)>,
+
+    // =========================================================================
+    // ENTITY RESOLUTION
+    // One bankruptcy, four sources, one case.
+    // =========================================================================
+
+    /// How long a case stays open for other sources to corroborate it.
+    /// An event matching a case nobody has mentioned in this long opens a
+    /// new case instead.
+    /// Default: 30 days, about how long FMCSA takes to notice
+    pub resolution_window: Duration,
+
+    /// Maximum number of open cases tracked for resolution.
+    /// Default: 10,000
+    pub resolution_max_cases: usize,
+
+    /// Name similarity (0.0–1.0) at which two debtor names that differ
+    /// after normalisation still count as the same company.
+    /// Default: 0.92. Lower it and "Acme Freight" starts marrying
+    /// "Acme Freightways".
+    pub name_match_threshold: f64,
+
+    // =========================================================================
+    // CASE LIFECYCLE
+    // Filing is chapter one. Conversion to Chapter 7 is the last chapter.
+    // =========================================================================
+
+    /// Where case records (status, chapter, lifecycle history) are kept so
+    /// conversions and dismissals of cases found before a restart are still
+    /// recognised. `None` (FREIGHT_DOOM_CASE_STORE set to "") keeps them in
+    /// memory only.
+    /// Default: ./cases.json
+    pub case_store_path: Option<PathBuf>,
+
+    /// How often the case store is written, if anything changed. Cases are
+    /// recorded in memory; a crash loses at most this much.
+    /// Default: 5 seconds
+    pub case_store_interval: Duration,
+
+    /// How long a closed or dismissed case is remembered after its last
+    /// docket entry. Open cases are kept however old they get.
+    /// Default: 180 days
+    pub case_retention: Duration,
+
+    /// How long to collect jointly administered affiliate filings before
+    /// publishing them as one event under the lead debtor. A carrier and
+    /// its subsidiaries file within minutes of each other; "0s" publishes
+    /// every affiliate on its own.
+    /// Default: 15 minutes
+    pub affiliate_window: Duration,
+
     // =========================================================================
     // CIRCUIT BREAKER PARAMETERS
     // Because government APIs go down more often than you'd think.
@@ -137,10 +296,19 @@ pub struct Config {
     // METRICS SERVER
     // =========================================================================
 
+    /// Address the metrics HTTP server binds to.
+    /// Default: 0.0.0.0, so the Rails app and Prometheus can both reach it.
+    pub metrics_host: String,
+
     /// Port for the metrics HTTP server.
     /// Default: 9090, because Prometheus conventions are conventions.
     pub metrics_port: u16,
 
+    /// /readyz fails once a source's last successful poll is more than
+    /// this many of its poll intervals old.
+    /// Default: 3. One missed poll is bad luck. Three is a pattern.
+    pub readiness_stale_intervals: u32,
+
     // =========================================================================
     // TEXT SCANNER PARAMETERS
     // =========================================================================
@@ -150,6 +318,21 @@ pub struct Config {
     /// Default: 0.3 (30%) — we'd rather have false positives than miss
     /// a real bankruptcy.
     pub min_confidence_threshold: f64,
+
+    /// A keyword dictionary (TOML, laid out like `text_scanner/keywords.toml`)
+    /// to use instead of the built-in one. `None` (FREIGHT_DOOM_KEYWORDS unset
+    /// or "") keeps the built-in vocabulary.
+    pub keywords_path: Option<PathBuf>,
+
+    /// How often the keyword dictionary is checked for changes. "0s" loads
+    /// it once at startup and never again.
+    /// Default: 30 seconds
+    pub keywords_reload_interval: Duration,
+
+    /// A relevance model written by `freight_doom_train`, blended with the
+    /// keyword score by the dictionary's `model_weight`. `None`
+    /// (FREIGHT_DOOM_RELEVANCE_MODEL unset or "") scores on keywords alone.
+    pub relevance_model_path: Option<PathBuf>,
 }
 
 impl Config {
@@ -171,6 +354,42 @@ impl Config {
             redis_url: env_or_default("FREIGHT_DOOM_REDIS_URL", "redis://127.0.0.1:6379"),
             redis_channel: env_or_default("FREIGHT_DOOM_REDIS_CHANNEL", "bankruptcy:events"),
             redis_sorted_set: env_or_default("FREIGHT_DOOM_REDIS_SORTED_SET", "bankruptcy:events:history"),
+            redis_stream: env_or_default("FREIGHT_DOOM_REDIS_STREAM", "bankruptcy:events:stream"),
+            redis_stream_maxlen: env_or_default("FREIGHT_DOOM_REDIS_STREAM_MAXLEN", "100000")
+                .parse().unwrap_or(100_000),
+            redis_timeout: parse_duration(&env_or_default("FREIGHT_DOOM_REDIS_TIMEOUT", "5s"))
+                .unwrap_or(Duration::from_secs(5)),
+
+            // Sinks
+            sinks: env_list("FREIGHT_DOOM_SINKS", "redis"),
+            sink_retry_base_delay: Duration::from_millis(
+                env_or_default("FREIGHT_DOOM_SINK_RETRY_BASE_MS", "1000").parse().unwrap_or(1000)
+            ),
+            sink_retry_max_delay: Duration::from_secs(
+                env_or_default("FREIGHT_DOOM_SINK_RETRY_MAX_SECS", "60").parse().unwrap_or(60)
+            ),
+            sink_max_backlog: env_or_default("FREIGHT_DOOM_SINK_MAX_BACKLOG", "10000")
+                .parse().unwrap_or(10_000),
+            jsonl_dir: PathBuf::from(env_or_default("FREIGHT_DOOM_JSONL_DIR", "events")),
+            jsonl_max_bytes: env_or_default("FREIGHT_DOOM_JSONL_MAX_BYTES", "67108864")
+                .parse().unwrap_or(64 * 1024 * 1024),
+            jsonl_max_files: env_or_default("FREIGHT_DOOM_JSONL_MAX_FILES", "30")
+                .parse().unwrap_or(30),
+            webhook_url: env::var("FREIGHT_DOOM_WEBHOOK_URL").ok().filter(|s| !s.is_empty()),
+            webhook_secret: env::var("FREIGHT_DOOM_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+            webhook_timeout: Duration::from_secs(
+                env_or_default("FREIGHT_DOOM_WEBHOOK_TIMEOUT_SECS", "10").parse().unwrap_or(10)
+            ),
+
+            // Outbox
+            outbox_dir: Some(env_or_default("FREIGHT_DOOM_OUTBOX_DIR", "outbox"))
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+            outbox_segment_bytes: env_or_default("FREIGHT_DOOM_OUTBOX_SEGMENT_BYTES", "8388608")
+                .parse().unwrap_or(8 * 1024 * 1024),
+            outbox_retry_interval: Duration::from_secs(
+                env_or_default("FREIGHT_DOOM_OUTBOX_RETRY_SECS", "5").parse().unwrap_or(5)
+            ),
 
             // Poll intervals (in seconds, converted to Duration)
             pacer_poll_interval: Duration::from_secs(
@@ -185,6 +404,7 @@ impl Config {
             court_listener_poll_interval: Duration::from_secs(
                 env_or_default("FREIGHT_DOOM_COURTLISTENER_POLL_SECS", "45").parse().unwrap_or(45)
             ),
+            enabled_scanners: env_list("FREIGHT_DOOM_SCANNERS", "pacer,edgar,fmcsa,court_listener"),
 
             // API Endpoints — these are the REAL deal
             pacer_base_url: env_or_default(
@@ -214,6 +434,41 @@ impl Config {
             ),
             lru_cache_size: env_or_default("FREIGHT_DOOM_LRU_CACHE_SIZE", "10000")
                 .parse().unwrap_or(10_000),
+            dedup_snapshot_path: Some(env_or_default("FREIGHT_DOOM_DEDUP_SNAPSHOT", "dedup.snapshot"))
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            dedup_snapshot_interval: Duration::from_secs(
+                env_or_default("FREIGHT_DOOM_DEDUP_SNAPSHOT_SECS", "60").parse().unwrap_or(60)
+            ),
+            dedup_backend: env_or_default("FREIGHT_DOOM_DEDUP_BACKEND", "local"),
+            dedup_default_ttl: parse_duration(&env_or_default("FREIGHT_DOOM_DEDUP_TTL", "30d"))
+                .unwrap_or(Duration::from_secs(30 * 86_400)),
+            dedup_source_ttls: env_list("FREIGHT_DOOM_DEDUP_SOURCE_TTLS", "fmcsa=90d")
+                .iter()
+                .filter_map(|entry| {
+                    let (source, ttl) = entry.split_once('=')?;
+                    Some((source.trim().to_string(), parse_duration(ttl.trim())?))
+                })
+                .collect(),
+
+            // Entity resolution
+            resolution_window: parse_duration(&env_or_default("FREIGHT_DOOM_RESOLUTION_WINDOW", "30d"))
+                .unwrap_or(Duration::from_secs(30 * 86_400)),
+            resolution_max_cases: env_or_default("FREIGHT_DOOM_RESOLUTION_MAX_CASES", "10000")
+                .parse().unwrap_or(10_000),
+            name_match_threshold: env_or_default("FREIGHT_DOOM_NAME_MATCH_THRESHOLD", "0.92")
+                .parse().unwrap_or(crate::names::DEFAULT_THRESHOLD),
+
+            // Case lifecycle
+            case_store_path: Some(env_or_default("FREIGHT_DOOM_CASE_STORE", "cases.json"))
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            case_store_interval: parse_duration(&env_or_default("FREIGHT_DOOM_CASE_STORE_INTERVAL", "5s"))
+                .unwrap_or(Duration::from_secs(5)),
+            case_retention: parse_duration(&env_or_default("FREIGHT_DOOM_CASE_RETENTION", "180d"))
+                .unwrap_or(crate::lifecycle::DEFAULT_RETENTION),
+            affiliate_window: parse_duration(&env_or_default("FREIGHT_DOOM_AFFILIATE_WINDOW", "15m"))
+                .unwrap_or(Duration::from_secs(15 * 60)),
 
             // Circuit breaker
             circuit_breaker_failure_threshold: env_or_default(
@@ -227,16 +482,37 @@ impl Config {
             ).parse().unwrap_or(2),
 
             // Metrics
+            metrics_host: env_or_default("FREIGHT_DOOM_METRICS_HOST", "0.0.0.0"),
             metrics_port: env_or_default("FREIGHT_DOOM_METRICS_PORT", "9090")
                 .parse().unwrap_or(9090),
+            readiness_stale_intervals: env_or_default("FREIGHT_DOOM_READINESS_STALE_INTERVALS", "3")
+                .parse().unwrap_or(3),
 
             // Text scanner
             min_confidence_threshold: env_or_default(
                 "FREIGHT_DOOM_MIN_CONFIDENCE", "0.3"
             ).parse().unwrap_or(0.3),
+            keywords_path: Some(env_or_default("FREIGHT_DOOM_KEYWORDS", ""))
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            keywords_reload_interval: parse_duration(&env_or_default("FREIGHT_DOOM_KEYWORDS_RELOAD", "30s"))
+                .unwrap_or(Duration::from_secs(30)),
+            relevance_model_path: Some(env_or_default("FREIGHT_DOOM_RELEVANCE_MODEL", ""))
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
         }
     }
 
+    /// `host:port` for the metrics server to bind to.
+    pub fn metrics_addr(&self) -> String {
+        format!("{}:{}", self.metrics_host, self.metrics_port)
+    }
+
+    /// Is the scanner with this name enabled?
+    pub fn scanner_enabled(&self, name: &str) -> bool {
+        self.enabled_scanners.iter().any(|s| s.eq_ignore_ascii_case(name))
+    }
+
     /// Returns the list of PACER bankruptcy court RSS feed URLs.
     /// These are REAL court RSS feeds from major bankruptcy courts
     /// across the United States. Each one is a firehose of financial despair.
@@ -277,3 +553,28 @@ impl Config {
 fn env_or_default(key: &str, default: &str) -> String {
     env::var(key).unwrap_or_else(|_| default.to_string())
 }
+
+/// Read a comma-separated environment variable into a list of trimmed,
+/// non-empty strings.
+fn env_list(key: &str, default: &str) -> Vec<String> {
+    env_or_default(key, default)
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Parse a duration like "90s", "15m", "12h" or "30d". A bare number is
+/// seconds.
+pub(crate) fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (number, unit_secs) = match text.char_indices().last()? {
+        (i, 's') => (&text[..i], 1),
+        (i, 'm') => (&text[..i], 60),
+        (i, 'h') => (&text[..i], 3_600),
+        (i, 'd') => (&text[..i], 86_400),
+        _ => (text, 1),
+    };
+    let secs: u64 = number.trim().parse().ok()?;
+    Some(Duration::from_secs(secs.checked_mul(unit_secs)?))
+}
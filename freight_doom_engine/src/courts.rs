@@ -0,0 +1,234 @@
+// =============================================================================
+// courts.rs — "DELAWARE", "D. DEL." AND "THE DISTRICT OF DELAWARE" ARE ONE COURT
+// =============================================================================
+//
+// Every scanner names courts its own way:
+//
+//   PACER:          "Delaware", "S.D. New York"       (our feed labels)
+//   CourtListener:  "United States Bankruptcy Court for the District of Delaware"
+//   Bluebook:       "Bankr. D. Del.", "Bankr. S.D.N.Y."
+//
+// Case keys, dedup keys and the case tracker all used to take the court as
+// written, so one case reported by PACER and CourtListener had two keys,
+// and entity resolution took the difference as proof of two bankruptcies.
+//
+// `court_id` turns any of those into the court's PACER id ("deb", "nysb"):
+// the state's postal code, the district letter if the state has more than
+// one district, and "b" for bankruptcy. The state is the last state name or
+// abbreviation in the text; the district is whatever sits right before it
+// ("N.D.", "Southern District of", "District of", "D."). A state with
+// several districts and no district named, or a district the state doesn't
+// have, is not a court.
+// =============================================================================
+
+/// (postal code, how the state is written, district letters). An empty
+/// district list means the state is one district. Spellings are in
+/// `tokens` form: uppercase words, punctuation dropped.
+const STATES: &[(&str, &[&str], &str)] = &[
+    ("al", &["ALABAMA", "ALA"], "nms"),
+    ("ak", &["ALASKA"], ""),
+    ("az", &["ARIZONA", "ARIZ"], ""),
+    ("ar", &["ARKANSAS", "ARK"], "ew"),
+    ("ca", &["CALIFORNIA", "CALIF", "CAL"], "necs"),
+    ("co", &["COLORADO", "COLO"], ""),
+    ("ct", &["CONNECTICUT", "CONN"], ""),
+    ("de", &["DELAWARE", "DEL"], ""),
+    ("dc", &["DISTRICT OF COLUMBIA", "D C"], ""),
+    ("fl", &["FLORIDA", "FLA"], "nms"),
+    ("ga", &["GEORGIA", "GA"], "nms"),
+    ("hi", &["HAWAII", "HAW"], ""),
+    ("id", &["IDAHO"], ""),
+    ("il", &["ILLINOIS", "ILL"], "ncs"),
+    ("in", &["INDIANA", "IND"], "ns"),
+    ("ia", &["IOWA"], "ns"),
+    ("ks", &["KANSAS", "KAN"], ""),
+    ("ky", &["KENTUCKY", "KY"], "ew"),
+    ("la", &["LOUISIANA", "LA"], "emw"),
+    ("me", &["MAINE", "ME"], ""),
+    ("md", &["MARYLAND", "MD"], ""),
+    ("ma", &["MASSACHUSETTS", "MASS"], ""),
+    ("mi", &["MICHIGAN", "MICH"], "ew"),
+    ("mn", &["MINNESOTA", "MINN"], ""),
+    ("ms", &["MISSISSIPPI", "MISS"], "ns"),
+    ("mo", &["MISSOURI", "MO"], "ew"),
+    ("mt", &["MONTANA", "MONT"], ""),
+    ("ne", &["NEBRASKA", "NEB"], ""),
+    ("nv", &["NEVADA", "NEV"], ""),
+    ("nh", &["NEW HAMPSHIRE", "N H"], ""),
+    ("nj", &["NEW JERSEY", "N J"], ""),
+    ("nm", &["NEW MEXICO", "N M"], ""),
+    ("ny", &["NEW YORK", "N Y"], "nesw"),
+    ("nc", &["NORTH CAROLINA", "N C"], "emw"),
+    ("nd", &["NORTH DAKOTA", "N D"], ""),
+    ("oh", &["OHIO"], "ns"),
+    ("ok", &["OKLAHOMA", "OKLA"], "new"),
+    ("or", &["OREGON", "OR"], ""),
+    ("pa", &["PENNSYLVANIA", "PA"], "emw"),
+    ("pr", &["PUERTO RICO", "P R"], ""),
+    ("ri", &["RHODE ISLAND", "R I"], ""),
+    ("sc", &["SOUTH CAROLINA", "S C"], ""),
+    ("sd", &["SOUTH DAKOTA", "S D"], ""),
+    ("tn", &["TENNESSEE", "TENN"], "emw"),
+    ("tx", &["TEXAS", "TEX"], "nesw"),
+    ("ut", &["UTAH"], ""),
+    ("vt", &["VERMONT", "VT"], ""),
+    ("va", &["VIRGINIA", "VA"], "ew"),
+    ("wa", &["WASHINGTON", "WASH"], "ew"),
+    ("wv", &["WEST VIRGINIA", "W VA"], "ns"),
+    ("wi", &["WISCONSIN", "WIS"], "ew"),
+    ("wy", &["WYOMING", "WYO"], ""),
+];
+
+/// Words that name a district, and the letter each one stands for.
+const DIRECTIONS: &[(&str, &str, char)] = &[
+    ("NORTHERN", "N", 'n'),
+    ("SOUTHERN", "S", 's'),
+    ("EASTERN", "E", 'e'),
+    ("WESTERN", "W", 'w'),
+    ("MIDDLE", "M", 'm'),
+    ("CENTRAL", "C", 'c'),
+];
+
+/// The PACER id of the bankruptcy court `court` names, e.g. "deb" for
+/// "Bankr. D. Del." or "nysb" for "Southern District of New York".
+/// An id passes through as itself.
+pub fn court_id(court: &str) -> Option<String> {
+    let bare = court.trim().to_lowercase();
+    if is_court_id(&bare) {
+        return Some(bare);
+    }
+
+    let upper = tokens(court);
+    let words: Vec<&str> = upper.split(' ').filter(|w| !w.is_empty()).collect();
+    let (state, start) = last_state(&words)?;
+    let (code, _, districts) = STATES[state];
+    match district_before(&words[..start]) {
+        Some(letter) if districts.contains(letter) => Some(format!("{}{}b", code, letter)),
+        None if districts.is_empty() => Some(format!("{}b", code)),
+        _ => None,
+    }
+}
+
+fn is_court_id(id: &str) -> bool {
+    let Some(rest) = id.strip_suffix('b') else {
+        return false;
+    };
+    STATES.iter().any(|(code, _, districts)| match rest.strip_prefix(code) {
+        Some("") => districts.is_empty(),
+        Some(letter) => letter.len() == 1 && districts.contains(letter),
+        None => false,
+    })
+}
+
+/// Uppercase words, with everything but letters and digits as separators:
+/// "S.D.N.Y." → "S D N Y".
+fn tokens(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { ' ' })
+        .collect()
+}
+
+/// The state whose name ends last in `words`, the longest spelling on a
+/// tie ("W. Va." over "Va."), and where its name starts.
+fn last_state(words: &[&str]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None; // (end, len, state)
+    for (state, (_, spellings, _)) in STATES.iter().enumerate() {
+        for spelling in *spellings {
+            let name: Vec<&str> = spelling.split(' ').collect();
+            for start in (0..words.len()).filter(|&i| words[i..].starts_with(&name)) {
+                let candidate = (start + name.len(), name.len(), state);
+                if best.is_none_or(|(end, len, _)| (candidate.0, candidate.1) > (end, len)) {
+                    best = Some(candidate);
+                }
+            }
+        }
+    }
+    best.map(|(end, len, state)| (state, end - len))
+}
+
+/// The district letter named at the end of `words`: `Some('n')` for "N.D."
+/// or "Northern District of", `None` for "District of", "D." or nothing.
+fn district_before(words: &[&str]) -> Option<char> {
+    let mut words = words;
+    if let [rest @ .., "OF"] = words {
+        words = rest;
+    }
+    if let [rest @ .., "DISTRICT" | "DIST" | "D"] = words {
+        words = rest;
+    }
+    let last = *words.last()?;
+    DIRECTIONS.iter().find_map(|(word, letter, c)| {
+        let abbreviated = last.len() == 2 && last.starts_with(letter) && last.ends_with('D');
+        (last == *word || last == *letter || abbreviated).then_some(*c)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_spelling_of_a_court_is_one_id() {
+        let cases = [
+            ("Delaware", "deb"),
+            ("Bankr. D. Del.", "deb"),
+            ("United States Bankruptcy Court for the District of Delaware", "deb"),
+            ("United States Bankruptcy Court, D. Delaware", "deb"),
+            ("S.D. New York", "nysb"),
+            ("Bankr. S.D.N.Y.", "nysb"),
+            ("United States Bankruptcy Court for the Southern District of New York", "nysb"),
+            ("D. New Jersey", "njb"),
+            ("D.N.J.", "njb"),
+            ("N.D. Illinois", "ilnb"),
+            ("C.D. California", "cacb"),
+            ("Bankr. C.D. Cal.", "cacb"),
+            ("W.D. Va.", "vawb"),
+            ("N.D. W. Va.", "wvnb"),
+            ("Western District of Virginia", "vawb"),
+            ("D.N.D.", "ndb"),
+            ("N.D. Ind.", "innb"),
+            ("M.D. Tennessee", "tnmb"),
+            ("ND Texas", "txnb"),
+            ("deb", "deb"),
+            ("TXSB", "txsb"),
+        ];
+        for (court, id) in cases {
+            assert_eq!(court_id(court).as_deref(), Some(id), "{}", court);
+        }
+    }
+
+    #[test]
+    fn test_pacer_feed_labels_are_their_feeds_courts() {
+        let feeds = [
+            ("Delaware", "deb"),
+            ("S.D. New York", "nysb"),
+            ("D. New Jersey", "njb"),
+            ("N.D. Illinois", "ilnb"),
+            ("N.D. Texas", "txnb"),
+            ("S.D. Texas", "txsb"),
+            ("C.D. California", "cacb"),
+            ("N.D. Georgia", "ganb"),
+            ("E.D. Virginia", "vaeb"),
+            ("W.D. Missouri", "mowb"),
+            ("S.D. Indiana", "insb"),
+            ("M.D. Tennessee", "tnmb"),
+        ];
+        for (label, id) in feeds {
+            assert_eq!(court_id(label).as_deref(), Some(id), "{}", label);
+        }
+    }
+
+    #[test]
+    fn test_courts_that_do_not_exist_have_no_id() {
+        for court in [
+            "Texas",                     // four districts, none named
+            "N.D. Delaware",             // Delaware has one
+            "SEC EDGAR",
+            "FMCSA — Status: INACTIVE | INSURANCE LAPSED",
+            "",
+            "nyb",
+        ] {
+            assert_eq!(court_id(court), None, "{}", court);
+        }
+    }
+}
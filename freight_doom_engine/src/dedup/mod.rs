@@ -0,0 +1,609 @@
+// =============================================================================
+// dedup.rs — THE DEDUPLICATION FORTRESS
+// =============================================================================
+//
+// This module implements a hybrid Bloom Filter + LRU Cache deduplication
+// engine. Because seeing the same bankruptcy event twice would be like
+// getting dumped by the same person twice — once is bad enough.
+//
+// The architecture is intentionally overkill:
+//
+// 1. First, we check the Bloom filter (O(k) where k is the number of hash
+//    functions, which is basically O(1)). If the Bloom filter says "never
+//    seen it", we KNOW it's new. Bloom filters never have false negatives.
+//
+// 2. If the Bloom filter says "maybe seen it" (because Bloom filters DO
+//    have false positives), we check the LRU cache for a definitive answer.
+//
+// 3. There are two Bloom filters, a current and a previous generation, and
+//    they rotate to prevent saturation. A saturated Bloom filter says "yes"
+//    to everything, which is about as useful as a chocolate teapot. The
+//    old engine threw its only filter away every hour, after which every
+//    key — even ones sitting right there in the LRU — looked brand new.
+//    Now a generation ends after the longest TTL or once it has taken
+//    `bloom_expected_items` new keys, whichever comes first: a generation
+//    that lasts 90 days (the FMCSA TTL) sized for an hour's worth of keys
+//    would saturate long before it rotated. The fresh filter is seeded with
+//    every key the LRU still holds within its TTL — those are the only keys
+//    that can be rejected anyway — so rotation forgets nothing that matters.
+//
+// 4. Each key is suppressed for its source's TTL (ttl.rs), measured from
+//    when it was accepted: "don't tell me about this PACER filing again
+//    for 30 days". The LRU stores the timestamp; after the TTL, the next
+//    sighting is news again. See `DedupEngine` for exactly what can
+//    re-fire and when.
+//
+// 5. Everything is thread-safe with parking_lot RwLock, because we have
+//    multiple scanner threads all trying to deduplicate simultaneously,
+//    and data races are not a feature we're looking to implement.
+//
+// 6. The whole state — both Bloom generations, LRU keys, rotation time — is snapshotted
+//    to disk (snapshot.rs) and reloaded at startup. Otherwise every restart
+//    re-emits every filing still sitting in the RSS feeds, and Rails gets
+//    to meet the same bankruptcies all over again.
+//
+// 7. Running more than one engine? Put a `RedisDedup` (shared.rs) in front
+//    and the replicas race to SET NX each key in Redis. One wins and
+//    publishes; the others stand down. The local engine stays in front as
+//    a fast path so repeats never leave the building.
+//
+// Is this overkill for deduplicating maybe 100 events per day? YES.
+// Could we just use a HashSet? YES.
+// Are we going to use a HashSet? ABSOLUTELY NOT.
+// =============================================================================
+
+pub mod shared;
+pub mod snapshot;
+pub mod ttl;
+
+use bloomfilter::Bloom;
+use futures::future::BoxFuture;
+use lru::LruCache;
+use parking_lot::RwLock;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+pub use shared::RedisDedup;
+pub use snapshot::{BloomState, DedupState};
+pub use ttl::DedupTtls;
+
+/// Something that can say whether a dedup key is new.
+///
+/// `DedupEngine` answers for this process alone. `RedisDedup` answers for
+/// every engine sharing the same Redis, so that when two regions spot the
+/// same filing, only one of them publishes it.
+pub trait DedupBackend: Send + Sync {
+    /// Short name for logging.
+    fn name(&self) -> &str;
+
+    /// Claim `key`. Resolves to `true` if the caller is the first to see it
+    /// and should publish, `false` if it's a duplicate. Backends deal with
+    /// their own failures — an error here would just be a coin flip the
+    /// caller has to make, so the backend makes it.
+    fn claim<'a>(&'a self, key: &'a str) -> BoxFuture<'a, bool>;
+}
+
+/// The Deduplication Engine. A monument to over-engineering.
+///
+/// Thread-safe, probabilistic, self-rotating, and completely unnecessary
+/// for the volume of data we're processing. But boy, does it feel good.
+///
+/// # What can re-fire
+///
+/// A key that was accepted at time T is rejected as a duplicate until
+/// T + its source's TTL, as long as it's still in the LRU cache. It comes
+/// back through only if:
+///
+/// 1. its TTL has passed (it then gets a fresh TTL from the new sighting), or
+/// 2. more than `lru_capacity` other keys have been seen more recently and
+///    pushed it out of the cache.
+///
+/// Bloom rotation never lets a key re-fire: a fresh generation starts out
+/// holding every cached key that's still within its TTL.
+pub struct DedupEngine {
+    /// The Bloom filter pair — our first line of defense against duplicates.
+    /// Wrapped in an RwLock because we need to rotate it periodically,
+    /// and wrapped in an Arc because multiple threads need access.
+    blooms: Arc<RwLock<BloomPair>>,
+
+    /// The LRU cache — our second line of defense.
+    /// When the Bloom filter says "maybe", the LRU cache says "definitely."
+    /// Bounded in size so we don't eat all the RAM. Values are when each
+    /// key was last accepted, in Unix milliseconds, which is what the TTLs
+    /// are measured from.
+    lru_cache: Arc<RwLock<LruCache<String, i64>>>,
+
+    /// When the Bloom pair was last rotated.
+    /// We track this to know when it's time for a fresh generation.
+    last_rotation: Arc<RwLock<Instant>>,
+
+    /// How long each Bloom generation lasts at most: the configured
+    /// rotation interval, stretched to the longest TTL if that's longer. A
+    /// generation that fills up first rotates early.
+    generation: Duration,
+
+    /// How long each source's keys are suppressed.
+    ttls: DedupTtls,
+
+    /// Parameters for creating new Bloom filters on rotation. Each
+    /// generation takes `bloom_expected_items` new keys before it rotates,
+    /// on top of the cached keys it's seeded with.
+    bloom_expected_items: u64,
+    bloom_fp_rate: f64,
+
+    /// Counters for metrics. Because if we can't measure it,
+    /// did the deduplication even happen?
+    pub stats: Arc<DedupStats>,
+}
+
+/// Two Bloom generations. New keys go into `current`; a key is "maybe
+/// seen" if either generation has it. On rotation `current` becomes
+/// `previous` and the old `previous` is dropped.
+struct BloomPair {
+    current: Bloom<String>,
+    previous: Option<Bloom<String>>,
+    /// New keys set in `current` since it was installed, not counting the
+    /// ones it was seeded with.
+    inserted: u64,
+}
+
+impl BloomPair {
+    fn check(&self, key: &String) -> bool {
+        self.current.check(key) || self.previous.as_ref().is_some_and(|b| b.check(key))
+    }
+}
+
+/// Statistics about deduplication operations.
+/// All counters are atomic because we're allergic to mutexes.
+pub struct DedupStats {
+    /// How many items were checked against the dedup engine
+    pub checks: portable_atomic::AtomicU64,
+    /// How many items were identified as new (not duplicates)
+    pub unique: portable_atomic::AtomicU64,
+    /// How many items were identified as duplicates
+    pub duplicates: portable_atomic::AtomicU64,
+    /// How many times the Bloom pair was rotated
+    pub rotations: portable_atomic::AtomicU64,
+    /// How many times the Bloom filter said "maybe" and we had to
+    /// check the LRU cache (the "false positive rescue" counter)
+    pub bloom_maybe_hits: portable_atomic::AtomicU64,
+    /// How many keys were found in the cache with their TTL expired, and
+    /// let through again
+    pub ttl_expirations: portable_atomic::AtomicU64,
+    /// How many times the state was saved to disk
+    pub snapshots_saved: portable_atomic::AtomicU64,
+    /// How many keys were new here but already claimed by another engine
+    pub shared_duplicates: portable_atomic::AtomicU64,
+    /// How many times the shared backend couldn't be asked, so we
+    /// published anyway
+    pub shared_errors: portable_atomic::AtomicU64,
+}
+
+impl DedupStats {
+    fn new() -> Self {
+        Self {
+            checks: portable_atomic::AtomicU64::new(0),
+            unique: portable_atomic::AtomicU64::new(0),
+            duplicates: portable_atomic::AtomicU64::new(0),
+            rotations: portable_atomic::AtomicU64::new(0),
+            bloom_maybe_hits: portable_atomic::AtomicU64::new(0),
+            ttl_expirations: portable_atomic::AtomicU64::new(0),
+            snapshots_saved: portable_atomic::AtomicU64::new(0),
+            shared_duplicates: portable_atomic::AtomicU64::new(0),
+            shared_errors: portable_atomic::AtomicU64::new(0),
+        }
+    }
+}
+
+impl DedupEngine {
+    /// Create a new DedupEngine with the specified parameters.
+    ///
+    /// # Arguments
+    /// * `expected_items` - How many items we expect per Bloom generation
+    /// * `fp_rate` - Target false positive rate (0.01 = 1%)
+    /// * `lru_capacity` - Maximum items in the LRU cache
+    /// * `rotation_interval_secs` - Minimum seconds between Bloom rotations.
+    ///   Until `with_ttls` says otherwise, this is also every key's TTL.
+    ///
+    /// # Returns
+    /// A freshly minted DedupEngine, ready to crush duplicates with
+    /// extreme prejudice.
+    pub fn new(
+        expected_items: u64,
+        fp_rate: f64,
+        lru_capacity: usize,
+        rotation_interval_secs: u64,
+    ) -> Self {
+        info!(
+            expected_items = expected_items,
+            fp_rate = fp_rate,
+            lru_capacity = lru_capacity,
+            rotation_secs = rotation_interval_secs,
+            "Initializing Deduplication Engine — duplicates will be ELIMINATED"
+        );
+
+        let lru_size = NonZeroUsize::new(lru_capacity).unwrap_or(NonZeroUsize::new(1000).unwrap());
+        let bloom = generation_bloom(expected_items, lru_size.get(), fp_rate);
+        let lru_cache = LruCache::new(lru_size);
+        let rotation_interval = Duration::from_secs(rotation_interval_secs);
+
+        Self {
+            blooms: Arc::new(RwLock::new(BloomPair { current: bloom, previous: None, inserted: 0 })),
+            lru_cache: Arc::new(RwLock::new(lru_cache)),
+            last_rotation: Arc::new(RwLock::new(Instant::now())),
+            generation: rotation_interval,
+            ttls: DedupTtls::uniform(rotation_interval),
+            bloom_expected_items: expected_items,
+            bloom_fp_rate: fp_rate,
+            stats: Arc::new(DedupStats::new()),
+        }
+    }
+
+    /// Suppress keys for per-source TTLs. Bloom generations may last as
+    /// long as the longest TTL, so the minimum rotation interval given to
+    /// `new` only matters if it's longer still.
+    pub fn with_ttls(mut self, ttls: DedupTtls) -> Self {
+        self.generation = self.generation.max(ttls.longest());
+        info!(
+            default_ttl_secs = ttls.default.as_secs(),
+            generation_secs = self.generation.as_secs(),
+            "Dedup TTLs set"
+        );
+        self.ttls = ttls;
+        self
+    }
+
+    /// The TTLs this engine applies.
+    pub fn ttls(&self) -> &DedupTtls {
+        &self.ttls
+    }
+
+    /// Check if an item has been seen before, and if not, mark it as seen.
+    ///
+    /// Returns `true` if the item is NEW (not a duplicate, or its TTL ran out).
+    /// Returns `false` if the item has been seen within its TTL (duplicate).
+    ///
+    /// The logic flow:
+    /// 1. Check if Bloom rotation is needed (by age or by fill)
+    /// 2. Check the Bloom pair for a fast "definitely new" answer
+    /// 3. If Bloom says "maybe seen", check the LRU cache and the key's TTL
+    /// 4. If new (or expired), add to the current Bloom and the LRU cache
+    ///
+    /// This entire operation is thread-safe, which is good because
+    /// we have scanners racing each other to report bankruptcies.
+    pub fn check_and_insert(&self, key: &str) -> bool {
+        self.check_and_insert_at(key, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// `check_and_insert`, with the wall clock supplied by the caller.
+    fn check_and_insert_at(&self, key: &str, now_ms: i64) -> bool {
+        use portable_atomic::Ordering;
+
+        self.stats.checks.fetch_add(1, Ordering::Relaxed);
+
+        // Step 0: Maybe rotate the bloom pair if the current generation is done
+        self.maybe_rotate(now_ms);
+
+        let key_owned = key.to_string();
+
+        // Step 1: Check the Bloom pair
+        // Read lock only — multiple threads can check simultaneously
+        let bloom_says_maybe_seen = self.blooms.read().check(&key_owned);
+
+        if bloom_says_maybe_seen {
+            // The Bloom filter thinks it's seen this before.
+            // But Bloom filters lie (false positives). Let's check the LRU.
+            self.stats.bloom_maybe_hits.fetch_add(1, Ordering::Relaxed);
+
+            let mut lru = self.lru_cache.write();
+            match lru.get(&key_owned) {
+                Some(&accepted_at) if now_ms - accepted_at < self.ttls.ttl_ms(key) => {
+                    // LRU confirms: this is a genuine duplicate, within its TTL.
+                    // Move along, nothing to see here.
+                    self.stats.duplicates.fetch_add(1, Ordering::Relaxed);
+                    debug!(key = key, "Duplicate detected — Bloom + LRU confirmed");
+                    return false;
+                }
+                Some(_) => {
+                    // Seen before, but long enough ago that it counts as news.
+                    self.stats.ttl_expirations.fetch_add(1, Ordering::Relaxed);
+                    debug!(key = key, "Dedup TTL expired — letting the key through again");
+                }
+                None => {
+                    // Bloom said "maybe" but LRU said "nope".
+                    // This was a Bloom filter false positive (or a key the
+                    // LRU has since evicted). Add it and let it through.
+                    debug!(
+                        key = key,
+                        "Bloom false positive rescued by LRU — event is actually new"
+                    );
+                }
+            }
+        }
+
+        // Step 2: This is a new (or newly expired) item. Add it everywhere.
+        {
+            let mut blooms = self.blooms.write();
+            blooms.current.set(&key_owned);
+            blooms.inserted += 1;
+        }
+        self.lru_cache.write().put(key_owned, now_ms);
+
+        self.stats.unique.fetch_add(1, Ordering::Relaxed);
+        debug!(key = key, "New unique item accepted into the dedup engine");
+        true
+    }
+
+    /// Check if the current Bloom generation is done — old enough, or
+    /// holding as many keys as it was sized for — and rotate if so.
+    ///
+    /// Rotation retires the current filter to `previous` (dropping the one
+    /// that was there) and starts a fresh one, seeded with every LRU key
+    /// still within its TTL at `now_ms`. This keeps the filters from
+    /// saturating (where they start saying "yes" to everything) without
+    /// forgetting any key the LRU would still reject.
+    ///
+    /// The LRU cache is NOT rotated — it self-evicts old entries naturally.
+    fn maybe_rotate(&self, now_ms: i64) {
+        let due = |blooms: &BloomPair, last: &Instant| {
+            last.elapsed() >= self.generation || blooms.inserted >= self.bloom_expected_items
+        };
+        if !due(&self.blooms.read(), &self.last_rotation.read()) {
+            return;
+        }
+
+        let mut blooms = self.blooms.write();
+        let mut last = self.last_rotation.write();
+
+        // Double-check after acquiring write lock (another thread might
+        // have rotated while we were waiting for the lock)
+        if !due(&blooms, &last) {
+            return;
+        }
+
+        let lru = self.lru_cache.read();
+        let mut fresh = generation_bloom(self.bloom_expected_items, lru.cap().get(), self.bloom_fp_rate);
+        let mut carried = 0;
+        for (key, &accepted_at) in lru.iter() {
+            if now_ms - accepted_at < self.ttls.ttl_ms(key) {
+                fresh.set(key);
+                carried += 1;
+            }
+        }
+        drop(lru);
+
+        let full = blooms.inserted >= self.bloom_expected_items;
+        let retired = std::mem::replace(&mut blooms.current, fresh);
+        blooms.previous = Some(retired);
+        blooms.inserted = 0;
+        *last = Instant::now();
+
+        self.stats.rotations.fetch_add(1, portable_atomic::Ordering::Relaxed);
+        info!(
+            generation_secs = self.generation.as_secs(),
+            full = full,
+            carried = carried,
+            "Bloom generation rotated — fresh filter installed with the keys still within their TTL"
+        );
+    }
+
+    /// Copy out everything needed to rebuild this engine after a restart.
+    /// LRU entries come out least recently used first.
+    pub fn export_state(&self) -> DedupState {
+        // Hold the Bloom lock while reading the rotation time, so the two
+        // can't straddle a rotation.
+        let blooms = self.blooms.read();
+        let rotated_ago = self.last_rotation.read().elapsed();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+
+        let current = BloomState::of(&blooms.current);
+        let previous = blooms.previous.as_ref().map(BloomState::of);
+        drop(blooms);
+
+        let entries = self
+            .lru_cache
+            .read()
+            .iter()
+            .rev()
+            .map(|(key, accepted_at)| (key.clone(), *accepted_at))
+            .collect();
+
+        DedupState {
+            rotated_at_ms: now_ms - rotated_ago.as_millis() as i64,
+            current,
+            previous,
+            entries,
+        }
+    }
+
+    /// Replace this engine's Bloom pair, LRU contents and rotation clock
+    /// with a previously exported state. If the LRU is smaller than it used
+    /// to be, the most recently used keys win.
+    pub fn restore_state(&self, state: DedupState) {
+        let mut blooms = self.blooms.write();
+        let mut last = self.last_rotation.write();
+        let mut lru = self.lru_cache.write();
+
+        blooms.current = state.current.into_bloom();
+        blooms.previous = state.previous.map(BloomState::into_bloom);
+        // The snapshot doesn't say how full the current generation is; the
+        // cached keys set into it below are a lower bound.
+        blooms.inserted = state.entries.len() as u64;
+
+        // Instants can't be persisted, so translate "rotated at 14:02" back
+        // into "rotated N seconds ago" on this boot's monotonic clock.
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let ago = Duration::from_millis(now_ms.saturating_sub(state.rotated_at_ms).max(0) as u64);
+        *last = Instant::now().checked_sub(ago).unwrap_or_else(Instant::now);
+
+        // Every cached key goes back into the current generation too. Older
+        // snapshots (and the rotation-amnesia engine that wrote them) could
+        // have keys in the LRU that no Bloom filter remembers, and a key the
+        // Bloom pair doesn't know is waved through without asking the LRU.
+        lru.clear();
+        for (key, accepted_at) in state.entries {
+            blooms.current.set(&key);
+            lru.put(key, accepted_at);
+        }
+    }
+
+    /// Get a snapshot of the current dedup statistics.
+    /// Useful for the metrics endpoint.
+    pub fn snapshot(&self) -> DedupSnapshot {
+        use portable_atomic::Ordering;
+        DedupSnapshot {
+            total_checks: self.stats.checks.load(Ordering::Relaxed),
+            unique_items: self.stats.unique.load(Ordering::Relaxed),
+            duplicates_caught: self.stats.duplicates.load(Ordering::Relaxed),
+            bloom_rotations: self.stats.rotations.load(Ordering::Relaxed),
+            bloom_false_positive_rescues: self.stats.bloom_maybe_hits.load(Ordering::Relaxed),
+            ttl_expirations: self.stats.ttl_expirations.load(Ordering::Relaxed),
+            snapshots_saved: self.stats.snapshots_saved.load(Ordering::Relaxed),
+            shared_duplicates: self.stats.shared_duplicates.load(Ordering::Relaxed),
+            shared_errors: self.stats.shared_errors.load(Ordering::Relaxed),
+            lru_cache_size: self.lru_cache.read().len(),
+        }
+    }
+}
+
+/// A Bloom filter for one generation: room for `expected_items` new keys
+/// plus the up to `lru_capacity` cached keys it's seeded with on rotation.
+fn generation_bloom(expected_items: u64, lru_capacity: usize, fp_rate: f64) -> Bloom<String> {
+    Bloom::new_for_fp_rate(expected_items as usize + lru_capacity, fp_rate)
+}
+
+/// A snapshot of dedup engine statistics at a point in time.
+/// Serializable for the metrics endpoint.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DedupSnapshot {
+    pub total_checks: u64,
+    pub unique_items: u64,
+    pub duplicates_caught: u64,
+    pub bloom_rotations: u64,
+    pub bloom_false_positive_rescues: u64,
+    pub ttl_expirations: u64,
+    pub snapshots_saved: u64,
+    pub shared_duplicates: u64,
+    pub shared_errors: u64,
+    pub lru_cache_size: usize,
+}
+
+impl DedupBackend for DedupEngine {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn claim<'a>(&'a self, key: &'a str) -> BoxFuture<'a, bool> {
+        Box::pin(async move { self.check_and_insert(key) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_items_are_accepted() {
+        let engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        assert!(engine.check_and_insert("bankruptcy:acme_freight:chapter_11"));
+    }
+
+    #[test]
+    fn test_duplicate_items_are_rejected() {
+        let engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        assert!(engine.check_and_insert("bankruptcy:acme_freight:chapter_11"));
+        assert!(!engine.check_and_insert("bankruptcy:acme_freight:chapter_11"));
+    }
+
+    #[test]
+    fn test_different_items_are_accepted() {
+        let engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        assert!(engine.check_and_insert("bankruptcy:acme_freight:chapter_11"));
+        assert!(engine.check_and_insert("bankruptcy:big_truck_co:chapter_7"));
+    }
+
+    #[test]
+    fn test_rotation_does_not_forget_keys_within_ttl() {
+        // Force zero-length generations so every check rotates. The old
+        // engine threw its only filter away here and waved the repeat
+        // through; now it's found in the retired generation.
+        let mut engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        engine.generation = Duration::ZERO;
+        assert!(engine.check_and_insert_at("pacer:acme_freight:24-10001", 0));
+        assert!(!engine.check_and_insert_at("pacer:acme_freight:24-10001", 1_000));
+        assert_eq!(engine.snapshot().bloom_rotations, 2);
+    }
+
+    #[test]
+    fn test_full_generations_rotate_before_their_time() {
+        // Ten keys per generation, but a 90 day TTL: without rotating on
+        // fill, this generation would take every key for three months.
+        let ttls = DedupTtls::uniform(Duration::from_secs(90 * 86_400));
+        let engine = DedupEngine::new(10, 0.01, 100, 3600).with_ttls(ttls);
+
+        let keys: Vec<String> = (0..35).map(|i| format!("fmcsa:{}:REVOKED", i)).collect();
+        for key in &keys {
+            assert!(engine.check_and_insert_at(key, 0));
+        }
+        assert_eq!(engine.snapshot().bloom_rotations, 3);
+        assert!(engine.blooms.read().inserted <= 10);
+
+        // Every key is still within its TTL, and none of them re-fires.
+        for key in &keys {
+            assert!(!engine.check_and_insert_at(key, 1_000), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_rotation_drops_keys_past_their_ttl() {
+        let mut engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        assert!(engine.check_and_insert_at("pacer:acme_freight:24-10001", 0));
+        engine.generation = Duration::ZERO;
+        engine.maybe_rotate(3_600_000);
+        engine.maybe_rotate(3_600_000);
+        assert!(!engine.blooms.read().check(&"pacer:acme_freight:24-10001".to_string()));
+    }
+
+    #[test]
+    fn test_keys_refire_after_their_source_ttl() {
+        let ttls = DedupTtls::uniform(Duration::from_secs(3600))
+            .with_source("fmcsa", Duration::from_secs(60));
+        let engine = DedupEngine::new(1000, 0.01, 100, 3600).with_ttls(ttls);
+
+        assert!(engine.check_and_insert_at("fmcsa:123:REVOKED", 0));
+        assert!(engine.check_and_insert_at("pacer:deb:1", 0));
+        assert!(!engine.check_and_insert_at("fmcsa:123:REVOKED", 59_000));
+        assert!(engine.check_and_insert_at("fmcsa:123:REVOKED", 60_000));
+        assert!(!engine.check_and_insert_at("pacer:deb:1", 60_000));
+        assert_eq!(engine.snapshot().ttl_expirations, 1);
+
+        // The re-fire starts a fresh TTL.
+        assert!(!engine.check_and_insert_at("fmcsa:123:REVOKED", 61_000));
+    }
+
+    #[test]
+    fn test_restored_engine_remembers_old_keys() {
+        let engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        assert!(engine.check_and_insert("pacer:acme_freight:24-10001"));
+
+        let restored = DedupEngine::new(1000, 0.01, 100, 3600);
+        restored.restore_state(engine.export_state());
+        assert!(!restored.check_and_insert("pacer:acme_freight:24-10001"));
+        assert!(restored.check_and_insert("pacer:big_truck_co:24-10002"));
+    }
+
+    #[test]
+    fn test_restore_into_smaller_cache_keeps_most_recent() {
+        let engine = DedupEngine::new(1000, 0.01, 100, 3600);
+        for key in ["a", "b", "c"] {
+            engine.check_and_insert(key);
+        }
+
+        let restored = DedupEngine::new(1000, 0.01, 2, 3600);
+        restored.restore_state(engine.export_state());
+        let keys: Vec<String> = restored.export_state().entries.into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["b", "c"]);
+    }
+}
@@ -0,0 +1,251 @@
+// =============================================================================
+// dedup/shared.rs — THE RADIO BETWEEN REGIONS
+// =============================================================================
+//
+// We run two engines for redundancy. Both poll the same feeds, both find
+// the same bankruptcies, and before this file existed both published them,
+// so Rails heard about every freight company's demise twice. Redundancy is
+// great for uptime and terrible for a notifications channel.
+//
+// `RedisDedup` makes the replicas cooperate:
+//
+// 1. The local `DedupEngine` answers first. If this process has already
+//    seen the key, it's a duplicate and Redis never hears about it.
+// 2. Otherwise we `SET <prefix><key> <instance> NX EX <ttl>`, with the same
+//    per-source TTL the local engine uses. Whoever's SET lands first owns
+//    the filing and publishes it. Everyone else gets nil back and stands
+//    down.
+// 3. If Redis can't be reached, we publish anyway and count it. Two copies
+//    of a bankruptcy is an annoyance; zero copies is the thing this whole
+//    engine exists to prevent. A Redis that accepts the SET and never
+//    answers counts as unreachable too, after a short timeout. After a
+//    failure we leave Redis alone for a few seconds instead of paying a
+//    timeout on every candidate.
+//
+// The value stored is the claiming instance's id, which makes "who
+// published this?" a single GET away.
+// =============================================================================
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use portable_atomic::Ordering;
+use redis::aio::MultiplexedConnection;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use super::{DedupBackend, DedupEngine};
+use crate::config::Config;
+
+/// Prefix for claimed keys, so dedup doesn't collide with anything else
+/// living in the same Redis.
+pub const KEY_PREFIX: &str = "freight_doom:dedup:";
+
+/// How long to skip Redis after it fails, and how long to wait for it.
+const BACKOFF: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connection state, behind one lock so only one task reconnects at a time.
+#[derive(Default)]
+struct Link {
+    connection: Option<MultiplexedConnection>,
+    /// Don't try Redis again before this instant.
+    retry_at: Option<Instant>,
+}
+
+/// Dedup shared between engines through Redis `SET NX EX`, with the local
+/// engine as a fast path.
+pub struct RedisDedup {
+    local: Arc<DedupEngine>,
+    client: redis::Client,
+    link: Mutex<Link>,
+    instance_id: String,
+    /// How long a SET NX may take before Redis counts as down.
+    command_timeout: Duration,
+}
+
+impl RedisDedup {
+    /// Connects lazily, on the first key the local engine hasn't seen.
+    /// Claimed keys expire with `local`'s per-source TTLs.
+    pub fn new(local: Arc<DedupEngine>, redis_url: &str) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .with_context(|| format!("invalid Redis URL for shared dedup: {}", redis_url))?;
+        let instance_id = uuid::Uuid::new_v4().to_string();
+        info!(instance = instance_id.as_str(), "Shared Redis dedup enabled");
+        Ok(Self {
+            local,
+            client,
+            link: Mutex::new(Link::default()),
+            instance_id,
+            command_timeout: COMMAND_TIMEOUT,
+        })
+    }
+
+    pub fn from_config(local: Arc<DedupEngine>, config: &Config) -> Result<Self> {
+        Self::new(local, &config.redis_url)
+    }
+
+    /// The id this engine writes into the keys it claims.
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// A connection to use, or `None` while backing off after a failure.
+    async fn connection(&self) -> Option<MultiplexedConnection> {
+        let mut link = self.link.lock().await;
+        if let Some(con) = &link.connection {
+            return Some(con.clone());
+        }
+        if link.retry_at.is_some_and(|at| Instant::now() < at) {
+            return None;
+        }
+
+        let connected =
+            tokio::time::timeout(CONNECT_TIMEOUT, self.client.get_multiplexed_async_connection()).await;
+        match connected {
+            Ok(Ok(con)) => {
+                info!("Shared dedup connected to Redis");
+                link.retry_at = None;
+                link.connection = Some(con.clone());
+                Some(con)
+            }
+            Ok(Err(e)) => {
+                warn!(error = %e, "Shared dedup can't reach Redis — deduplicating locally for now");
+                link.retry_at = Some(Instant::now() + BACKOFF);
+                None
+            }
+            Err(_) => {
+                warn!("Shared dedup timed out connecting to Redis — deduplicating locally for now");
+                link.retry_at = Some(Instant::now() + BACKOFF);
+                None
+            }
+        }
+    }
+
+    /// Forget a connection that just failed us.
+    async fn disconnect(&self) {
+        let mut link = self.link.lock().await;
+        link.connection = None;
+        link.retry_at = Some(Instant::now() + BACKOFF);
+    }
+
+    /// SET NX EX. `Ok(true)` if we claimed the key, `Ok(false)` if someone
+    /// else already had. No answer within `command_timeout` is an error.
+    async fn claim_remote(&self, con: &mut MultiplexedConnection, key: &str) -> redis::RedisResult<bool> {
+        let mut set = redis::cmd("SET");
+        set.arg(format!("{}{}", KEY_PREFIX, key))
+            .arg(&self.instance_id)
+            .arg("NX")
+            .arg("EX")
+            .arg(self.local.ttls().ttl_for(key).as_secs().max(1));
+        let reply: Option<String> = tokio::time::timeout(self.command_timeout, set.query_async(con))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "no reply to SET NX"))??;
+        Ok(reply.is_some())
+    }
+
+    fn publish_anyway(&self, key: &str) -> bool {
+        self.local.stats.shared_errors.fetch_add(1, Ordering::Relaxed);
+        debug!(key = key, "Shared dedup unavailable — publishing on the local verdict");
+        true
+    }
+}
+
+impl DedupBackend for RedisDedup {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    fn claim<'a>(&'a self, key: &'a str) -> BoxFuture<'a, bool> {
+        Box::pin(async move {
+            if !self.local.check_and_insert(key) {
+                return false;
+            }
+
+            let Some(mut con) = self.connection().await else {
+                return self.publish_anyway(key);
+            };
+
+            match self.claim_remote(&mut con, key).await {
+                Ok(true) => true,
+                Ok(false) => {
+                    self.local.stats.shared_duplicates.fetch_add(1, Ordering::Relaxed);
+                    debug!(key = key, "Another engine already claimed this one — standing down");
+                    false
+                }
+                Err(e) => {
+                    warn!(error = %e, "Shared dedup SET NX failed — publishing on the local verdict");
+                    self.disconnect().await;
+                    self.publish_anyway(key)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable() -> (RedisDedup, Arc<DedupEngine>) {
+        let local = Arc::new(DedupEngine::new(1000, 0.01, 100, 3600));
+        // Port 1 is reliably closed, so connecting fails fast.
+        let shared = RedisDedup::new(local.clone(), "redis://127.0.0.1:1").unwrap();
+        (shared, local)
+    }
+
+    #[tokio::test]
+    async fn test_redis_down_falls_back_to_local_verdict() {
+        let (shared, local) = unreachable();
+        assert!(shared.claim("pacer:acme_freight:24-10001").await);
+        assert!(!shared.claim("pacer:acme_freight:24-10001").await);
+
+        let snapshot = local.snapshot();
+        assert_eq!(snapshot.shared_errors, 1);
+        assert_eq!(snapshot.duplicates_caught, 1);
+    }
+
+    #[tokio::test]
+    async fn test_backs_off_after_connect_failure() {
+        let (shared, _) = unreachable();
+        assert!(shared.connection().await.is_none());
+        assert!(shared.link.lock().await.retry_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_silent_redis_counts_as_down() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers the connection handshake (CLIENT SETINFO, twice), then
+        // reads everything and says nothing.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(b"+OK\r\n+OK\r\n").await;
+            while socket.read(&mut buf).await.is_ok_and(|n| n > 0) {}
+        });
+
+        let local = Arc::new(DedupEngine::new(1000, 0.01, 100, 3600));
+        let mut shared = RedisDedup::new(local.clone(), &format!("redis://{}", addr)).unwrap();
+        shared.command_timeout = Duration::from_millis(100);
+
+        let claimed = tokio::time::timeout(Duration::from_secs(5), shared.claim("pacer:case:del:24-10001"))
+            .await
+            .expect("claim waited on Redis forever");
+        assert!(claimed);
+        assert_eq!(local.snapshot().shared_errors, 1);
+        assert!(shared.link.lock().await.connection.is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_url() {
+        let local = Arc::new(DedupEngine::new(1000, 0.01, 100, 3600));
+        assert!(RedisDedup::new(local, "not a url").is_err());
+    }
+}
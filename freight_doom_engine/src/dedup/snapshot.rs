@@ -0,0 +1,440 @@
+// =============================================================================
+// dedup/snapshot.rs — THE DEDUP ENGINE'S DIARY
+// =============================================================================
+//
+// A Bloom filter that forgets everything on restart isn't a dedup engine,
+// it's a goldfish. PACER RSS feeds, CourtListener searches and EDGAR daily
+// indexes all keep serving the same filings for days, so every deploy used
+// to re-announce every one of them to Rails.
+//
+// So the engine's state goes to disk: every `dedup_snapshot_interval`, and
+// once more at shutdown after the scanners have stopped. At startup it's
+// read back before the first scanner polls.
+//
+// File layout, all integers little-endian:
+//
+//   magic          8 bytes   "FDDEDUP\0"
+//   version        u32       FORMAT_VERSION
+//   crc32          u32       of everything after the header
+//   body length    u64
+//   -- body --
+//   rotated_at     i64       Unix ms of the last Bloom rotation
+//   current        Bloom     the generation new keys go into
+//   has previous   u8        0 or 1
+//   previous       Bloom     the retired generation, if has previous is 1
+//   entries        u64 count, then per entry (least recently used first):
+//                  i64 accepted-at Unix ms, u32 key length, key bytes
+//
+// where each Bloom is:
+//
+//   bits           u64       filter size in bits
+//   k              u32       hash functions
+//   sip keys       4 × u64
+//   bitmap         u64 length + bytes
+//
+// Version 1 files (one Bloom filter, no "has previous") are still read:
+// their filter becomes the current generation.
+//
+// Writes go to a temp file that's fsynced and renamed over the old one, so
+// a crash mid-save leaves the previous snapshot intact. A file with the
+// wrong magic, an unknown version or a bad checksum is refused — starting
+// empty beats trusting a Bloom filter full of garbage bits, which would
+// happily call every new bankruptcy a duplicate.
+// =============================================================================
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, ensure, Context, Result};
+use bloomfilter::Bloom;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use super::DedupEngine;
+
+const MAGIC: &[u8; 8] = b"FDDEDUP\0";
+
+/// Bumped whenever the body layout changes. Version 1 is still readable;
+/// anything else that isn't this is refused.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Magic + version + checksum + body length.
+const HEADER_LEN: usize = 8 + 4 + 4 + 8;
+
+/// Everything a `DedupEngine` needs to pick up where it left off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DedupState {
+    /// When the Bloom pair was last rotated, in Unix milliseconds.
+    pub rotated_at_ms: i64,
+    pub current: BloomState,
+    pub previous: Option<BloomState>,
+    /// LRU keys with the time they were accepted, least recently used first.
+    pub entries: Vec<(String, i64)>,
+}
+
+/// The raw state of a Bloom filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomState {
+    pub bitmap: Vec<u8>,
+    pub bits: u64,
+    pub hash_functions: u32,
+    pub sip_keys: [(u64, u64); 2],
+}
+
+impl BloomState {
+    pub fn of(bloom: &Bloom<String>) -> Self {
+        Self {
+            bitmap: bloom.bitmap(),
+            bits: bloom.number_of_bits(),
+            hash_functions: bloom.number_of_hash_functions(),
+            sip_keys: bloom.sip_keys(),
+        }
+    }
+
+    pub fn into_bloom(self) -> Bloom<String> {
+        Bloom::from_existing(&self.bitmap, self.bits, self.hash_functions, self.sip_keys)
+    }
+
+    fn encode(&self, body: &mut Vec<u8>) {
+        body.extend_from_slice(&self.bits.to_le_bytes());
+        body.extend_from_slice(&self.hash_functions.to_le_bytes());
+        for (k0, k1) in self.sip_keys {
+            body.extend_from_slice(&k0.to_le_bytes());
+            body.extend_from_slice(&k1.to_le_bytes());
+        }
+        body.extend_from_slice(&(self.bitmap.len() as u64).to_le_bytes());
+        body.extend_from_slice(&self.bitmap);
+    }
+
+    fn decode(r: &mut Reader<'_>) -> Result<Self> {
+        let bits = r.u64()?;
+        let hash_functions = r.u32()?;
+        let sip_keys = [(r.u64()?, r.u64()?), (r.u64()?, r.u64()?)];
+        let bitmap_len = r.u64()? as usize;
+        let bitmap = r.take(bitmap_len)?.to_vec();
+        ensure!(
+            bits > 0 && hash_functions > 0 && bits <= bitmap.len() as u64 * 8,
+            "dedup snapshot has an impossible Bloom filter ({} bits, k = {}, {} bytes)",
+            bits,
+            hash_functions,
+            bitmap.len()
+        );
+        Ok(Self { bitmap, bits, hash_functions, sip_keys })
+    }
+}
+
+/// Serialize a state into the snapshot file format.
+pub fn encode(state: &DedupState) -> Vec<u8> {
+    let bloom_bytes = state.current.bitmap.len() + state.previous.as_ref().map_or(0, |b| b.bitmap.len());
+    let mut body = Vec::with_capacity(128 + bloom_bytes + state.entries.len() * 48);
+    body.extend_from_slice(&state.rotated_at_ms.to_le_bytes());
+    state.current.encode(&mut body);
+    match &state.previous {
+        Some(previous) => {
+            body.push(1);
+            previous.encode(&mut body);
+        }
+        None => body.push(0),
+    }
+    body.extend_from_slice(&(state.entries.len() as u64).to_le_bytes());
+    for (key, seen_at) in &state.entries {
+        body.extend_from_slice(&seen_at.to_le_bytes());
+        body.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        body.extend_from_slice(key.as_bytes());
+    }
+
+    let mut out = Vec::with_capacity(HEADER_LEN + body.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+    out.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    out.extend_from_slice(&body);
+    out
+}
+
+/// Parse a snapshot file, refusing anything that isn't exactly what
+/// `encode` wrote.
+pub fn decode(bytes: &[u8]) -> Result<DedupState> {
+    ensure!(bytes.len() >= HEADER_LEN, "dedup snapshot is truncated ({} bytes)", bytes.len());
+    ensure!(&bytes[..8] == MAGIC, "not a dedup snapshot (bad magic)");
+
+    let mut header = Reader { bytes: &bytes[8..HEADER_LEN] };
+    let version = header.u32()?;
+    ensure!(
+        version == FORMAT_VERSION || version == 1,
+        "dedup snapshot format version {} is not supported (expected {})",
+        version,
+        FORMAT_VERSION
+    );
+    let crc = header.u32()?;
+    let len = header.u64()?;
+
+    let body = &bytes[HEADER_LEN..];
+    ensure!(
+        body.len() as u64 == len,
+        "dedup snapshot body is {} bytes, header says {}",
+        body.len(),
+        len
+    );
+    ensure!(crc32fast::hash(body) == crc, "dedup snapshot checksum mismatch");
+
+    let mut r = Reader { bytes: body };
+    let rotated_at_ms = r.i64()?;
+    let current = BloomState::decode(&mut r)?;
+    let previous = match version {
+        1 => None,
+        _ => match r.take(1)?[0] {
+            0 => None,
+            1 => Some(BloomState::decode(&mut r)?),
+            flag => bail!("dedup snapshot has a bad previous-generation flag {}", flag),
+        },
+    };
+
+    let count = r.u64()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let seen_at = r.i64()?;
+        let key_len = r.u32()? as usize;
+        let key = std::str::from_utf8(r.take(key_len)?)
+            .context("dedup snapshot key is not UTF-8")?
+            .to_string();
+        entries.push((key, seen_at));
+    }
+    ensure!(r.bytes.is_empty(), "dedup snapshot has {} trailing bytes", r.bytes.len());
+
+    Ok(DedupState {
+        rotated_at_ms,
+        current,
+        previous,
+        entries,
+    })
+}
+
+/// A cursor over a byte slice that fails instead of panicking.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < n {
+            bail!("dedup snapshot ends early");
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+}
+
+/// Write the engine's state to `path`, atomically.
+pub fn save(dedup: &DedupEngine, path: &Path) -> Result<()> {
+    let bytes = encode(&dedup.export_state());
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("creating dedup snapshot directory {}", dir.display()))?;
+    }
+    let tmp = tmp_path(path);
+    let mut file = File::create(&tmp)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+
+    dedup
+        .stats
+        .snapshots_saved
+        .fetch_add(1, portable_atomic::Ordering::Relaxed);
+    Ok(())
+}
+
+/// Load a snapshot from `path` into the engine. Returns `Ok(false)` if
+/// there is no snapshot yet.
+pub fn load(dedup: &DedupEngine, path: &Path) -> Result<bool> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let state = decode(&bytes).with_context(|| format!("loading {}", path.display()))?;
+    let entries = state.entries.len();
+    dedup.restore_state(state);
+    info!(path = %path.display(), entries, "Dedup state restored from snapshot");
+    Ok(true)
+}
+
+/// Load a snapshot at startup. A corrupt one is moved aside to
+/// `<path>.corrupt` for a human to look at, and the engine starts empty.
+pub fn restore_or_start_fresh(dedup: &DedupEngine, path: &Path) {
+    match load(dedup, path) {
+        Ok(true) => {}
+        Ok(false) => info!(path = %path.display(), "No dedup snapshot yet — starting empty"),
+        Err(e) => {
+            let aside = PathBuf::from(format!("{}.corrupt", path.display()));
+            error!(
+                error = format!("{:#}", e),
+                moved_to = %aside.display(),
+                "Dedup snapshot unreadable — starting empty, expect some repeats"
+            );
+            if let Err(e) = fs::rename(path, &aside) {
+                warn!(error = %e, "Could not move the bad dedup snapshot aside");
+            }
+        }
+    }
+}
+
+/// Save the engine's state every `interval` until `shutdown` flips. The
+/// final save at shutdown is the pipeline's job, once the scanners are done.
+pub async fn run_snapshotter(
+    dedup: Arc<DedupEngine>,
+    path: PathBuf,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+    ticker.tick().await;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(e) = save(&dedup, &path) {
+                    warn!(error = format!("{:#}", e), "Failed to save dedup snapshot");
+                }
+            }
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.tmp", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("freight-doom-dedup-{}", uuid::Uuid::new_v4()))
+            .join("dedup.snapshot")
+    }
+
+    fn engine() -> DedupEngine {
+        DedupEngine::new(1000, 0.01, 100, 3600)
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let dedup = engine();
+        dedup.check_and_insert("edgar:0001234567:chapter_11");
+        dedup.check_and_insert("fmcsa:usdot_123456");
+        let state = dedup.export_state();
+        assert_eq!(decode(&encode(&state)).unwrap(), state);
+    }
+
+    #[test]
+    fn test_round_trip_with_previous_generation() {
+        let mut state = engine().export_state();
+        state.previous = Some(state.current.clone());
+        assert_eq!(decode(&encode(&state)).unwrap(), state);
+    }
+
+    #[test]
+    fn test_reads_version_1_snapshots() {
+        let dedup = engine();
+        dedup.check_and_insert("pacer:deb:1");
+        let state = dedup.export_state();
+
+        // Version 1: the same layout minus the previous-generation flag.
+        let mut body = state.rotated_at_ms.to_le_bytes().to_vec();
+        state.current.encode(&mut body);
+        body.extend_from_slice(&(state.entries.len() as u64).to_le_bytes());
+        for (key, at) in &state.entries {
+            body.extend_from_slice(&at.to_le_bytes());
+            body.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            body.extend_from_slice(key.as_bytes());
+        }
+        let mut v1 = MAGIC.to_vec();
+        v1.extend_from_slice(&1u32.to_le_bytes());
+        v1.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+        v1.extend_from_slice(&(body.len() as u64).to_le_bytes());
+        v1.extend_from_slice(&body);
+
+        let decoded = decode(&v1).unwrap();
+        assert_eq!(decoded.current, state.current);
+        assert_eq!(decoded.previous, None);
+        assert_eq!(decoded.entries, state.entries);
+    }
+
+    #[test]
+    fn test_save_and_load_survives_restart() {
+        let path = temp_path();
+        let before = engine();
+        before.check_and_insert("courtlistener:acme_freight:24-10001");
+        save(&before, &path).unwrap();
+        assert_eq!(before.snapshot().snapshots_saved, 1);
+
+        let after = engine();
+        assert!(load(&after, &path).unwrap());
+        assert!(!after.check_and_insert("courtlistener:acme_freight:24-10001"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_missing_snapshot_is_not_an_error() {
+        assert!(!load(&engine(), &temp_path()).unwrap());
+    }
+
+    #[test]
+    fn test_corruption_and_version_mismatch_are_refused() {
+        let dedup = engine();
+        dedup.check_and_insert("pacer:deb:1");
+        let bytes = encode(&dedup.export_state());
+
+        let mut flipped = bytes.clone();
+        *flipped.last_mut().unwrap() ^= 0xff;
+        assert!(decode(&flipped).unwrap_err().to_string().contains("checksum"));
+
+        let mut future = bytes.clone();
+        future[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(decode(&future).unwrap_err().to_string().contains("version"));
+
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(b"definitely not a snapshot").is_err());
+    }
+
+    #[test]
+    fn test_corrupt_snapshot_is_moved_aside() {
+        let path = temp_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, b"FDDEDUP\0garbage garbage garbage").unwrap();
+
+        let dedup = engine();
+        restore_or_start_fresh(&dedup, &path);
+        assert!(!path.exists());
+        assert!(PathBuf::from(format!("{}.corrupt", path.display())).exists());
+        assert!(dedup.check_and_insert("pacer:deb:1"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+// =============================================================================
+// dedup/ttl.rs — HOW LONG IS A GRUDGE
+// =============================================================================
+//
+// Different sources deserve different memories. A PACER filing stays in the
+// court's RSS feed for days and we never want to hear about it twice in a
+// month. An FMCSA authority revocation can be reinstated and revoked again,
+// and when that happens, somebody in Rails wants to know.
+//
+// Every dedup key starts with its source — "pacer:", "edgar:", "fmcsa:",
+// "cl:" — and that prefix picks the TTL:
+//
+//   FREIGHT_DOOM_DEDUP_TTL=30d
+//   FREIGHT_DOOM_DEDUP_SOURCE_TTLS=fmcsa=90d,edgar=7d
+//
+// Keys with a prefix nobody configured get the default.
+// =============================================================================
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Per-source suppression windows for dedup keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DedupTtls {
+    /// TTL for keys whose source has no entry in `per_source`.
+    pub default: Duration,
+    /// TTL by dedup key prefix (the part before the first ':').
+    pub per_source: BTreeMap<String, Duration>,
+}
+
+impl DedupTtls {
+    /// The same TTL for every source.
+    pub fn uniform(ttl: Duration) -> Self {
+        Self {
+            default: ttl,
+            per_source: BTreeMap::new(),
+        }
+    }
+
+    /// Override the TTL for keys starting with `source:`.
+    pub fn with_source(mut self, source: impl Into<String>, ttl: Duration) -> Self {
+        self.per_source.insert(source.into(), ttl);
+        self
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        config
+            .dedup_source_ttls
+            .iter()
+            .fold(Self::uniform(config.dedup_default_ttl), |ttls, (source, ttl)| {
+                ttls.with_source(source.clone(), *ttl)
+            })
+    }
+
+    /// How long `key` is suppressed after it's accepted.
+    pub fn ttl_for(&self, key: &str) -> Duration {
+        key.split_once(':')
+            .and_then(|(source, _)| self.per_source.get(source))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// `ttl_for` in milliseconds, saturating rather than overflowing.
+    pub(crate) fn ttl_ms(&self, key: &str) -> i64 {
+        i64::try_from(self.ttl_for(key).as_millis()).unwrap_or(i64::MAX)
+    }
+
+    /// The longest TTL of any source, and so the longest a Bloom generation
+    /// needs to last.
+    pub fn longest(&self) -> Duration {
+        self.per_source
+            .values()
+            .copied()
+            .fold(self.default, Duration::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    #[test]
+    fn test_ttl_is_picked_by_key_prefix() {
+        let ttls = DedupTtls::uniform(30 * DAY).with_source("fmcsa", 90 * DAY);
+        assert_eq!(ttls.ttl_for("fmcsa:123456:REVOKED"), 90 * DAY);
+        assert_eq!(ttls.ttl_for("pacer:deb:https://example.com/1"), 30 * DAY);
+        assert_eq!(ttls.ttl_for("no-prefix-at-all"), 30 * DAY);
+        assert_eq!(ttls.longest(), 90 * DAY);
+    }
+
+    #[test]
+    fn test_parse_duration_units() {
+        use crate::config::parse_duration;
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("15m"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration("12h"), Some(Duration::from_secs(43_200)));
+        assert_eq!(parse_duration(" 30d "), Some(30 * DAY));
+        assert_eq!(parse_duration("forever"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = Config::from_env();
+        config.dedup_default_ttl = 30 * DAY;
+        config.dedup_source_ttls = vec![("edgar".into(), 7 * DAY)];
+        let ttls = DedupTtls::from_config(&config);
+        assert_eq!(ttls.ttl_for("edgar:ACME FREIGHT:8-K"), 7 * DAY);
+        assert_eq!(ttls.ttl_for("cl:42:In re Acme"), 30 * DAY);
+        assert_eq!(ttls.longest(), 30 * DAY);
+    }
+}
@@ -0,0 +1,691 @@
+// =============================================================================
+// extract.rs — EVERY NUMBER THAT MEANS SOMETHING
+// =============================================================================
+//
+// Filings, dockets and 8-Ks are full of identifiers, written every way a
+// paralegal can think of:
+//
+//   "USDOT 1234567"   "U.S. DOT No. 1234567"   "DOT#: 1234567"
+//   "MC-123456"       "MC - 123456"            "Docket No. MX 54321"
+//   "CIK 0001234567"  "EIN: XX-XXX1234"        "Case No. 24-10123-ABC"
+//
+// The PACER, EDGAR and CourtListener scanners used to carry three slightly
+// different copies of `detect_chapter`, `extract_dot_number` and
+// `extract_mc_number`, each missing a different half of the list above.
+// This is the one copy, and `identifiers` pulls everything out in one pass
+// into an `Identifiers`.
+//
+// No regex crate: each identifier is a label found at a word boundary,
+// followed by optional separators ("#", ":", "-", "No.", "Number") and a
+// run of digits. Periods after letters are dropped first, so "U.S.D.O.T."
+// reads as "USDOT" and "No." as "NO".
+// =============================================================================
+
+use serde::Serialize;
+
+use crate::models::{BankruptcyChapter, PetitionType};
+
+/// Longest USDOT number we believe. They're 1–8 digits in practice.
+const MAX_DOT_DIGITS: usize = 8;
+
+/// Longest MC/MX/FF docket number we believe.
+const MAX_DOCKET_DIGITS: usize = 8;
+
+/// Shortest USDOT or docket number we believe. "DOT 12" is more often a
+/// page reference than a carrier.
+const MIN_ID_DIGITS: usize = 3;
+
+/// Words that may sit between a label and its number: "DOT No. 123",
+/// "MC Number: 123".
+const NUMBER_WORDS: &[&str] = &["NO", "NUMBER", "NUM", "NBR", "NR"];
+
+/// What a jointly administered affiliate's filings say right before the
+/// lead debtor's case number, give or take a "Case No.".
+const LEAD_CASE_MARKERS: &[&str] = &["LEAD CASE", "JOINTLY ADMINISTERED UNDER", "JOINT ADMINISTRATION UNDER"];
+
+/// An FMCSA operating-authority docket prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum DocketPrefix {
+    /// Motor carrier (and broker) authority. The one everyone means.
+    MC,
+    /// Mexico-domiciled carriers operating in the US.
+    MX,
+    /// Freight forwarders.
+    FF,
+}
+
+impl DocketPrefix {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocketPrefix::MC => "MC",
+            DocketPrefix::MX => "MX",
+            DocketPrefix::FF => "FF",
+        }
+    }
+}
+
+/// An FMCSA docket number, e.g. MC-123456.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Docket {
+    pub prefix: DocketPrefix,
+    /// Digits only, leading zeros dropped.
+    pub number: String,
+}
+
+impl std::fmt::Display for Docket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.prefix.as_str(), self.number)
+    }
+}
+
+/// Everything `identifiers` found in a piece of text. Each field holds the
+/// first match; `dockets` and `ein_last4` hold every distinct match in
+/// order of appearance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Identifiers {
+    /// USDOT number, digits only, leading zeros dropped.
+    pub usdot: Option<String>,
+    /// MC, MX and FF docket numbers.
+    pub dockets: Vec<Docket>,
+    /// SEC Central Index Key, zero-padded to 10 digits like EDGAR does.
+    pub cik: Option<String>,
+    /// Last four digits of an EIN. Courts only publish those, and even a
+    /// full EIN is reduced to them so the two compare equal.
+    pub ein_last4: Vec<String>,
+    /// The first court case number, e.g. 24-10123-ABC or 2:24-bk-12345.
+    pub case_number: Option<CaseNumber>,
+    /// The lead case this one is jointly administered under, if the text
+    /// says so.
+    pub lead_case_number: Option<CaseNumber>,
+}
+
+impl Identifiers {
+    /// The first MC docket number, digits only.
+    pub fn mc_number(&self) -> Option<String> {
+        self.docket(DocketPrefix::MC).map(|d| d.number.clone())
+    }
+
+    /// The first docket number with this prefix.
+    pub fn docket(&self, prefix: DocketPrefix) -> Option<&Docket> {
+        self.dockets.iter().find(|d| d.prefix == prefix)
+    }
+
+    /// What goes in `BankruptcyEvent::mc_number`: the MC number's digits if
+    /// there is one, otherwise an MX or FF docket with its prefix
+    /// ("MX-54321"), so it can't be mistaken for an MC number.
+    pub fn event_docket(&self) -> Option<String> {
+        self.mc_number()
+            .or_else(|| self.dockets.first().map(|d| d.to_string()))
+    }
+}
+
+/// Pull every identifier we know out of `text`.
+pub fn identifiers(text: &str) -> Identifiers {
+    let upper = normalize(text);
+
+    let mut dockets: Vec<(usize, Docket)> = Vec::new();
+    for (label, prefix) in [("MC", DocketPrefix::MC), ("MX", DocketPrefix::MX), ("FF", DocketPrefix::FF)] {
+        for (pos, number) in labeled_numbers(&upper, label, MIN_ID_DIGITS, MAX_DOCKET_DIGITS) {
+            let docket = Docket { prefix, number: strip_zeros(&number) };
+            if !dockets.iter().any(|(_, d)| *d == docket) {
+                dockets.push((pos, docket));
+            }
+        }
+    }
+    dockets.sort_by_key(|(pos, _)| *pos);
+
+    Identifiers {
+        usdot: first_labeled(&upper, &["USDOT", "DOT"], MIN_ID_DIGITS, MAX_DOT_DIGITS).map(|n| strip_zeros(&n)),
+        dockets: dockets.into_iter().map(|(_, d)| d).collect(),
+        cik: first_labeled(&upper, &["CIK"], 1, 10).map(|n| format!("{:0>10}", n)),
+        ein_last4: ein_last4(&upper),
+        case_number: first_case_number(text),
+        lead_case_number: lead_case_number(text),
+    }
+}
+
+/// The USDOT number in `text`, if any.
+pub fn extract_dot_number(text: &str) -> Option<String> {
+    identifiers(text).usdot
+}
+
+/// The MC number in `text`, if any.
+pub fn extract_mc_number(text: &str) -> Option<String> {
+    identifiers(text).mc_number()
+}
+
+/// The first thing in `text` shaped like a bankruptcy case number, in its
+/// canonical spelling:
+///
+///   "24-10123"   "24-10123-ABC"   "2:24-bk-12345"   "1:24-BK-67890-abc" → "1:24-bk-67890-ABC"
+pub fn extract_case_number(text: &str) -> Option<String> {
+    first_case_number(text).map(|case| case.to_string())
+}
+
+/// Whether `token` is a bankruptcy case number and nothing else.
+pub fn is_case_number(token: &str) -> bool {
+    CaseNumber::parse(token).is_some()
+}
+
+/// A federal bankruptcy case number, taken apart:
+///
+///   2:24-bk-10123-ABC
+///   │ │  │  │     └── judge initials (change when the case is reassigned)
+///   │ │  │  └──────── sequence number within the year
+///   │ │  └─────────── case type: "bk" for the case itself, "ap" for an
+///   │ │               adversary proceeding inside it
+///   │ └────────────── two-digit year filed
+///   └──────────────── divisional office
+///
+/// Only the year, type and sequence identify the case. The same filing is
+/// "24-10123" in the RSS title, "2:24-bk-10123" on the docket sheet and
+/// "24-10123-ABC" in the caption; `normalized` makes all three "24-10123".
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CaseNumber {
+    pub office: Option<u8>,
+    pub year: u8,
+    /// Lowercase case type, if written.
+    pub kind: Option<String>,
+    pub sequence: u32,
+    /// Uppercase judge initials, if written.
+    pub judge: Option<String>,
+}
+
+impl CaseNumber {
+    /// Parse `[D:]YY-[tt-]NNNNN[-JJJ]`: optional office digit, two-digit
+    /// year, optional case type, 3–6 digit sequence number, optional judge
+    /// initials. Anything else — dates, phone numbers, "I-95" — is `None`.
+    pub fn parse(token: &str) -> Option<Self> {
+        let digits = |s: &str, min: usize, max: usize| (min..=max).contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit());
+        let letters = |s: &str, min: usize, max: usize| (min..=max).contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphabetic());
+
+        let (office, rest) = match token.split_once(':') {
+            Some((office, rest)) if digits(office, 1, 2) => (Some(office.parse().ok()?), rest),
+            Some(_) => return None,
+            None => (None, token),
+        };
+        let parts: Vec<&str> = rest.split('-').collect();
+        let (year, kind, sequence, judge) = match parts.as_slice() {
+            [year, seq] => (*year, None, *seq, None),
+            [year, kind, seq] if letters(kind, 2, 2) => (*year, Some(*kind), *seq, None),
+            [year, seq, judge] => (*year, None, *seq, Some(*judge)),
+            [year, kind, seq, judge] => (*year, Some(*kind), *seq, Some(*judge)),
+            _ => return None,
+        };
+        if !digits(year, 2, 2)
+            || !digits(sequence, 3, 6)
+            || !kind.is_none_or(|k| letters(k, 2, 2))
+            || !judge.is_none_or(|j| letters(j, 2, 4))
+        {
+            return None;
+        }
+
+        Some(Self {
+            office,
+            year: year.parse().ok()?,
+            kind: kind.map(str::to_lowercase),
+            sequence: sequence.parse().ok()?,
+            judge: judge.map(str::to_uppercase),
+        })
+    }
+
+    /// Whether this is the bankruptcy case itself rather than an adversary
+    /// proceeding or other matter filed inside it.
+    pub fn is_main_case(&self) -> bool {
+        self.kind.as_deref().is_none_or(|k| k == "bk")
+    }
+
+    /// Year and sequence, plus the case type when it isn't "bk": the part
+    /// that's the same however a document writes the case. Unique within
+    /// a court, not across courts.
+    pub fn normalized(&self) -> String {
+        match self.kind.as_deref() {
+            Some(kind) if !self.is_main_case() => format!("{:02}-{}-{:05}", self.year, kind, self.sequence),
+            _ => format!("{:02}-{:05}", self.year, self.sequence),
+        }
+    }
+}
+
+impl std::fmt::Display for CaseNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(office) = self.office {
+            write!(f, "{}:", office)?;
+        }
+        write!(f, "{:02}-", self.year)?;
+        if let Some(kind) = &self.kind {
+            write!(f, "{}-", kind)?;
+        }
+        write!(f, "{:05}", self.sequence)?;
+        if let Some(judge) = &self.judge {
+            write!(f, "-{}", judge)?;
+        }
+        Ok(())
+    }
+}
+
+/// The first case number in `text`.
+pub fn first_case_number(text: &str) -> Option<CaseNumber> {
+    case_tokens(text).find_map(CaseNumber::parse)
+}
+
+/// The lead case `text` says it's jointly administered under, if any:
+///
+///   "Case No. 24-10124 (Jointly Administered under Case No. 24-10123)"
+///   "Acme Freight Holdings, Inc. (Lead Case No. 24-10123)"
+///
+/// For the lead debtor's own filings this is its own case number.
+pub fn lead_case_number(text: &str) -> Option<CaseNumber> {
+    let upper = normalize(text);
+    for marker in LEAD_CASE_MARKERS {
+        for pos in word_positions(&upper, marker) {
+            let found = case_tokens(&upper[pos + marker.len()..])
+                .find(|token| *token != "CASE" && !NUMBER_WORDS.contains(token))
+                .and_then(CaseNumber::parse);
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+    None
+}
+
+/// Whitespace- and bracket-separated tokens, with trailing "." and ":"
+/// trimmed so "No. 24-10123." still parses.
+fn case_tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '[' | ']'))
+        .map(|token| token.trim_end_matches(['.', ':']))
+        .filter(|token| !token.is_empty())
+}
+
+/// The bankruptcy chapter `text` is talking about.
+///
+/// Chapter 15 and Subchapter V win over everything else, since their
+/// filings routinely mention other chapters ("...rather than a case under
+/// chapter 7"). After that, the order is 7, 11, 13, 12, 9: the chapters a
+/// trucking company is most likely to be in come first.
+pub fn detect_chapter(text: &str) -> BankruptcyChapter {
+    let upper = normalize(text);
+
+    if upper.contains("FOREIGN MAIN PROCEEDING")
+        || upper.contains("FOREIGN NONMAIN PROCEEDING")
+        || upper.contains("RECOGNITION OF FOREIGN")
+    {
+        return BankruptcyChapter::Chapter15;
+    }
+    let chapters = chapters_mentioned(&upper);
+    if chapters.contains(&15) {
+        return BankruptcyChapter::Chapter15;
+    }
+    if contains_word(&upper, "SUBCHAPTER V") {
+        return BankruptcyChapter::Chapter11SubchapterV;
+    }
+    [7, 11, 13, 12, 9]
+        .into_iter()
+        .find(|n| chapters.contains(n))
+        .map_or(BankruptcyChapter::Unknown, BankruptcyChapter::from_number)
+}
+
+/// Who filed the petition, if the text says.
+///
+/// "Involuntary Petition Against a Non-Individual" → Involuntary
+/// "Chapter 11 Voluntary Petition for Non-Individuals" → Voluntary
+pub fn detect_petition_type(text: &str) -> PetitionType {
+    let text = text.to_lowercase();
+    // "involuntary petition" contains "voluntary petition", so it goes first.
+    if ["involuntary petition", "involuntary chapter", "involuntary case", "filed involuntarily"]
+        .iter()
+        .any(|p| text.contains(p))
+    {
+        PetitionType::Involuntary
+    } else if ["voluntary petition", "filed voluntarily", "voluntary chapter", "voluntary case"]
+        .iter()
+        .any(|p| text.contains(p))
+    {
+        PetitionType::Voluntary
+    } else {
+        PetitionType::Unknown
+    }
+}
+
+/// Uppercase, with periods after letters dropped: "U.S. DOT No. 12" →
+/// "US DOT NO 12". Periods after digits stay, so "1.5" is still a number.
+fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut prev = ' ';
+    for c in text.chars() {
+        if c == '.' && prev.is_alphabetic() {
+            continue;
+        }
+        out.extend(c.to_uppercase());
+        prev = c;
+    }
+    out
+}
+
+/// Every chapter number written after "CHAPTER", "CH." or "CH", in digits
+/// or Roman numerals.
+fn chapters_mentioned(upper: &str) -> Vec<u32> {
+    let mut found = Vec::new();
+    for label in ["CHAPTER", "CH"] {
+        for pos in word_positions(upper, label) {
+            let rest = upper[pos + label.len()..].trim_start_matches([' ', '.', '\t']);
+            let token: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric()).collect();
+            let number = if token.bytes().all(|b| b.is_ascii_digit()) {
+                token.parse().ok()
+            } else {
+                roman(&token)
+            };
+            if let Some(n) = number {
+                found.push(n);
+            }
+        }
+    }
+    found
+}
+
+fn roman(token: &str) -> Option<u32> {
+    Some(match token {
+        "VII" => 7,
+        "IX" => 9,
+        "XI" => 11,
+        "XII" => 12,
+        "XIII" => 13,
+        "XV" => 15,
+        _ => return None,
+    })
+}
+
+/// Byte offsets where `word` appears with no letter or digit on either side.
+fn word_positions<'a>(upper: &'a str, word: &'a str) -> impl Iterator<Item = usize> + 'a {
+    upper.match_indices(word).map(|(pos, _)| pos).filter(move |&pos| {
+        let before = upper[..pos].chars().next_back();
+        let after = upper[pos + word.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphabetic())
+    })
+}
+
+fn contains_word(upper: &str, word: &str) -> bool {
+    word_positions(upper, word).any(|pos| {
+        !upper[pos + word.len()..].chars().next().is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+/// The first number after any of `labels`, trying labels in order.
+fn first_labeled(upper: &str, labels: &[&str], min: usize, max: usize) -> Option<String> {
+    labels
+        .iter()
+        .find_map(|label| labeled_numbers(upper, label, min, max).into_iter().next())
+        .map(|(_, number)| number)
+}
+
+/// Every `(position, digits)` where `label` is followed by separators and
+/// a run of `min..=max` digits.
+fn labeled_numbers(upper: &str, label: &str, min: usize, max: usize) -> Vec<(usize, String)> {
+    let mut found = Vec::new();
+    for pos in word_positions(upper, label) {
+        let mut rest = &upper[pos + label.len()..];
+        loop {
+            let trimmed = rest.trim_start_matches([' ', '\t', '#', ':', '-', '.']);
+            match NUMBER_WORDS.iter().find(|w| {
+                trimmed.starts_with(**w) && !trimmed[w.len()..].starts_with(|c: char| c.is_alphanumeric())
+            }) {
+                Some(word) => rest = &trimmed[word.len()..],
+                None => {
+                    rest = trimmed;
+                    break;
+                }
+            }
+        }
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        let followed_by_word = rest[digits.len()..].starts_with(|c: char| c.is_alphanumeric());
+        if (min..=max).contains(&digits.len()) && !followed_by_word {
+            found.push((pos, digits));
+        }
+    }
+    found
+}
+
+/// Last four digits of every EIN mentioned: "EIN 12-3456789", "EIN:
+/// XX-XXX1234", "last four digits of the debtor's federal tax
+/// identification number are 1234".
+fn ein_last4(upper: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for label in ["EIN", "FEIN", "TAX ID", "TAX IDENTIFICATION NUMBER", "TAXPAYER IDENTIFICATION NUMBER"] {
+        for pos in word_positions(upper, label) {
+            // The number follows within a few words: "EIN: ", "EIN ARE ",
+            // "NUMBER ARE ".
+            let window: String = upper[pos + label.len()..].chars().take(40).collect();
+            let Some(start) = window.find(|c: char| c.is_ascii_digit() || c == 'X') else {
+                continue;
+            };
+            let candidate: String = window[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == 'X' || *c == '-')
+                .filter(|c| *c != '-')
+                .collect();
+            let digits: String = candidate.chars().rev().take_while(char::is_ascii_digit).collect();
+            let valid = matches!(candidate.len(), 4 | 9) && digits.len() >= 4;
+            if valid {
+                let last4: String = digits.chars().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+                if !found.contains(&last4) {
+                    found.push(last4);
+                }
+            }
+        }
+    }
+    found
+}
+
+fn strip_zeros(digits: &str) -> String {
+    match digits.trim_start_matches('0') {
+        "" => "0".to_string(),
+        stripped => stripped.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mc(number: &str) -> Docket {
+        Docket { prefix: DocketPrefix::MC, number: number.into() }
+    }
+
+    #[test]
+    fn test_usdot_corpus() {
+        let cases: &[(&str, Option<&str>)] = &[
+            ("USDOT 1234567", Some("1234567")),
+            ("USDOT# 1234567", Some("1234567")),
+            ("USDOT #1234567", Some("1234567")),
+            ("USDOT: 1234567", Some("1234567")),
+            ("USDOT No. 1234567", Some("1234567")),
+            ("U.S. DOT No. 1234567", Some("1234567")),
+            ("U.S.D.O.T. 1234567", Some("1234567")),
+            ("US DOT Number: 1234567", Some("1234567")),
+            ("DOT#1234567", Some("1234567")),
+            ("DOT # 1234567", Some("1234567")),
+            ("dot no 0012345", Some("12345")),
+            ("Acme Freight (USDOT 987654) filed chapter 11", Some("987654")),
+            ("USDOT 123456789", None),
+            ("DOT 12", None),
+            ("DOTS 1234567", None),
+            ("ANECDOT 1234567", None),
+            ("no identifiers here", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(extract_dot_number(text).as_deref(), *expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_docket_corpus() {
+        let cases: &[(&str, &[(DocketPrefix, &str)])] = &[
+            ("MC-123456", &[(DocketPrefix::MC, "123456")]),
+            ("MC - 123456", &[(DocketPrefix::MC, "123456")]),
+            ("MC 123456", &[(DocketPrefix::MC, "123456")]),
+            ("MC# 123456", &[(DocketPrefix::MC, "123456")]),
+            ("MC #123456", &[(DocketPrefix::MC, "123456")]),
+            ("MC No. 123456", &[(DocketPrefix::MC, "123456")]),
+            ("mc-0123456", &[(DocketPrefix::MC, "123456")]),
+            ("Docket MX-54321", &[(DocketPrefix::MX, "54321")]),
+            ("FF 7654", &[(DocketPrefix::FF, "7654")]),
+            ("MX 111222 and MC-333444", &[(DocketPrefix::MX, "111222"), (DocketPrefix::MC, "333444")]),
+            ("MC-123456, MC 123456", &[(DocketPrefix::MC, "123456")]),
+            ("MCKINLEY 123456", &[]),
+            ("EMC 123456", &[]),
+            ("MC-12", &[]),
+            ("MC-123456789", &[]),
+        ];
+        for (text, expected) in cases {
+            let dockets: Vec<Docket> = expected
+                .iter()
+                .map(|(prefix, number)| Docket { prefix: *prefix, number: number.to_string() })
+                .collect();
+            assert_eq!(identifiers(text).dockets, dockets, "{:?}", text);
+        }
+        assert_eq!(extract_mc_number("MX-54321 MC-123456").as_deref(), Some("123456"));
+        assert_eq!(identifiers("MX-54321").event_docket().as_deref(), Some("MX-54321"));
+    }
+
+    #[test]
+    fn test_cik_and_ein_corpus() {
+        let cases: &[(&str, Option<&str>, &[&str])] = &[
+            ("CIK 0001234567", Some("0001234567"), &[]),
+            ("CIK: 1234567", Some("0001234567"), &[]),
+            ("CIK No. 320193", Some("0000320193"), &[]),
+            ("EIN 12-3456789", None, &["6789"]),
+            ("EIN: XX-XXX1234", None, &["1234"]),
+            ("FEIN 123456789", None, &["6789"]),
+            ("The last four digits of the Debtor's federal tax identification number are 4321", None, &["4321"]),
+            ("Acme (EIN 12-3456789) and Acme Leasing (EIN XX-XXX5555)", None, &["6789", "5555"]),
+            ("EIN pending", None, &[]),
+            ("REINSTATED 1234", None, &[]),
+        ];
+        for (text, cik, eins) in cases {
+            let ids = identifiers(text);
+            assert_eq!(ids.cik.as_deref(), *cik, "{:?}", text);
+            assert_eq!(ids.ein_last4, *eins, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_case_number_corpus() {
+        let cases: &[(&str, Option<&str>)] = &[
+            ("24-10123", Some("24-10123")),
+            ("24-10123-ABC", Some("24-10123-ABC")),
+            ("2:24-bk-12345", Some("2:24-bk-12345")),
+            ("1:24-bk-67890-ABC Big Truck Company Inc.", Some("1:24-bk-67890-ABC")),
+            ("1:24-BK-67890-abc", Some("1:24-bk-67890-ABC")),
+            ("Case No. 24-10123 (ABC)", Some("24-10123")),
+            ("In re Acme Freight, Case No. 23-90001.", Some("23-90001")),
+            ("[24-10123-KBO]", Some("24-10123-KBO")),
+            ("filed 2024-01-15", None),
+            ("I-95 corridor", None),
+            ("phone 555-1234", None),
+            ("Acme Freight LLC", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(extract_case_number(text).as_deref(), *expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_case_number_parts() {
+        let case = CaseNumber::parse("2:24-bk-10123-KBO").unwrap();
+        assert_eq!(case.office, Some(2));
+        assert_eq!(case.year, 24);
+        assert_eq!(case.kind.as_deref(), Some("bk"));
+        assert_eq!(case.sequence, 10123);
+        assert_eq!(case.judge.as_deref(), Some("KBO"));
+        assert!(case.is_main_case());
+
+        let same: &[&str] = &["24-10123", "2:24-bk-10123", "24-10123-KBO", "1:24-BK-10123-mfw"];
+        for written in same {
+            assert_eq!(CaseNumber::parse(written).unwrap().normalized(), "24-10123", "{:?}", written);
+        }
+
+        let adversary = CaseNumber::parse("24-ap-50001").unwrap();
+        assert!(!adversary.is_main_case());
+        assert_eq!(adversary.normalized(), "24-ap-50001");
+        assert_eq!(CaseNumber::parse("09-00123").unwrap().to_string(), "09-00123");
+
+        for junk in ["2024-01-15", "a:24-10123", "24-10123-A1", "24-12", "24-bk-10123-ABCDE"] {
+            assert_eq!(CaseNumber::parse(junk), None, "{:?}", junk);
+        }
+    }
+
+    #[test]
+    fn test_lead_case_corpus() {
+        let cases: &[(&str, Option<&str>, Option<&str>)] = &[
+            (
+                "Case No. 24-10124 (Jointly Administered under Case No. 24-10123)",
+                Some("24-10124"),
+                Some("24-10123"),
+            ),
+            ("Acme Freight Holdings, Inc. (Lead Case No. 24-10123-KBO)", Some("24-10123-KBO"), Some("24-10123-KBO")),
+            ("Motion for Joint Administration under 1:24-bk-10123", Some("1:24-bk-10123"), Some("1:24-bk-10123")),
+            ("24-10124 Acme Trucking LLC Chapter 11 Voluntary Petition", Some("24-10124"), None),
+            ("lead case pending; see 24-10123 et seq.", None, None),
+        ];
+        for (text, case, lead) in cases {
+            let ids = identifiers(text);
+            if case.is_some() {
+                assert_eq!(ids.case_number.map(|c| c.to_string()).as_deref(), *case, "{:?}", text);
+            }
+            assert_eq!(ids.lead_case_number.map(|c| c.to_string()).as_deref(), *lead, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_chapter_corpus() {
+        use BankruptcyChapter::*;
+        let cases: &[(&str, BankruptcyChapter)] = &[
+            ("Voluntary Petition under Chapter 7", Chapter7),
+            ("CH. 7", Chapter7),
+            ("Ch 11 petition", Chapter11),
+            ("ch.11", Chapter11),
+            ("filed for relief under chapter XI", Chapter11),
+            ("Chapter XIII plan", Chapter13),
+            ("Chapter 12 family farmer", Chapter12),
+            ("Chapter 9 municipality", Chapter9),
+            ("Chapter 15 petition for recognition", Chapter15),
+            ("Verified Petition for Recognition of Foreign Main Proceeding", Chapter15),
+            ("converting the chapter 11 case to a case under chapter 7", Chapter7),
+            ("Chapter 11 Subchapter V small business", Chapter11SubchapterV),
+            ("Chapter 15 ancillary to a Chapter 11 proceeding", Chapter15),
+            ("each 7 days", Unknown),
+            ("Chapter 110 of the state code", Unknown),
+            ("nothing to see", Unknown),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_chapter(text), *expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_petition_type_corpus() {
+        let cases: &[(&str, PetitionType)] = &[
+            ("Involuntary Petition Against a Non-Individual", PetitionType::Involuntary),
+            ("Chapter 11 Voluntary Petition for Non-Individuals", PetitionType::Voluntary),
+            ("the Company filed a voluntary petition for relief under Chapter 11", PetitionType::Voluntary),
+            ("creditors commenced an involuntary chapter 7 case", PetitionType::Involuntary),
+            ("Order Dismissing Case", PetitionType::Unknown),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_petition_type(text), *expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_everything_at_once() {
+        let text = "2:24-bk-12345 Acme Freight LLC (U.S. DOT No. 1234567, MC-654321, EIN XX-XXX9876) \
+                    Chapter 11 Voluntary Petition";
+        let ids = identifiers(text);
+        assert_eq!(ids.usdot.as_deref(), Some("1234567"));
+        assert_eq!(ids.dockets, vec![mc("654321")]);
+        assert_eq!(ids.ein_last4, vec!["9876"]);
+        assert_eq!(ids.case_number.map(|c| c.to_string()).as_deref(), Some("2:24-bk-12345"));
+        assert_eq!(ids.lead_case_number, None);
+        assert_eq!(ids.cik, None);
+    }
+}
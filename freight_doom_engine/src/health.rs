@@ -0,0 +1,184 @@
+// =============================================================================
+// health.rs — IS THE ENGINE ALIVE, AND IS IT ANY USE?
+// =============================================================================
+//
+// Two different questions, asked by an orchestrator that has no patience:
+//
+// - Liveness (/healthz): is anybody home? If every scanner task has exited,
+//   the engine is a very expensive way to hold a TCP port open. Restart it.
+//
+// - Readiness (/readyz): is it actually detecting anything? Not if every
+//   circuit breaker is open, and not if a source hasn't had a successful
+//   poll in N poll intervals. A scanner that's wedged looks exactly like a
+//   scanner that's working, right up until somebody checks the timestamps.
+//
+// Both endpoints return the same report — per-scanner `ScannerHealth`,
+// Redis connectivity, channel depth — with a 503 when the answer is no.
+// =============================================================================
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::metrics::{MetricsCollector, QueueDepth};
+use crate::models::ScannerHealth;
+
+/// Everything the health endpoints know, plus the verdicts.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub live: bool,
+    pub ready: bool,
+    /// Why the engine isn't live or ready. Empty when all is well.
+    pub reasons: Vec<String>,
+    pub scanners: BTreeMap<String, ScannerHealth>,
+    /// Whether every Redis sink is connected. `None` when the pipeline
+    /// isn't publishing to Redis.
+    pub redis_connected: Option<bool>,
+    pub queue: Option<QueueDepth>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Check the engine's health as of `now`.
+///
+/// A scanner counts as stale once its last successful poll (or, before the
+/// first one, its registration) is more than `stale_after_intervals` of its
+/// own poll intervals ago.
+pub fn check(metrics: &MetricsCollector, stale_after_intervals: u32, now: DateTime<Utc>) -> HealthReport {
+    let statuses = metrics.scanner_statuses();
+    let mut reasons = Vec::new();
+
+    let live = statuses.is_empty() || statuses.iter().any(|s| s.health.is_running);
+    if !live {
+        reasons.push("every scanner task has stopped".to_string());
+    }
+
+    let mut ready = live;
+    if statuses.is_empty() {
+        ready = false;
+        reasons.push("no scanners registered".to_string());
+    } else if statuses.iter().all(|s| s.health.circuit_breaker_state == "OPEN") {
+        ready = false;
+        reasons.push("every circuit breaker is open".to_string());
+    }
+
+    for status in &statuses {
+        let limit = status.poll_interval * stale_after_intervals;
+        let since = status.health.last_poll.unwrap_or(status.registered_at);
+        let age = (now - since).to_std().unwrap_or_default();
+        if age > limit {
+            ready = false;
+            reasons.push(format!(
+                "{}: no successful poll in {}s (limit {}s)",
+                status.name,
+                age.as_secs(),
+                limit.as_secs()
+            ));
+        }
+    }
+
+    HealthReport {
+        live,
+        ready,
+        reasons,
+        scanners: statuses
+            .into_iter()
+            .map(|s| (s.name, s.health))
+            .collect(),
+        redis_connected: redis_connected(metrics),
+        queue: metrics.queue_depth(),
+        checked_at: now,
+    }
+}
+
+fn redis_connected(metrics: &MetricsCollector) -> Option<bool> {
+    let publishers = metrics.snapshot().publishers;
+    (!publishers.is_empty()).then(|| publishers.values().all(|p| p.connected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::CircuitBreaker;
+    use crate::models::Source;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn breaker() -> Arc<CircuitBreaker> {
+        Arc::new(CircuitBreaker::new("test", 1, Duration::from_secs(60), 1))
+    }
+
+    #[test]
+    fn test_ready_when_scanners_are_polling() {
+        let metrics = MetricsCollector::new();
+        metrics.register_scanner("pacer", Source::Pacer, Duration::from_secs(60), breaker());
+        metrics.set_scanner_running("pacer", true);
+        metrics.record_successful_poll("pacer");
+
+        let report = check(&metrics, 3, Utc::now());
+        assert!(report.live);
+        assert!(report.ready, "{:?}", report.reasons);
+        assert!(report.scanners["pacer"].is_running);
+    }
+
+    #[test]
+    fn test_redis_connected_covers_every_redis_sink() {
+        use crate::publisher::PublisherStats;
+
+        let metrics = MetricsCollector::new();
+        assert_eq!(check(&metrics, 3, Utc::now()).redis_connected, None);
+
+        let redis = Arc::new(PublisherStats::new());
+        let stream = Arc::new(PublisherStats::new());
+        metrics.register_publisher("redis", redis.clone());
+        metrics.register_publisher("redis_stream", stream.clone());
+        redis.connected.store(true, portable_atomic::Ordering::Relaxed);
+        assert_eq!(check(&metrics, 3, Utc::now()).redis_connected, Some(false));
+
+        stream.connected.store(true, portable_atomic::Ordering::Relaxed);
+        assert_eq!(check(&metrics, 3, Utc::now()).redis_connected, Some(true));
+    }
+
+    #[test]
+    fn test_not_ready_when_all_breakers_open() {
+        let metrics = MetricsCollector::new();
+        let pacer = breaker();
+        let edgar = breaker();
+        metrics.register_scanner("pacer", Source::Pacer, Duration::from_secs(60), pacer.clone());
+        metrics.register_scanner("edgar", Source::Edgar, Duration::from_secs(60), edgar.clone());
+        metrics.set_scanner_running("pacer", true);
+        metrics.set_scanner_running("edgar", true);
+
+        pacer.record_failure();
+        assert!(check(&metrics, 3, Utc::now()).ready);
+
+        edgar.record_failure();
+        let report = check(&metrics, 3, Utc::now());
+        assert!(report.live);
+        assert!(!report.ready);
+    }
+
+    #[test]
+    fn test_not_ready_when_source_is_stale() {
+        let metrics = MetricsCollector::new();
+        metrics.register_scanner("fmcsa", Source::Fmcsa, Duration::from_secs(60), breaker());
+        metrics.set_scanner_running("fmcsa", true);
+
+        assert!(check(&metrics, 3, Utc::now()).ready);
+
+        let later = Utc::now() + chrono::Duration::seconds(181);
+        let report = check(&metrics, 3, later);
+        assert!(!report.ready);
+        assert!(report.reasons[0].starts_with("fmcsa:"));
+    }
+
+    #[test]
+    fn test_not_live_when_every_scanner_stopped() {
+        let metrics = MetricsCollector::new();
+        metrics.register_scanner("pacer", Source::Pacer, Duration::from_secs(60), breaker());
+
+        let report = check(&metrics, 3, Utc::now());
+        assert!(!report.live);
+        assert!(!report.ready);
+    }
+}
@@ -0,0 +1,65 @@
+// =============================================================================
+// lib.rs — THE ENGINE, NOW AVAILABLE IN LIBRARY FORM
+// =============================================================================
+//
+// Everything that makes the Freight Doom Engine tick, exposed for other Rust
+// services to embed: the Aho-Corasick classifier, the Bloom + LRU dedup
+// engine, the bankruptcy event types, and a pipeline builder that accepts
+// your own sources and sinks.
+//
+// The `freight_doom_engine` binary is just one consumer of this crate. Your
+// ingestion worker can be another. We don't judge. We just detect.
+//
+// Stable entry points:
+// - `models`        — `BankruptcyEvent`, `Source`, `BankruptcyChapter`, ...
+// - `scan_text`     — freight/bankruptcy relevance scoring
+// - `DedupEngine`   — Bloom filter + LRU cache deduplication
+// - `DedupBackend`  — local dedup, or `RedisDedup` shared between replicas
+// - `courts`        — every scanner's name for a bankruptcy court, as its PACER id
+// - `extract`       — USDOT, MC/MX/FF, CIK, EIN and case numbers, chapter, petition type
+// - `names`         — debtor-name normalisation and fuzzy matching
+// - `EntityResolver` — merges one bankruptcy seen by several sources into a case
+// - `CaseTracker`   — conversions, dismissals and confirmations of known cases
+// - `AffiliateGrouper` — folds jointly administered affiliates into their lead case
+// - `Pipeline`      — scanners → dedup → resolution → channel → sink, assembled for you
+// - `Scanner`       — implement this to add a source
+// - `EventSink`     — implement this to send events somewhere other than Redis
+// - `OutboxSink`    — put any sink behind a durable on-disk outbox
+// - `sinks`         — JSONL, webhook, and stdout sinks, plus the fan-out
+// =============================================================================
+
+pub mod affiliates;
+pub mod circuit_breaker;
+pub mod config;
+pub mod courts;
+pub mod dedup;
+pub mod extract;
+pub mod health;
+pub mod lifecycle;
+pub mod metrics;
+pub mod models;
+pub mod names;
+pub mod outbox;
+pub mod pipeline;
+pub mod publisher;
+pub mod resolution;
+pub mod scanners;
+pub mod server;
+pub mod sinks;
+pub mod text_scanner;
+
+pub use affiliates::AffiliateGrouper;
+pub use config::Config;
+pub use dedup::{DedupBackend, DedupEngine, RedisDedup};
+pub use lifecycle::CaseTracker;
+pub use models::{
+    AffiliateDebtor, BankruptcyChapter, BankruptcyEvent, CaseStatus, CaseUpdate, ClassScore, CompanyClassification, EventType,
+    LifecycleEvent, ScoreContribution, Source,
+};
+pub use names::NameMatcher;
+pub use outbox::{Outbox, OutboxSink};
+pub use pipeline::{Pipeline, PipelineBuilder};
+pub use publisher::{EventSink, RedisPublisher, RedisStreamSink};
+pub use resolution::EntityResolver;
+pub use scanners::{Candidate, FetchTarget, Scanner};
+pub use text_scanner::{quick_freight_check, scan_text, scan_text_with, Dictionary, Keywords, RelevanceModel, ScanResult, ScoringModel};
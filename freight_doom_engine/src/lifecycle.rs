@@ -0,0 +1,726 @@
+// =============================================================================
+// lifecycle.rs — WHAT HAPPENED AFTER THE PETITION
+// =============================================================================
+//
+// A petition is the start of a bankruptcy, not the end. For a shipper the
+// moments that matter come later:
+//
+// - the Chapter 11 converts to Chapter 7 and the trucks stop rolling
+// - the case is dismissed and the carrier is back to owing everyone
+// - a plan is confirmed and the company either survives or liquidates
+// - a § 363 sale hands the fleet and the contracts to a buyer
+// - the final decree closes the case
+//
+// PACER's docket feeds mention all of these, in entries like
+// "Order Granting Motion to Convert Case to Chapter 7". The `CaseTracker`
+// keeps a record for every case it has seen, keyed by court + case number,
+// and turns docket text for a known case into a `CaseUpdate` event when it
+// changes the case's state.
+//
+// Docket entries for a known case skip the freight relevance check — "Order
+// Dismissing Case" doesn't mention trucks, but we already know the debtor
+// does. Motions, notices and objections don't count; only orders and the
+// things that happen because of them do. A motion to convert is a threat,
+// not a conversion, and so is a "Motion for Entry of an Order Converting
+// Case": an entry is an order only if it says so before it says anything
+// about being asked for.
+//
+// The records are kept in a JSON file (`FREIGHT_DOOM_CASE_STORE`), read
+// back at startup. `record` only marks the store dirty: rewriting and
+// fsyncing the whole file for every new case, under the lock, on the
+// submit path, made every scanner wait on the disk. `run_saver` writes it
+// from a blocking task every `FREIGHT_DOOM_CASE_STORE_INTERVAL` if anything
+// changed, and the pipeline saves it once more at shutdown.
+//
+// Closed and dismissed cases are dropped once they've been quiet for
+// `FREIGHT_DOOM_CASE_RETENTION`, so the store holds the cases that can still
+// change rather than every case ever filed. A dismissal that gets vacated
+// after that shows up as a new case.
+// =============================================================================
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use portable_atomic::{AtomicBool, AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::config::Config;
+use crate::courts;
+use crate::extract;
+use crate::models::{BankruptcyChapter, BankruptcyEvent, CaseStatus, CaseUpdate, LifecycleEvent};
+
+/// Bumped whenever the case store's layout changes.
+pub const STORE_VERSION: u32 = 1;
+
+/// How long a closed or dismissed case is kept after its last update,
+/// unless the config says otherwise.
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(180 * 86_400);
+
+/// Phrases that mean someone is asking the court for something rather than
+/// the court doing it.
+const REQUEST_MARKERS: &[&str] = &[
+    "motion", "notice", "objection", "application", "request", "proposed order", "hearing",
+];
+
+/// Phrases that mean the court actually ruled.
+const RULING_MARKERS: &[&str] = &["order", "final decree", "judgment"];
+
+/// Orders that change nothing: ones that only move the calendar around, and
+/// refusals. "Order Scheduling Hearing on Motion to Convert" converts
+/// nothing, and neither does "Order Denying Motion to Convert".
+const PROCEDURAL_ORDERS: &[&str] = &[
+    "scheduling", "setting hearing", "shortening", "continuing", "adjourning", "extending time",
+    "denying",
+];
+
+/// One lifecycle event in a case's history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LifecycleEntry {
+    pub event: LifecycleEvent,
+    pub at: DateTime<Utc>,
+}
+
+/// Everything we know about one court case.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaseRecord {
+    pub case_key: String,
+    pub court: String,
+    pub case_number: String,
+    pub company_name: String,
+    /// The canonical case id from entity resolution, if it ran.
+    pub case_id: Option<String>,
+    pub chapter: BankruptcyChapter,
+    pub status: CaseStatus,
+    /// Confidence of the detection that opened the case. Updates reuse it.
+    pub confidence_score: f64,
+    /// Every lifecycle event seen, oldest first.
+    pub history: Vec<LifecycleEntry>,
+    pub first_seen: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CaseRecord {
+    /// Apply `event`, returning the update if it told us something new.
+    fn apply(&mut self, event: LifecycleEvent, at: DateTime<Utc>) -> Option<CaseUpdate> {
+        let repeatable = event == LifecycleEvent::AffiliatesJoined;
+        if !repeatable && self.history.iter().any(|entry| entry.event == event) {
+            return None;
+        }
+
+        let previous_status = self.status;
+        let previous_chapter = self.chapter.clone();
+        match &event {
+            LifecycleEvent::Filed | LifecycleEvent::SaleOrder363 | LifecycleEvent::AffiliatesJoined => {}
+            LifecycleEvent::ConvertedTo(chapter) => {
+                if *chapter != BankruptcyChapter::Unknown {
+                    self.chapter = chapter.clone();
+                }
+                self.status = CaseStatus::Open;
+            }
+            LifecycleEvent::Dismissed => self.status = CaseStatus::Dismissed,
+            LifecycleEvent::PlanConfirmed => self.status = CaseStatus::PlanConfirmed,
+            LifecycleEvent::Closed => self.status = CaseStatus::Closed,
+        }
+        self.history.push(LifecycleEntry { event: event.clone(), at });
+        self.updated_at = at;
+
+        Some(CaseUpdate {
+            case_key: self.case_key.clone(),
+            lifecycle_event: event,
+            previous_status,
+            status: self.status,
+            previous_chapter,
+            chapter: self.chapter.clone(),
+        })
+    }
+}
+
+/// The case store as it sits on disk.
+#[derive(Deserialize)]
+struct StoreFile {
+    version: u32,
+    cases: Vec<CaseRecord>,
+}
+
+/// Statistics for metrics.
+#[derive(Default)]
+pub struct CaseTrackerStats {
+    pub cases_opened: AtomicU64,
+    pub updates: AtomicU64,
+    pub expired: AtomicU64,
+    pub save_errors: AtomicU64,
+}
+
+/// A serializable snapshot of the tracker's statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseTrackerSnapshot {
+    pub cases_tracked: usize,
+    pub cases_opened: u64,
+    pub updates: u64,
+    pub expired: u64,
+    pub save_errors: u64,
+}
+
+/// Persistent case records, keyed by court + case number.
+pub struct CaseTracker {
+    cases: Mutex<HashMap<String, CaseRecord>>,
+    path: Option<PathBuf>,
+    /// Set by every change, cleared by the save that writes it.
+    dirty: AtomicBool,
+    retention: Duration,
+    pub stats: CaseTrackerStats,
+}
+
+impl CaseTracker {
+    /// A tracker that keeps its cases in memory only.
+    pub fn in_memory() -> Self {
+        Self {
+            cases: Mutex::new(HashMap::new()),
+            path: None,
+            dirty: AtomicBool::new(false),
+            retention: DEFAULT_RETENTION,
+            stats: CaseTrackerStats::default(),
+        }
+    }
+
+    /// A tracker backed by the file at `path`. A missing file starts empty;
+    /// an unreadable one is moved aside to `<path>.corrupt` and the tracker
+    /// starts empty, so a bad file costs us history rather than the engine.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let cases = match load(&path) {
+            Ok(Some(cases)) => {
+                info!(path = %path.display(), cases = cases.len(), "Case store restored");
+                cases
+            }
+            Ok(None) => {
+                info!(path = %path.display(), "No case store yet — starting empty");
+                Vec::new()
+            }
+            Err(e) => {
+                let aside = PathBuf::from(format!("{}.corrupt", path.display()));
+                error!(
+                    error = format!("{:#}", e),
+                    moved_to = %aside.display(),
+                    "Case store unreadable — starting empty, known cases will be reported as new"
+                );
+                if let Err(e) = fs::rename(&path, &aside) {
+                    warn!(error = %e, "Could not move the bad case store aside");
+                }
+                Vec::new()
+            }
+        };
+
+        // Re-derive keys rather than trusting the stored ones, so records
+        // written before case numbers were normalised still match.
+        let cases = cases.into_iter().map(|mut c| {
+            if let Some(key) = case_key(&c.court, &c.case_number) {
+                c.case_key = key;
+            }
+            (c.case_key.clone(), c)
+        });
+
+        Self {
+            cases: Mutex::new(cases.collect()),
+            path: Some(path),
+            dirty: AtomicBool::new(false),
+            retention: DEFAULT_RETENTION,
+            stats: CaseTrackerStats::default(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let tracker = match &config.case_store_path {
+            Some(path) => Self::open(path),
+            None => Self::in_memory(),
+        };
+        tracker.with_retention(config.case_retention)
+    }
+
+    /// Keep closed and dismissed cases for `retention` after their last
+    /// update.
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Is the case `event` belongs to one we're tracking?
+    pub fn is_known(&self, event: &BankruptcyEvent) -> bool {
+        event_case_key(event).is_some_and(|key| self.cases.lock().contains_key(&key))
+    }
+
+    /// A copy of the record for `case_key`.
+    pub fn get(&self, case_key: &str) -> Option<CaseRecord> {
+        self.cases.lock().get(case_key).cloned()
+    }
+
+    /// Record what `event` says about its case.
+    ///
+    /// An event for a case we haven't seen opens a record (in the state
+    /// `lifecycle` describes, or just filed) and returns `None`: the
+    /// detection itself is the news. For a known case, returns the update
+    /// if `lifecycle` is something the case hasn't been through yet.
+    /// Events without a court and case number are ignored.
+    pub fn record(&self, event: &BankruptcyEvent, lifecycle: Option<LifecycleEvent>) -> Option<CaseUpdate> {
+        let (Some(court), Some(case_number)) = (&event.court, &event.case_number) else {
+            return None;
+        };
+        let key = case_key(court, case_number)?;
+        let at = event.detected_at;
+
+        let mut cases = self.cases.lock();
+        let update = match cases.get_mut(&key) {
+            Some(case) => {
+                if case.case_id.is_none() {
+                    case.case_id = event.case_id.clone();
+                }
+                let update = case.apply(lifecycle?, at)?;
+                self.stats.updates.fetch_add(1, Ordering::Relaxed);
+                Some(update)
+            }
+            None => {
+                let mut case = CaseRecord {
+                    case_key: key.clone(),
+                    court: court.clone(),
+                    case_number: case_number.clone(),
+                    company_name: event.company_name.clone(),
+                    case_id: event.case_id.clone(),
+                    chapter: event.chapter.clone(),
+                    status: CaseStatus::Open,
+                    confidence_score: event.confidence_score,
+                    history: Vec::new(),
+                    first_seen: at,
+                    updated_at: at,
+                };
+                case.apply(LifecycleEvent::Filed, at);
+                if let Some(lifecycle) = lifecycle {
+                    case.apply(lifecycle, at);
+                }
+                cases.insert(key, case);
+                self.stats.cases_opened.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        };
+
+        self.dirty.store(true, Ordering::Relaxed);
+        update
+    }
+
+    /// Drop closed and dismissed cases nobody has mentioned for longer than
+    /// the retention period. Returns how many went.
+    pub fn expire(&self, now: DateTime<Utc>) -> usize {
+        let cutoff = chrono::Duration::from_std(self.retention)
+            .ok()
+            .and_then(|retention| now.checked_sub_signed(retention));
+        let Some(cutoff) = cutoff else {
+            return 0;
+        };
+
+        let mut cases = self.cases.lock();
+        let before = cases.len();
+        cases.retain(|_, case| {
+            !matches!(case.status, CaseStatus::Closed | CaseStatus::Dismissed) || case.updated_at > cutoff
+        });
+        let expired = before - cases.len();
+        if expired > 0 {
+            self.stats.expired.fetch_add(expired as u64, Ordering::Relaxed);
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        expired
+    }
+
+    /// Write the store if anything changed since the last save. The records
+    /// are copied under the lock and written after it's released. Blocks on
+    /// the disk, so async callers go through `spawn_blocking`.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let mut records: Vec<CaseRecord> = self.cases.lock().values().cloned().collect();
+        records.sort_by(|a, b| a.case_key.cmp(&b.case_key));
+        write_store(&records, path).inspect_err(|_| {
+            // Try again next time rather than losing the change.
+            self.dirty.store(true, Ordering::Relaxed);
+            self.stats.save_errors.fetch_add(1, Ordering::Relaxed);
+        })
+    }
+
+    pub fn snapshot(&self) -> CaseTrackerSnapshot {
+        CaseTrackerSnapshot {
+            cases_tracked: self.cases.lock().len(),
+            cases_opened: self.stats.cases_opened.load(Ordering::Relaxed),
+            updates: self.stats.updates.load(Ordering::Relaxed),
+            expired: self.stats.expired.load(Ordering::Relaxed),
+            save_errors: self.stats.save_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The key a case is tracked under: the court's PACER id and the case
+/// number, normalised so that every scanner's name for the court and office
+/// and judge suffixes on the number don't split one case in two. A court
+/// with no PACER id is lowercased with everything but letters and digits
+/// dropped.
+///
+/// ("Bankr. D. Del.", "24-10001") → "deb:24-10001"
+/// ("Delaware", "1:24-bk-10001-KBO") → "deb:24-10001"
+pub fn case_key(court: &str, case_number: &str) -> Option<String> {
+    let court = courts::court_id(court)
+        .unwrap_or_else(|| court.chars().filter(char::is_ascii_alphanumeric).collect());
+    let case_number = match extract::CaseNumber::parse(case_number.trim()) {
+        Some(parsed) => parsed.normalized(),
+        None => case_number.trim().to_lowercase(),
+    };
+    if court.is_empty() || case_number.is_empty() {
+        return None;
+    }
+    Some(format!("{}:{}", court.to_lowercase(), case_number))
+}
+
+fn event_case_key(event: &BankruptcyEvent) -> Option<String> {
+    case_key(event.court.as_deref()?, event.case_number.as_deref()?)
+}
+
+/// What a docket entry says happened to its case, if anything we track.
+///
+/// "Order Granting Motion to Convert Case to Chapter 7"          → ConvertedTo(7)
+/// "Motion to Convert Case to Chapter 7"                          → None
+/// "Motion for Entry of an Order Dismissing the Case"             → None
+/// "Voluntary Petition (Chapter 11)"                              → Filed
+pub fn detect_lifecycle(text: &str) -> Option<LifecycleEvent> {
+    let text = text.to_lowercase();
+    let has = |phrases: &[&str]| phrases.iter().any(|p| text.contains(p));
+
+    // A request changes nothing yet, whatever order it asks for, and
+    // neither does a ruling about when the real ruling will happen. Text
+    // that neither asks nor orders ("Case Dismissed") means what it says.
+    let ruling = match (first_word(&text, RULING_MARKERS), first_word(&text, REQUEST_MARKERS)) {
+        (Some(ruled), Some(asked)) => ruled < asked,
+        (None, Some(_)) => false,
+        (_, None) => true,
+    } && !has(PROCEDURAL_ORDERS);
+
+    if ruling {
+        if has(&["final decree", "order closing case", "case closed", "closing the case"]) {
+            return Some(LifecycleEvent::Closed);
+        }
+        if has(&["dismissing case", "dismissing the case", "dismissing chapter", "case dismissed",
+                 "order of dismissal", "dismissal of case"]) {
+            return Some(LifecycleEvent::Dismissed);
+        }
+        if text.contains("conver") && text.contains("to chapter") {
+            return Some(LifecycleEvent::ConvertedTo(chapter_after(&text, "to chapter")));
+        }
+        if has(&["confirming", "confirmation order", "plan confirmed", "order confirming"])
+            && text.contains("plan")
+        {
+            return Some(LifecycleEvent::PlanConfirmed);
+        }
+        if text.contains("sale")
+            && has(&["363", "free and clear", "approving sale", "approving the sale",
+                     "authorizing sale", "authorizing the sale"])
+            && text.contains("order")
+        {
+            return Some(LifecycleEvent::SaleOrder363);
+        }
+    }
+
+    if has(&["voluntary petition", "involuntary petition"]) {
+        return Some(LifecycleEvent::Filed);
+    }
+    None
+}
+
+/// Where the first of `phrases` to appear in `text` at the start of a word
+/// begins. "Border Freight" doesn't start with an order.
+fn first_word(text: &str, phrases: &[&str]) -> Option<usize> {
+    phrases
+        .iter()
+        .filter_map(|phrase| {
+            text.match_indices(phrase)
+                .map(|(i, _)| i)
+                .find(|&i| !text[..i].chars().next_back().is_some_and(char::is_alphanumeric))
+        })
+        .min()
+}
+
+/// The chapter number right after the last `marker` in `text`.
+fn chapter_after(text: &str, marker: &str) -> BankruptcyChapter {
+    let Some(idx) = text.rfind(marker) else {
+        return BankruptcyChapter::Unknown;
+    };
+    let digits: String = text[idx + marker.len()..]
+        .trim_start()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().map_or(BankruptcyChapter::Unknown, BankruptcyChapter::from_number)
+}
+
+/// Read the store at `path`. `Ok(None)` if there isn't one yet.
+fn load(path: &Path) -> Result<Option<Vec<CaseRecord>>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let store: StoreFile =
+        serde_json::from_slice(&bytes).with_context(|| format!("parsing {}", path.display()))?;
+    anyhow::ensure!(
+        store.version == STORE_VERSION,
+        "case store version {} is not supported (expected {})",
+        store.version,
+        STORE_VERSION
+    );
+    Ok(Some(store.cases))
+}
+
+/// Write every case to `path` via a temp file and a rename, so a crash
+/// mid-save leaves the previous store intact.
+fn write_store(records: &[CaseRecord], path: &Path) -> Result<()> {
+    let bytes = serde_json::to_vec(&serde_json::json!({
+        "version": STORE_VERSION,
+        "cases": records,
+    }))?;
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("creating case store directory {}", dir.display()))?;
+    }
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    let mut file = File::create(&tmp)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Expire old cases and save the store every `interval` until `shutdown`.
+/// The final save is the pipeline's, once the scanners have stopped.
+pub async fn run_saver(cases: Arc<CaseTracker>, interval: Duration, mut shutdown: watch::Receiver<bool>) {
+    let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+    ticker.tick().await;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                cases.expire(Utc::now());
+                let tracker = cases.clone();
+                match tokio::task::spawn_blocking(move || tracker.save()).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => error!(error = format!("{:#}", e), "Failed to save the case store"),
+                    Err(e) => error!(error = %e, "Case store save task failed"),
+                }
+            }
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    fn docket(case_number: &str, chapter: BankruptcyChapter) -> BankruptcyEvent {
+        let mut event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Pacer, 0.8);
+        event.court = Some("Bankr. D. Del.".into());
+        event.case_number = Some(case_number.into());
+        event.chapter = chapter;
+        event
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fde-lifecycle-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detects_rulings_not_requests() {
+        assert_eq!(
+            detect_lifecycle("Order Granting Motion to Convert Case to Chapter 7"),
+            Some(LifecycleEvent::ConvertedTo(BankruptcyChapter::Chapter7))
+        );
+        assert_eq!(detect_lifecycle("Motion to Convert Case to Chapter 7"), None);
+        assert_eq!(detect_lifecycle("Order Scheduling Hearing on Motion to Convert Case to Chapter 7"), None);
+        assert_eq!(detect_lifecycle("Order Dismissing Case"), Some(LifecycleEvent::Dismissed));
+        assert_eq!(detect_lifecycle("Notice of Hearing on Motion to Dismiss Case"), None);
+        assert_eq!(
+            detect_lifecycle("Order Confirming Debtor's Second Amended Chapter 11 Plan"),
+            Some(LifecycleEvent::PlanConfirmed)
+        );
+        assert_eq!(
+            detect_lifecycle("Order (I) Approving the Sale of Substantially All Assets Free and Clear"),
+            Some(LifecycleEvent::SaleOrder363)
+        );
+        assert_eq!(detect_lifecycle("Final Decree Closing Chapter 11 Case"), Some(LifecycleEvent::Closed));
+        assert_eq!(detect_lifecycle("Voluntary Petition (Chapter 11)"), Some(LifecycleEvent::Filed));
+        assert_eq!(detect_lifecycle("Certificate of Service"), None);
+    }
+
+    #[test]
+    fn test_motions_asking_for_orders_are_not_orders() {
+        for text in [
+            "Motion to Convert Case to Chapter 7 Filed by U.S. Trustee (Attachments: # 1 Proposed Order)",
+            "Motion for Entry of an Order Dismissing the Case",
+            "Debtor's Motion for Entry of an Order Confirming the Plan",
+            "Order Denying Motion to Convert Case to Chapter 7",
+            "1:24-bk-10001 Border Freight LLC Motion to Dismiss Case",
+        ] {
+            assert_eq!(detect_lifecycle(text), None, "{}", text);
+        }
+        // PACER puts the case number and debtor ahead of the docket text.
+        assert_eq!(
+            detect_lifecycle("1:24-bk-10001 Acme Freight LLC Order Dismissing Case"),
+            Some(LifecycleEvent::Dismissed)
+        );
+    }
+
+    #[test]
+    fn test_conversion_reads_any_chapter() {
+        assert_eq!(
+            detect_lifecycle("Order Granting Motion to Convert Case to Chapter 12"),
+            Some(LifecycleEvent::ConvertedTo(BankruptcyChapter::Chapter12))
+        );
+    }
+
+    #[test]
+    fn test_known_case_changes_produce_updates_once() {
+        let tracker = CaseTracker::in_memory();
+        let filing = docket("24-10001", BankruptcyChapter::Chapter11);
+        assert!(!tracker.is_known(&filing));
+        assert_eq!(tracker.record(&filing, Some(LifecycleEvent::Filed)), None);
+        assert!(tracker.is_known(&filing));
+
+        let conversion = docket("24-10001", BankruptcyChapter::Unknown);
+        let update = tracker
+            .record(&conversion, Some(LifecycleEvent::ConvertedTo(BankruptcyChapter::Chapter7)))
+            .unwrap();
+        assert_eq!(update.previous_chapter, BankruptcyChapter::Chapter11);
+        assert_eq!(update.chapter, BankruptcyChapter::Chapter7);
+        assert_eq!(update.case_key, "deb:24-10001");
+
+        // The same docket entry again, or one that says nothing new.
+        assert_eq!(tracker.record(&conversion, Some(LifecycleEvent::ConvertedTo(BankruptcyChapter::Chapter7))), None);
+        assert_eq!(tracker.record(&conversion, None), None);
+
+        let closed = tracker.record(&conversion, Some(LifecycleEvent::Closed)).unwrap();
+        assert_eq!(closed.previous_status, CaseStatus::Open);
+        assert_eq!(closed.status, CaseStatus::Closed);
+        assert_eq!(tracker.get("deb:24-10001").unwrap().history.len(), 3);
+        assert_eq!(tracker.snapshot().updates, 2);
+    }
+
+    #[test]
+    fn test_one_case_however_its_number_is_written() {
+        let tracker = CaseTracker::in_memory();
+        tracker.record(&docket("24-10001", BankruptcyChapter::Chapter11), Some(LifecycleEvent::Filed));
+        for spelling in ["1:24-bk-10001", "24-10001-KBO", "1:24-BK-10001-kbo"] {
+            assert!(tracker.is_known(&docket(spelling, BankruptcyChapter::Unknown)), "{}", spelling);
+        }
+        assert!(!tracker.is_known(&docket("24-ap-10001", BankruptcyChapter::Unknown)));
+        assert_eq!(case_key("D. Del.", "1:24-bk-10001-KBO").as_deref(), Some("deb:24-10001"));
+        assert_eq!(case_key("SEC EDGAR", "24-10001").as_deref(), Some("secedgar:24-10001"));
+    }
+
+    #[test]
+    fn test_events_without_case_numbers_are_ignored() {
+        let tracker = CaseTracker::in_memory();
+        let event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Edgar, 0.8);
+        assert_eq!(tracker.record(&event, Some(LifecycleEvent::Dismissed)), None);
+        assert_eq!(tracker.snapshot().cases_tracked, 0);
+    }
+
+    #[test]
+    fn test_cases_survive_a_restart() {
+        let dir = temp_dir("restart");
+        let path = dir.join("cases.json");
+
+        let tracker = CaseTracker::open(&path);
+        tracker.record(&docket("24-10001", BankruptcyChapter::Chapter11), None);
+        tracker.save().unwrap();
+        drop(tracker);
+
+        let tracker = CaseTracker::open(&path);
+        let event = docket("24-10001", BankruptcyChapter::Unknown);
+        assert!(tracker.is_known(&event));
+        assert!(tracker.record(&event, Some(LifecycleEvent::Dismissed)).is_some());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_corrupt_store_is_moved_aside() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join("cases.json");
+        fs::write(&path, b"{ not json").unwrap();
+
+        let tracker = CaseTracker::open(&path);
+        assert_eq!(tracker.snapshot().cases_tracked, 0);
+        assert!(dir.join("cases.json.corrupt").exists());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_recording_leaves_the_disk_to_the_saver() {
+        let dir = temp_dir("dirty");
+        let path = dir.join("cases.json");
+
+        let tracker = CaseTracker::open(&path);
+        tracker.record(&docket("24-10001", BankruptcyChapter::Chapter11), None);
+        assert!(!path.exists());
+
+        tracker.save().unwrap();
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+        tracker.save().unwrap();
+        assert!(!path.exists(), "nothing changed, so nothing to write");
+
+        tracker.record(&docket("24-10002", BankruptcyChapter::Chapter7), None);
+        tracker.save().unwrap();
+        assert_eq!(CaseTracker::open(&path).snapshot().cases_tracked, 2);
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_quiet_closed_and_dismissed_cases_expire() {
+        let tracker = CaseTracker::in_memory().with_retention(Duration::from_secs(30 * 86_400));
+        let now = Utc::now();
+        let days_ago = |days| now - chrono::Duration::days(days);
+
+        let mut closed = docket("24-10001", BankruptcyChapter::Chapter11);
+        closed.detected_at = days_ago(40);
+        tracker.record(&closed, Some(LifecycleEvent::Closed));
+        let mut dismissed = docket("24-10002", BankruptcyChapter::Chapter11);
+        dismissed.detected_at = days_ago(10);
+        tracker.record(&dismissed, Some(LifecycleEvent::Dismissed));
+        let mut open = docket("24-10003", BankruptcyChapter::Chapter11);
+        open.detected_at = days_ago(400);
+        tracker.record(&open, None);
+
+        assert_eq!(tracker.expire(now), 1);
+        assert!(tracker.get("deb:24-10001").is_none());
+        assert!(tracker.get("deb:24-10002").is_some(), "dismissed too recently");
+        assert!(tracker.get("deb:24-10003").is_some(), "open cases stay");
+        assert_eq!(tracker.snapshot().expired, 1);
+    }
+}
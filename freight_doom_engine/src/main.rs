@@ -18,34 +18,24 @@
 // Rust + Tokio + Crossbeam + Bloom Filters + SIMD + Circuit Breakers
 // All to detect when a trucking company files for Chapter 11.
 
-mod config;
-mod models;
-mod scanners;
-mod dedup;
-mod circuit_breaker;
-mod publisher;
-mod text_scanner;
-mod metrics;
-
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{bail, Context};
 use tokio::sync::watch;
 use tokio::signal;
 use tracing::{info, warn, error};
 use tracing_subscriber::{self, EnvFilter, fmt};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+use freight_doom_engine::server;
+use freight_doom_engine::text_scanner::{evaluation, Dictionary, Keywords, RelevanceModel};
+use freight_doom_engine::{Config, Pipeline};
 
-use crate::config::Config;
-use crate::dedup::DedupEngine;
-use crate::models::BankruptcyEvent;
-use crate::publisher::RedisPublisher;
-use crate::metrics::MetricsCollector;
-use crate::scanners::{
-    pacer_scanner,
-    edgar_scanner,
-    fmcsa_scanner,
-    court_listener_scanner,
-};
-
-fn print_banner() {
+const EVAL_USAGE: &str =
+    "usage: freight_doom_engine eval <corpus.jsonl> [--keywords PATH] [--model PATH] [--threshold X]";
+
+fn print_banner(to_stderr: bool) {
     let banner = r#"
 
     ╔══════════════════════════════════════════════════════════════════╗
@@ -77,13 +67,80 @@ fn print_banner() {
     ╚══════════════════════════════════════════════════════════════════╝
 
     "#;
-    println!("{}", banner);
+    if to_stderr {
+        eprintln!("{}", banner);
+    } else {
+        println!("{}", banner);
+    }
+}
+
+/// `freight_doom_engine eval`: grade the text scanner on a labelled corpus
+/// and print the report (see `text_scanner::evaluation`). The keywords,
+/// relevance model and threshold default to whatever the engine would use.
+fn eval(mut args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let config = Config::from_env();
+    let mut corpus = None;
+    let mut keywords_path = config.keywords_path;
+    let mut model_path = config.relevance_model_path;
+    let mut threshold = config.min_confidence_threshold;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().with_context(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--keywords" => keywords_path = Some(PathBuf::from(value("--keywords")?)),
+            "--model" => model_path = Some(PathBuf::from(value("--model")?)),
+            "--threshold" => threshold = value("--threshold")?.parse().context("--threshold")?,
+            flag if flag.starts_with("--") => bail!("unknown option {}", flag),
+            path if corpus.is_none() => corpus = Some(PathBuf::from(path)),
+            _ => bail!(EVAL_USAGE),
+        }
+    }
+    let corpus = corpus.context(EVAL_USAGE)?;
+
+    let keywords = match &keywords_path {
+        Some(path) => Keywords::compile(&Dictionary::load(path)?)?,
+        None => Keywords::builtin(),
+    };
+    let model = model_path.as_deref().map(RelevanceModel::load).transpose()?;
+    let cases = evaluation::read_cases(&corpus)?;
+    let report = evaluation::evaluate(&keywords, model.as_ref(), cases, threshold);
+
+    println!("corpus     {}", corpus.display());
+    println!("keywords   {}", keywords_path.map_or_else(|| "built-in".to_string(), |p| p.display().to_string()));
+    println!("model      {}", model_path.map_or_else(|| "none".to_string(), |p| p.display().to_string()));
+    print!("{}", report);
+    Ok(())
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("eval") {
+        return Ok(match eval(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("freight_doom_engine eval: {:#}", e);
+                ExitCode::FAILURE
+            }
+        });
+    }
+    run()?;
+    Ok(ExitCode::SUCCESS)
 }
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 8)]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration first: if events are going to stdout, logs (and
+    // the banner) have to get out of the way and go to stderr.
+    let config = Config::from_env();
+    let stdout_sink = config.sinks.iter().any(|s| s.eq_ignore_ascii_case("stdout"));
+    let log_writer = if stdout_sink {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
     // Initialize tracing
     fmt()
+        .with_writer(log_writer)
         .with_env_filter(
             EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| EnvFilter::new("info"))
@@ -96,117 +153,51 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_ansi(true)
         .init();
 
-    print_banner();
+    print_banner(stdout_sink);
 
     info!("🚛 FREIGHT DOOM ENGINE initializing...");
-
-    // Load configuration
-    let config = Arc::new(Config::from_env());
     info!("✅ Configuration loaded: redis_url={}", config.redis_url);
-
-    // Lock-free crossbeam channel for events (capacity: 10,000)
-    let (event_tx, event_rx) = crossbeam_channel::bounded::<BankruptcyEvent>(10_000);
-    info!("✅ Lock-free crossbeam channel created (capacity: 10,000)");
-
-    // Deduplication engine: Bloom filter + LRU cache
-    let dedup_engine = Arc::new(DedupEngine::new(
-        config.bloom_expected_items,
-        config.bloom_false_positive_rate,
-        config.lru_cache_size,
-        config.bloom_rotation_interval.as_secs(),
-    ));
-    info!("✅ Deduplication engine online");
-
-    // Metrics collector
-    let metrics_collector = Arc::new(MetricsCollector::new());
-    info!("✅ Metrics collector initialized");
+    let redis_url = config.redis_url.clone();
+    let metrics_addr = config.metrics_addr();
+
+    // Assemble the pipeline: built-in scanners → text scanner →
+    // Bloom + LRU dedup → lock-free crossbeam channel → fan-out to sinks
+    let pipeline = Pipeline::builder(config)
+        .with_builtin_scanners()
+        .build()?;
+    let metrics_collector = pipeline.metrics().clone();
+    let pipeline_config = pipeline.config().clone();
+    let scanner_names = pipeline.scanner_names();
+    let sink_names = pipeline.sink_names().join(", ");
+    let dedup_backend = pipeline.dedup_backend_name().to_string();
+    info!("✅ Pipeline assembled");
 
     // Shutdown signal
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
     // ═══════════════════════════════════════════
-    // SPAWN SCANNERS
+    // SPAWN PIPELINE (SCANNERS + PUBLISHER)
     // ═══════════════════════════════════════════
-
     info!("🚀 Spawning scanner tasks...");
-
-    // PACER Scanner
-    let pacer_config = config.clone();
-    let pacer_tx = event_tx.clone();
-    let pacer_dedup = dedup_engine.clone();
-    let mut pacer_shutdown = shutdown_rx.clone();
-    let pacer_handle = tokio::spawn(async move {
-        info!("📡 PACER Scanner: ONLINE");
-        pacer_scanner::run(pacer_config, pacer_tx, pacer_dedup, &mut pacer_shutdown).await;
-        info!("📡 PACER Scanner: OFFLINE");
-    });
-
-    // SEC EDGAR Scanner
-    let edgar_config = config.clone();
-    let edgar_tx = event_tx.clone();
-    let edgar_dedup = dedup_engine.clone();
-    let mut edgar_shutdown = shutdown_rx.clone();
-    let edgar_handle = tokio::spawn(async move {
-        info!("📡 EDGAR Scanner: ONLINE");
-        edgar_scanner::run(edgar_config, edgar_tx, edgar_dedup, &mut edgar_shutdown).await;
-        info!("📡 EDGAR Scanner: OFFLINE");
-    });
-
-    // FMCSA Scanner
-    let fmcsa_config = config.clone();
-    let fmcsa_tx = event_tx.clone();
-    let fmcsa_dedup = dedup_engine.clone();
-    let mut fmcsa_shutdown = shutdown_rx.clone();
-    let fmcsa_handle = tokio::spawn(async move {
-        info!("📡 FMCSA Scanner: ONLINE");
-        fmcsa_scanner::run(fmcsa_config, fmcsa_tx, fmcsa_dedup, &mut fmcsa_shutdown).await;
-        info!("📡 FMCSA Scanner: OFFLINE");
-    });
-
-    // CourtListener Scanner
-    let cl_config = config.clone();
-    let cl_tx = event_tx.clone();
-    let cl_dedup = dedup_engine.clone();
-    let mut cl_shutdown = shutdown_rx.clone();
-    let cl_handle = tokio::spawn(async move {
-        info!("📡 CourtListener Scanner: ONLINE");
-        court_listener_scanner::run(cl_config, cl_tx, cl_dedup, &mut cl_shutdown).await;
-        info!("📡 CourtListener Scanner: OFFLINE");
-    });
-
-    // Drop our copy of event_tx so publisher knows when all senders are gone
-    drop(event_tx);
-
-    // ═══════════════════════════════════════════
-    // SPAWN REDIS PUBLISHER
-    // ═══════════════════════════════════════════
-    let pub_config = config.clone();
-    let pub_shutdown = shutdown_rx.clone();
-    let (publisher, _pub_stats) = RedisPublisher::new(pub_config, event_rx, pub_shutdown);
-    let publisher_handle = tokio::spawn(async move {
-        info!("📤 Redis Publisher: ONLINE");
-        if let Err(e) = publisher.run().await {
-            error!("📤 Redis Publisher error: {}", e);
-        }
-        info!("📤 Redis Publisher: OFFLINE");
-    });
+    let pipeline_handle = tokio::spawn(pipeline.run(shutdown_rx.clone()));
 
     // ═══════════════════════════════════════════
-    // SPAWN METRICS HTTP SERVER on port 9090
+    // SPAWN METRICS HTTP SERVER
     // ═══════════════════════════════════════════
-    let metrics_for_server = metrics_collector.clone();
     let mut metrics_shutdown = shutdown_rx.clone();
     let metrics_handle = tokio::spawn(async move {
-        info!("📊 Metrics server starting on port 9090...");
-        metrics::run_metrics_server(metrics_for_server, &mut metrics_shutdown).await;
+        info!("📊 Metrics server starting on {}...", pipeline_config.metrics_addr());
+        server::run_metrics_server(&pipeline_config, metrics_collector, &mut metrics_shutdown).await;
         info!("📊 Metrics server: OFFLINE");
     });
 
     info!("═══════════════════════════════════════════════════════");
     info!("  🟢 ALL SYSTEMS ONLINE - FREIGHT DOOM ENGINE ACTIVE");
-    info!("  📡 4 scanners active");
-    info!("  📤 Publishing to Redis at {}", config.redis_url);
-    info!("  📊 Metrics at http://0.0.0.0:9090/metrics");
+    info!("  📡 {} scanners active: {}", scanner_names.len(), scanner_names.join(", "));
+    info!("  📤 Publishing to: {} (redis at {})", sink_names, redis_url);
+    info!("  🧹 Dedup backend: {}", dedup_backend);
+    info!("  📊 Metrics at http://{}/metrics", metrics_addr);
+    info!("  🩺 Health at http://{}/healthz and /readyz", metrics_addr);
     info!("  ⚡ Press Ctrl+C for graceful shutdown");
     info!("═══════════════════════════════════════════════════════");
 
@@ -226,14 +217,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _ = tokio::time::timeout(
         std::time::Duration::from_secs(10),
         async {
-            let _ = tokio::join!(
-                pacer_handle,
-                edgar_handle,
-                fmcsa_handle,
-                cl_handle,
-                publisher_handle,
-                metrics_handle,
-            );
+            let _ = tokio::join!(pipeline_handle, metrics_handle);
         }
     ).await;
 
@@ -3,23 +3,42 @@
 // ═══════════════════════════════════════════════════════════════
 //
 // Atomic counters for everything. Lock-free because we're THAT paranoid
-// about contention. Exposes a tiny HTTP server on port 9090 so
-// the Rails app can check engine health.
+// about contention. Served by the tiny HTTP server in `server.rs` so
+// the Rails app (and Prometheus) can check engine health.
+//
+// Scanners count their events and errors here directly. The dedup engine,
+// the publisher, and every circuit breaker keep their own stats and are
+// registered with the collector, which reads them at snapshot time.
 //
 // This is massive overkill for a metrics system. We have:
 // - Atomic counters (no locks, no mutexes, PURE ATOMICS)
 // - Per-scanner breakdowns
 // - Throughput calculations
-// - A full HTTP server just for metrics
-// - JSON serialization of every metric
+// - Histograms for fetch latency and confidence scores
+// - Prometheus text exposition AND JSON serialization of every metric
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::watch;
-use tracing::{info, error};
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, TimeZone, Utc};
+use crossbeam_channel::Receiver;
+use parking_lot::RwLock;
+use portable_atomic::AtomicF64;
 use serde::Serialize;
 
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerSnapshot, CircuitState};
+use crate::courts;
+use crate::dedup::{DedupEngine, DedupSnapshot};
+use crate::models::{BankruptcyEvent, ScannerHealth, Source};
+use crate::outbox::{OutboxSnapshot, OutboxStats};
+use crate::sinks::{SinkSnapshot, SinkStats};
+use crate::publisher::{PublisherSnapshot, PublisherStats, RedisPublisher};
+use crate::affiliates::{AffiliateGrouper, AffiliateSnapshot};
+use crate::lifecycle::{CaseTracker, CaseTrackerSnapshot};
+use crate::resolution::{EntityResolver, ResolverSnapshot};
+
 /// The metrics snapshot - what gets serialized to JSON
 #[derive(Debug, Serialize, Clone)]
 pub struct MetricsSnapshot {
@@ -40,45 +59,117 @@ pub struct MetricsSnapshot {
     pub bloom_filter_rotations: u64,
     pub redis_publish_failures: u64,
     pub status: String,
+    /// Events waiting in the channel between the scanners and the sink.
+    pub queue_depth: usize,
+    /// Per-scanner counters, keyed by scanner name. Includes any scanner,
+    /// not just the four with their own top-level fields above.
+    pub scanners: BTreeMap<String, ScannerMetricsSnapshot>,
+    pub circuit_breakers: Vec<CircuitBreakerSnapshot>,
+    pub dedup: Option<DedupSnapshot>,
+    pub resolution: Option<ResolverSnapshot>,
+    pub cases: Option<CaseTrackerSnapshot>,
+    pub affiliates: Option<AffiliateSnapshot>,
+    /// Redis publisher stats, keyed by sink name.
+    pub publishers: BTreeMap<String, PublisherSnapshot>,
+    /// Outbox stats, keyed by the name of the sink behind it.
+    pub outboxes: BTreeMap<String, OutboxSnapshot>,
+    /// Per-sink delivery stats, keyed by sink name.
+    pub sinks: BTreeMap<String, SinkSnapshot>,
+}
+
+/// Per-scanner counters in a snapshot
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ScannerMetricsSnapshot {
+    pub events: u64,
+    pub errors: u64,
+}
+
+/// Per-scanner atomic counters
+#[derive(Default)]
+struct ScannerCounters {
+    events: AtomicU64,
+    errors: AtomicU64,
+    running: AtomicBool,
+    /// Unix millis of the last successful fetch. 0 = never.
+    last_success_ms: AtomicI64,
+    /// Filled in when the scanner's driver starts up.
+    registration: Option<ScannerRegistration>,
+}
+
+struct ScannerRegistration {
+    source: Source,
+    poll_interval: Duration,
+    breaker: Arc<CircuitBreaker>,
+    registered_at: DateTime<Utc>,
+}
+
+/// A registered scanner's health, plus what it takes to judge it stale.
+#[derive(Debug, Clone)]
+pub struct ScannerStatus {
+    pub name: String,
+    pub health: ScannerHealth,
+    pub poll_interval: Duration,
+    /// When the scanner was registered. Stands in for `last_poll` until the
+    /// first successful poll.
+    pub registered_at: DateTime<Utc>,
+}
+
+/// How full the channel between the scanners and the sink is.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct QueueDepth {
+    pub depth: usize,
+    pub capacity: Option<usize>,
 }
 
 /// Thread-safe atomic metrics collector
-/// Every counter is atomic because mutexes are for the weak
+/// Every counter is atomic because mutexes are for the weak.
+///
+/// Components that already keep their own stats (the dedup engine, the
+/// Redis publisher, each circuit breaker) are registered here and read at
+/// snapshot time, so there's exactly one place to ask "how's it going?"
 pub struct MetricsCollector {
     total_detected: AtomicU64,
     total_published: AtomicU64,
-    total_deduplicated: AtomicU64,
-    pacer_events: AtomicU64,
-    edgar_events: AtomicU64,
-    fmcsa_events: AtomicU64,
-    court_listener_events: AtomicU64,
-    pacer_errors: AtomicU64,
-    edgar_errors: AtomicU64,
-    fmcsa_errors: AtomicU64,
-    court_listener_errors: AtomicU64,
-    circuit_breaker_trips: AtomicU64,
-    bloom_filter_rotations: AtomicU64,
-    redis_publish_failures: AtomicU64,
+    publish_failures: AtomicU64,
+    scanners: RwLock<BTreeMap<String, ScannerCounters>>,
+    circuit_breakers: RwLock<Vec<Arc<CircuitBreaker>>>,
+    dedup: RwLock<Option<Arc<DedupEngine>>>,
+    resolver: RwLock<Option<Arc<EntityResolver>>>,
+    cases: RwLock<Option<Arc<CaseTracker>>>,
+    affiliates: RwLock<Option<Arc<AffiliateGrouper>>>,
+    publishers: RwLock<BTreeMap<String, Arc<PublisherStats>>>,
+    queue: RwLock<Option<Receiver<BankruptcyEvent>>>,
+    outboxes: RwLock<BTreeMap<String, Arc<OutboxStats>>>,
+    sinks: RwLock<BTreeMap<String, Arc<SinkStats>>>,
+    fetch_latency: HistogramFamily,
+    confidence: HistogramFamily,
     start_time: Instant,
 }
 
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             total_detected: AtomicU64::new(0),
             total_published: AtomicU64::new(0),
-            total_deduplicated: AtomicU64::new(0),
-            pacer_events: AtomicU64::new(0),
-            edgar_events: AtomicU64::new(0),
-            fmcsa_events: AtomicU64::new(0),
-            court_listener_events: AtomicU64::new(0),
-            pacer_errors: AtomicU64::new(0),
-            edgar_errors: AtomicU64::new(0),
-            fmcsa_errors: AtomicU64::new(0),
-            court_listener_errors: AtomicU64::new(0),
-            circuit_breaker_trips: AtomicU64::new(0),
-            bloom_filter_rotations: AtomicU64::new(0),
-            redis_publish_failures: AtomicU64::new(0),
+            publish_failures: AtomicU64::new(0),
+            scanners: RwLock::new(BTreeMap::new()),
+            circuit_breakers: RwLock::new(Vec::new()),
+            dedup: RwLock::new(None),
+            resolver: RwLock::new(None),
+            cases: RwLock::new(None),
+            affiliates: RwLock::new(None),
+            publishers: RwLock::new(BTreeMap::new()),
+            queue: RwLock::new(None),
+            outboxes: RwLock::new(BTreeMap::new()),
+            sinks: RwLock::new(BTreeMap::new()),
+            fetch_latency: HistogramFamily::new(FETCH_LATENCY_BUCKETS),
+            confidence: HistogramFamily::new(CONFIDENCE_BUCKETS),
             start_time: Instant::now(),
         }
     }
@@ -87,47 +178,165 @@ impl MetricsCollector {
         self.total_detected.fetch_add(1, Ordering::Relaxed);
     }
 
-    pub fn increment_published(&self) {
-        self.total_published.fetch_add(1, Ordering::Relaxed);
+    pub fn add_published(&self, count: u64) {
+        self.total_published.fetch_add(count, Ordering::Relaxed);
     }
 
-    pub fn increment_deduplicated(&self) {
-        self.total_deduplicated.fetch_add(1, Ordering::Relaxed);
+    pub fn add_publish_failures(&self, count: u64) {
+        self.publish_failures.fetch_add(count, Ordering::Relaxed);
     }
 
-    pub fn increment_scanner_events(&self, source: &str) {
-        match source {
-            "pacer" => { self.pacer_events.fetch_add(1, Ordering::Relaxed); }
-            "edgar" => { self.edgar_events.fetch_add(1, Ordering::Relaxed); }
-            "fmcsa" => { self.fmcsa_events.fetch_add(1, Ordering::Relaxed); }
-            "court_listener" => { self.court_listener_events.fetch_add(1, Ordering::Relaxed); }
-            _ => {}
-        }
+    pub fn increment_scanner_events(&self, scanner: &str) {
+        self.with_scanner(scanner, |c| { c.events.fetch_add(1, Ordering::Relaxed); });
     }
 
-    pub fn increment_scanner_errors(&self, source: &str) {
-        match source {
-            "pacer" => { self.pacer_errors.fetch_add(1, Ordering::Relaxed); }
-            "edgar" => { self.edgar_errors.fetch_add(1, Ordering::Relaxed); }
-            "fmcsa" => { self.fmcsa_errors.fetch_add(1, Ordering::Relaxed); }
-            "court_listener" => { self.court_listener_errors.fetch_add(1, Ordering::Relaxed); }
-            _ => {}
+    pub fn increment_scanner_errors(&self, scanner: &str) {
+        self.with_scanner(scanner, |c| { c.errors.fetch_add(1, Ordering::Relaxed); });
+    }
+
+    /// Record how long one fetch against a source took, successful or not.
+    pub fn observe_fetch_latency(&self, source: &str, elapsed: Duration) {
+        self.fetch_latency.observe(&[source], elapsed.as_secs_f64());
+    }
+
+    /// Record the confidence score of an event that made it into the channel.
+    /// The court label is the court's PACER id, so every spelling of a court
+    /// shares one series and the label can't grow past the ~90 courts there
+    /// are: `court="none"` without a court, `court="other"` for one that
+    /// isn't a bankruptcy court we know ("SEC EDGAR", FMCSA status text).
+    pub fn observe_confidence(&self, source: &str, court: Option<&str>, confidence: f64) {
+        let court = match court {
+            Some(court) => courts::court_id(court).unwrap_or_else(|| "other".to_string()),
+            None => "none".to_string(),
+        };
+        self.confidence.observe(&[source, &court], confidence);
+    }
+
+    /// Run `f` on a scanner's counters, creating them on first use.
+    fn with_scanner(&self, scanner: &str, f: impl FnOnce(&ScannerCounters)) {
+        if let Some(counters) = self.scanners.read().get(scanner) {
+            f(counters);
+            return;
         }
+        let mut scanners = self.scanners.write();
+        f(scanners.entry(scanner.to_string()).or_default());
+    }
+
+    /// Register a scanner's driver: its source, how often it polls, and its
+    /// circuit breaker. Registered scanners show up in `scanner_statuses`.
+    pub fn register_scanner(
+        &self,
+        scanner: &str,
+        source: Source,
+        poll_interval: Duration,
+        breaker: Arc<CircuitBreaker>,
+    ) {
+        self.register_circuit_breaker(breaker.clone());
+        let mut scanners = self.scanners.write();
+        scanners.entry(scanner.to_string()).or_default().registration = Some(ScannerRegistration {
+            source,
+            poll_interval,
+            breaker,
+            registered_at: Utc::now(),
+        });
+    }
+
+    /// Mark a scanner's driver as running (or not).
+    pub fn set_scanner_running(&self, scanner: &str, running: bool) {
+        self.with_scanner(scanner, |c| c.running.store(running, Ordering::Relaxed));
+    }
+
+    /// A scanner fetched from its source successfully.
+    pub fn record_successful_poll(&self, scanner: &str) {
+        let now = Utc::now().timestamp_millis();
+        self.with_scanner(scanner, |c| c.last_success_ms.store(now, Ordering::Relaxed));
+    }
+
+    /// Health of every registered scanner, in name order.
+    pub fn scanner_statuses(&self) -> Vec<ScannerStatus> {
+        self.scanners
+            .read()
+            .iter()
+            .filter_map(|(name, c)| {
+                let reg = c.registration.as_ref()?;
+                let last_success_ms = c.last_success_ms.load(Ordering::Relaxed);
+                let last_poll = if last_success_ms == 0 {
+                    None
+                } else {
+                    Utc.timestamp_millis_opt(last_success_ms).single()
+                };
+                Some(ScannerStatus {
+                    name: name.clone(),
+                    health: ScannerHealth {
+                        source: reg.source.clone(),
+                        is_running: c.running.load(Ordering::Relaxed),
+                        events_found: c.events.load(Ordering::Relaxed),
+                        errors: c.errors.load(Ordering::Relaxed),
+                        last_poll,
+                        circuit_breaker_state: reg.breaker.state().to_string(),
+                    },
+                    poll_interval: reg.poll_interval,
+                    registered_at: reg.registered_at,
+                })
+            })
+            .collect()
     }
 
-    pub fn increment_circuit_breaker_trips(&self) {
-        self.circuit_breaker_trips.fetch_add(1, Ordering::Relaxed);
+    /// Watch this channel's depth. Holds a receiver handle, but never
+    /// receives from it.
+    pub fn register_queue(&self, receiver: Receiver<BankruptcyEvent>) {
+        *self.queue.write() = Some(receiver);
     }
 
-    pub fn increment_bloom_rotations(&self) {
-        self.bloom_filter_rotations.fetch_add(1, Ordering::Relaxed);
+    /// Current depth of the registered channel, if any.
+    pub fn queue_depth(&self) -> Option<QueueDepth> {
+        self.queue.read().as_ref().map(|rx| QueueDepth {
+            depth: rx.len(),
+            capacity: rx.capacity(),
+        })
     }
 
-    pub fn increment_redis_failures(&self) {
-        self.redis_publish_failures.fetch_add(1, Ordering::Relaxed);
+    /// Report this circuit breaker's state and trips in every snapshot.
+    pub fn register_circuit_breaker(&self, breaker: Arc<CircuitBreaker>) {
+        self.circuit_breakers.write().push(breaker);
     }
 
-    /// Take a snapshot of all metrics (lock-free reads)
+    /// Report this dedup engine's stats in every snapshot.
+    pub fn register_dedup(&self, dedup: Arc<DedupEngine>) {
+        *self.dedup.write() = Some(dedup);
+    }
+
+    /// Report this entity resolver's stats in every snapshot.
+    pub fn register_resolver(&self, resolver: Arc<EntityResolver>) {
+        *self.resolver.write() = Some(resolver);
+    }
+
+    /// Report this case tracker's stats in every snapshot.
+    pub fn register_cases(&self, cases: Arc<CaseTracker>) {
+        *self.cases.write() = Some(cases);
+    }
+
+    /// Report this affiliate grouper's stats in every snapshot.
+    pub fn register_affiliates(&self, affiliates: Arc<AffiliateGrouper>) {
+        *self.affiliates.write() = Some(affiliates);
+    }
+
+    /// Report this sink's Redis publisher stats in every snapshot.
+    pub fn register_publisher(&self, name: &str, stats: Arc<PublisherStats>) {
+        self.publishers.write().insert(name.to_string(), stats);
+    }
+
+    /// Report the stats of the outbox in front of this sink in every snapshot.
+    pub fn register_outbox(&self, name: &str, stats: Arc<OutboxStats>) {
+        self.outboxes.write().insert(name.to_string(), stats);
+    }
+
+    /// Report this sink's delivery stats in every snapshot.
+    pub fn register_sink(&self, name: &str, stats: Arc<SinkStats>) {
+        self.sinks.write().insert(name.to_string(), stats);
+    }
+
+    /// Take a snapshot of all metrics
     pub fn snapshot(&self) -> MetricsSnapshot {
         let uptime = self.start_time.elapsed().as_secs();
         let total_detected = self.total_detected.load(Ordering::Relaxed);
@@ -137,73 +346,482 @@ impl MetricsCollector {
             0.0
         };
 
+        let scanners: BTreeMap<String, ScannerMetricsSnapshot> = self
+            .scanners
+            .read()
+            .iter()
+            .map(|(name, c)| {
+                (
+                    name.clone(),
+                    ScannerMetricsSnapshot {
+                        events: c.events.load(Ordering::Relaxed),
+                        errors: c.errors.load(Ordering::Relaxed),
+                    },
+                )
+            })
+            .collect();
+        let scanner = |name: &str| scanners.get(name).cloned().unwrap_or_default();
+
+        let circuit_breakers: Vec<CircuitBreakerSnapshot> = self
+            .circuit_breakers
+            .read()
+            .iter()
+            .map(|cb| cb.snapshot())
+            .collect();
+        let dedup = self.dedup.read().as_ref().map(|d| d.snapshot());
+        let publishers = self
+            .publishers
+            .read()
+            .iter()
+            .map(|(name, stats)| (name.clone(), RedisPublisher::snapshot(stats)))
+            .collect();
+
         MetricsSnapshot {
             total_events_detected: total_detected,
             total_events_published: self.total_published.load(Ordering::Relaxed),
-            total_events_deduplicated: self.total_deduplicated.load(Ordering::Relaxed),
-            pacer_events: self.pacer_events.load(Ordering::Relaxed),
-            edgar_events: self.edgar_events.load(Ordering::Relaxed),
-            fmcsa_events: self.fmcsa_events.load(Ordering::Relaxed),
-            court_listener_events: self.court_listener_events.load(Ordering::Relaxed),
-            pacer_errors: self.pacer_errors.load(Ordering::Relaxed),
-            edgar_errors: self.edgar_errors.load(Ordering::Relaxed),
-            fmcsa_errors: self.fmcsa_errors.load(Ordering::Relaxed),
-            court_listener_errors: self.court_listener_errors.load(Ordering::Relaxed),
+            total_events_deduplicated: dedup
+                .as_ref()
+                .map_or(0, |d| d.duplicates_caught + d.shared_duplicates),
+            pacer_events: scanner("pacer").events,
+            edgar_events: scanner("edgar").events,
+            fmcsa_events: scanner("fmcsa").events,
+            court_listener_events: scanner("court_listener").events,
+            pacer_errors: scanner("pacer").errors,
+            edgar_errors: scanner("edgar").errors,
+            fmcsa_errors: scanner("fmcsa").errors,
+            court_listener_errors: scanner("court_listener").errors,
             uptime_seconds: uptime,
             events_per_minute,
-            circuit_breaker_trips: self.circuit_breaker_trips.load(Ordering::Relaxed),
-            bloom_filter_rotations: self.bloom_filter_rotations.load(Ordering::Relaxed),
-            redis_publish_failures: self.redis_publish_failures.load(Ordering::Relaxed),
-            status: "operational".to_string(),
+            circuit_breaker_trips: circuit_breakers.iter().map(|cb| cb.total_trips).sum(),
+            bloom_filter_rotations: dedup.as_ref().map_or(0, |d| d.bloom_rotations),
+            redis_publish_failures: self.publish_failures.load(Ordering::Relaxed),
+            status: overall_status(&circuit_breakers).to_string(),
+            queue_depth: self.queue_depth().map_or(0, |q| q.depth),
+            scanners,
+            circuit_breakers,
+            dedup,
+            resolution: self.resolver.read().as_ref().map(|r| r.snapshot()),
+            cases: self.cases.read().as_ref().map(|c| c.snapshot()),
+            affiliates: self.affiliates.read().as_ref().map(|a| a.snapshot()),
+            publishers,
+            outboxes: self
+                .outboxes
+                .read()
+                .iter()
+                .map(|(name, stats)| (name.clone(), stats.snapshot()))
+                .collect(),
+            sinks: self
+                .sinks
+                .read()
+                .iter()
+                .map(|(name, stats)| (name.clone(), stats.snapshot()))
+                .collect(),
+        }
+    }
+}
+
+impl MetricsCollector {
+    /// Render every metric in the Prometheus text exposition format (0.0.4).
+    pub fn render_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut out = String::with_capacity(4096);
+
+        write_metric(&mut out, "freight_doom_events_detected_total", "counter",
+            "Events that passed scoring and dedup and entered the channel.",
+            &[(&[], snapshot.total_events_detected as f64)]);
+        write_metric(&mut out, "freight_doom_events_published_total", "counter",
+            "Events at least one sink accepted.",
+            &[(&[], snapshot.total_events_published as f64)]);
+        write_metric(&mut out, "freight_doom_publish_failures_total", "counter",
+            "Events in delivery attempts a sink rejected, retries included.",
+            &[(&[], snapshot.redis_publish_failures as f64)]);
+
+        let per_scanner = |f: fn(&ScannerMetricsSnapshot) -> u64| -> Vec<(Vec<(&str, &str)>, f64)> {
+            snapshot
+                .scanners
+                .iter()
+                .map(|(name, s)| (vec![("source", name.as_str())], f(s) as f64))
+                .collect()
+        };
+        write_labelled(&mut out, "freight_doom_scanner_events_total", "counter",
+            "Events detected per source.", &per_scanner(|s| s.events));
+        write_labelled(&mut out, "freight_doom_scanner_errors_total", "counter",
+            "Failed fetches per source.", &per_scanner(|s| s.errors));
+
+        let breakers = |f: fn(&CircuitBreakerSnapshot) -> f64| -> Vec<(Vec<(&str, &str)>, f64)> {
+            snapshot
+                .circuit_breakers
+                .iter()
+                .map(|cb| (vec![("breaker", cb.name.as_str())], f(cb)))
+                .collect()
+        };
+        write_labelled(&mut out, "freight_doom_circuit_breaker_trips_total", "counter",
+            "Times each circuit breaker has tripped open.", &breakers(|cb| cb.total_trips as f64));
+        write_labelled(&mut out, "freight_doom_circuit_breaker_state", "gauge",
+            "Circuit breaker state: 0 = closed, 1 = half-open, 2 = open.",
+            &breakers(|cb| match cb.state {
+                CircuitState::Closed => 0.0,
+                CircuitState::HalfOpen => 1.0,
+                CircuitState::Open => 2.0,
+            }));
+
+        if let Some(dedup) = &snapshot.dedup {
+            write_metric(&mut out, "freight_doom_dedup_checks_total", "counter",
+                "Keys checked against the dedup engine.", &[(&[], dedup.total_checks as f64)]);
+            write_metric(&mut out, "freight_doom_dedup_duplicates_total", "counter",
+                "Keys the dedup engine had already seen.", &[(&[], dedup.duplicates_caught as f64)]);
+            write_metric(&mut out, "freight_doom_bloom_rotations_total", "counter",
+                "Bloom filter rotations.", &[(&[], dedup.bloom_rotations as f64)]);
+            write_metric(&mut out, "freight_doom_lru_cache_entries", "gauge",
+                "Entries in the dedup LRU cache.", &[(&[], dedup.lru_cache_size as f64)]);
+            write_metric(&mut out, "freight_doom_dedup_snapshots_saved_total", "counter",
+                "Times the dedup state was saved to disk.", &[(&[], dedup.snapshots_saved as f64)]);
+            write_metric(&mut out, "freight_doom_dedup_ttl_expirations_total", "counter",
+                "Keys let through again because their dedup TTL had passed.",
+                &[(&[], dedup.ttl_expirations as f64)]);
+            write_metric(&mut out, "freight_doom_dedup_shared_duplicates_total", "counter",
+                "Keys another engine had already claimed in the shared backend.",
+                &[(&[], dedup.shared_duplicates as f64)]);
+            write_metric(&mut out, "freight_doom_dedup_shared_errors_total", "counter",
+                "Times the shared dedup backend was unreachable and we published anyway.",
+                &[(&[], dedup.shared_errors as f64)]);
+        }
+
+        if let Some(resolution) = &snapshot.resolution {
+            write_metric(&mut out, "freight_doom_resolution_cases_opened_total", "counter",
+                "Events that matched no known case and opened a new one.",
+                &[(&[], resolution.cases_opened as f64)]);
+            write_metric(&mut out, "freight_doom_resolution_corroborations_total", "counter",
+                "Events that joined a case first reported by a different source.",
+                &[(&[], resolution.corroborations as f64)]);
+            write_metric(&mut out, "freight_doom_resolution_cases_tracked", "gauge",
+                "Open cases held for cross-source matching.",
+                &[(&[], resolution.cases_tracked as f64)]);
+        }
+
+        if let Some(cases) = &snapshot.cases {
+            write_metric(&mut out, "freight_doom_cases_tracked", "gauge",
+                "Court cases with a lifecycle record.",
+                &[(&[], cases.cases_tracked as f64)]);
+            write_metric(&mut out, "freight_doom_case_updates_total", "counter",
+                "Conversions, dismissals, confirmations, sales and closings seen on known cases.",
+                &[(&[], cases.updates as f64)]);
+            write_metric(&mut out, "freight_doom_cases_expired_total", "counter",
+                "Closed and dismissed cases dropped after the retention period.",
+                &[(&[], cases.expired as f64)]);
+            write_metric(&mut out, "freight_doom_case_store_save_errors_total", "counter",
+                "Failed writes of the case store.",
+                &[(&[], cases.save_errors as f64)]);
         }
+
+        if let Some(affiliates) = &snapshot.affiliates {
+            write_metric(&mut out, "freight_doom_affiliate_groups_open", "gauge",
+                "Jointly administered families still being collected.",
+                &[(&[], affiliates.groups_open as f64)]);
+            write_metric(&mut out, "freight_doom_affiliate_groups_published_total", "counter",
+                "Jointly administered families published as one event.",
+                &[(&[], affiliates.groups_published as f64)]);
+            write_metric(&mut out, "freight_doom_affiliate_filings_grouped_total", "counter",
+                "Filings folded into a jointly administered family.",
+                &[(&[], affiliates.filings_grouped as f64)]);
+        }
+
+        write_metric(&mut out, "freight_doom_queue_depth", "gauge",
+            "Events waiting in the channel between scanners and sink.",
+            &[(&[], snapshot.queue_depth as f64)]);
+        let connected: Vec<(Vec<(&str, &str)>, f64)> = snapshot
+            .publishers
+            .iter()
+            .map(|(name, p)| (vec![("sink", name.as_str())], if p.connected { 1.0 } else { 0.0 }))
+            .collect();
+        if !connected.is_empty() {
+            write_labelled(&mut out, "freight_doom_redis_connected", "gauge",
+                "1 if the sink's Redis connection's last operation succeeded.", &connected);
+        }
+
+        let per_sink = |f: fn(&SinkSnapshot) -> u64| -> Vec<(Vec<(&str, &str)>, f64)> {
+            snapshot
+                .sinks
+                .iter()
+                .map(|(name, s)| (vec![("sink", name.as_str())], f(s) as f64))
+                .collect()
+        };
+        write_labelled(&mut out, "freight_doom_sink_events_delivered_total", "counter",
+            "Events each sink accepted.", &per_sink(|s| s.events_delivered));
+        write_labelled(&mut out, "freight_doom_sink_failed_attempts_total", "counter",
+            "Failed delivery attempts per sink.", &per_sink(|s| s.failed_attempts));
+        write_labelled(&mut out, "freight_doom_sink_events_dropped_total", "counter",
+            "Events dropped because a sink's backlog was full.", &per_sink(|s| s.events_dropped));
+        write_labelled(&mut out, "freight_doom_sink_backlog", "gauge",
+            "Events waiting for a retry, per sink.", &per_sink(|s| s.backlog));
+
+        if !snapshot.outboxes.is_empty() {
+            let per_outbox = |f: fn(&OutboxSnapshot) -> u64| -> Vec<(Vec<(&str, &str)>, f64)> {
+                snapshot
+                    .outboxes
+                    .iter()
+                    .map(|(name, o)| (vec![("sink", name.as_str())], f(o) as f64))
+                    .collect()
+            };
+            write_labelled(&mut out, "freight_doom_outbox_pending_events", "gauge",
+                "Events on disk waiting for delivery, per sink.", &per_outbox(|o| o.events_pending));
+            write_labelled(&mut out, "freight_doom_outbox_delivered_total", "counter",
+                "Events delivered from the outbox, per sink.", &per_outbox(|o| o.events_delivered));
+            write_labelled(&mut out, "freight_doom_outbox_corrupt_records_total", "counter",
+                "Outbox records skipped for failing their checksum, per sink.",
+                &per_outbox(|o| o.corrupt_records));
+        }
+
+        write_metric(&mut out, "freight_doom_uptime_seconds", "gauge",
+            "Seconds since the engine started.", &[(&[], snapshot.uptime_seconds as f64)]);
+        write_metric(&mut out, "freight_doom_events_per_minute", "gauge",
+            "Average detections per minute since start.", &[(&[], snapshot.events_per_minute)]);
+
+        self.fetch_latency.render(&mut out, "freight_doom_fetch_duration_seconds",
+            "Time taken by each fetch, by source.", &["source"]);
+        self.confidence.render(&mut out, "freight_doom_event_confidence",
+            "Confidence score of detected events, by source and court.", &["source", "court"]);
+
+        out
     }
 }
 
-/// Run a tiny HTTP server on port 9090 that serves metrics as JSON
-/// This is the Rust equivalent of mounting a turret on a skateboard
-pub async fn run_metrics_server(
-    metrics: Arc<MetricsCollector>,
-    shutdown: &mut watch::Receiver<bool>,
-) {
-    use tokio::net::TcpListener;
-    use tokio::io::AsyncWriteExt;
+/// Fetch latency buckets in seconds. Court RSS feeds are not known for speed.
+const FETCH_LATENCY_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0];
+
+/// Confidence score buckets. Confidence lives in [0, 1].
+const CONFIDENCE_BUCKETS: &[f64] = &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+
+/// One histogram series: per-bucket counts (not cumulative), plus sum and count.
+struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum: AtomicF64,
+}
+
+/// A histogram metric with one series per distinct set of label values.
+struct HistogramFamily {
+    bounds: &'static [f64],
+    series: RwLock<BTreeMap<Vec<String>, Histogram>>,
+}
+
+impl HistogramFamily {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            series: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    fn observe(&self, labels: &[&str], value: f64) {
+        let record = |h: &Histogram| {
+            if let Some(i) = self.bounds.iter().position(|b| value <= *b) {
+                h.buckets[i].fetch_add(1, Ordering::Relaxed);
+            }
+            h.count.fetch_add(1, Ordering::Relaxed);
+            h.sum.fetch_add(value, Ordering::Relaxed);
+        };
 
-    let listener = match TcpListener::bind("0.0.0.0:9090").await {
-        Ok(l) => l,
-        Err(e) => {
-            error!("Failed to bind metrics server on :9090: {}", e);
+        let key: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        if let Some(h) = self.series.read().get(&key) {
+            record(h);
             return;
         }
-    };
-
-    info!("📊 Metrics server listening on http://0.0.0.0:9090");
-
-    loop {
-        tokio::select! {
-            accept_result = listener.accept() => {
-                match accept_result {
-                    Ok((mut stream, _addr)) => {
-                        let snapshot = metrics.snapshot();
-                        let json = serde_json::to_string_pretty(&snapshot)
-                            .unwrap_or_else(|_| "{}".to_string());
-
-                        let response = format!(
-                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                            json.len(),
-                            json,
-                        );
-
-                        let _ = stream.write_all(response.as_bytes()).await;
-                    }
-                    Err(e) => {
-                        error!("Metrics server accept error: {}", e);
-                    }
-                }
+        let mut series = self.series.write();
+        let h = series.entry(key).or_insert_with(|| Histogram {
+            buckets: self.bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicF64::new(0.0),
+        });
+        record(h);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str, label_names: &[&str]) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        let les: Vec<String> = self.bounds.iter().map(|b| b.to_string()).collect();
+        for (values, h) in self.series.read().iter() {
+            let mut labels: Vec<(&str, &str)> = label_names
+                .iter()
+                .copied()
+                .zip(values.iter().map(String::as_str))
+                .collect();
+
+            let mut cumulative = 0;
+            for (le, bucket) in les.iter().zip(&h.buckets) {
+                cumulative += bucket.load(Ordering::Relaxed);
+                labels.push(("le", le));
+                write_sample(out, &format!("{name}_bucket"), &labels, cumulative as f64);
+                labels.pop();
             }
-            _ = shutdown.changed() => {
-                info!("Metrics server: shutting down");
-                break;
+            let count = h.count.load(Ordering::Relaxed);
+            labels.push(("le", "+Inf"));
+            write_sample(out, &format!("{name}_bucket"), &labels, count as f64);
+            labels.pop();
+            write_sample(out, &format!("{name}_sum"), &labels, h.sum.load(Ordering::Relaxed));
+            write_sample(out, &format!("{name}_count"), &labels, count as f64);
+        }
+    }
+}
+
+/// Write a metric's HELP/TYPE header and one sample per label set.
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&[(&str, &str)], f64)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (labels, value) in samples {
+        write_sample(out, name, labels, *value);
+    }
+}
+
+/// `write_metric` for samples whose label sets are built at runtime.
+fn write_labelled(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(Vec<(&str, &str)>, f64)]) {
+    let borrowed: Vec<(&[(&str, &str)], f64)> =
+        samples.iter().map(|(labels, v)| (labels.as_slice(), *v)).collect();
+    write_metric(out, name, kind, help, &borrowed);
+}
+
+fn write_sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        out.push('{');
+        for (i, (key, val)) in labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
             }
+            let _ = write!(out, "{key}=\"{}\"", escape_label(val));
         }
+        out.push('}');
+    }
+    let _ = writeln!(out, " {value}");
+}
+
+/// Escape a label value per the exposition format: backslash, quote, newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// "operational" when every breaker is closed (or half-open and testing),
+/// "degraded" when some sources are tripped, "critical" when all of them are.
+fn overall_status(breakers: &[CircuitBreakerSnapshot]) -> &'static str {
+    let open = breakers.iter().filter(|cb| cb.state == CircuitState::Open).count();
+    if open == 0 {
+        "operational"
+    } else if open == breakers.len() {
+        "critical"
+    } else {
+        "degraded"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_snapshot_reads_registered_components() {
+        let metrics = MetricsCollector::new();
+        let dedup = Arc::new(DedupEngine::new(1000, 0.01, 100, 3600));
+        metrics.register_dedup(dedup.clone());
+
+        assert!(dedup.check_and_insert("pacer:deb:1"));
+        assert!(!dedup.check_and_insert("pacer:deb:1"));
+        metrics.increment_detected();
+        metrics.increment_scanner_events("pacer");
+        metrics.increment_scanner_errors("edgar");
+        metrics.increment_scanner_events("state_court");
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_events_detected, 1);
+        assert_eq!(snapshot.total_events_deduplicated, 1);
+        assert_eq!(snapshot.pacer_events, 1);
+        assert_eq!(snapshot.edgar_errors, 1);
+        assert_eq!(snapshot.scanners["state_court"].events, 1);
+    }
+
+    #[test]
+    fn test_status_reflects_circuit_breakers() {
+        let metrics = MetricsCollector::new();
+        let pacer = Arc::new(CircuitBreaker::new("PACER", 1, Duration::from_secs(60), 1));
+        let edgar = Arc::new(CircuitBreaker::new("SEC_EDGAR", 1, Duration::from_secs(60), 1));
+        metrics.register_circuit_breaker(pacer.clone());
+        metrics.register_circuit_breaker(edgar.clone());
+        assert_eq!(metrics.snapshot().status, "operational");
+
+        pacer.record_failure();
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.status, "degraded");
+        assert_eq!(snapshot.circuit_breaker_trips, 1);
+
+        edgar.record_failure();
+        assert_eq!(metrics.snapshot().status, "critical");
+    }
+
+    #[test]
+    fn test_scanner_statuses_track_registered_scanners() {
+        let metrics = MetricsCollector::new();
+        let breaker = Arc::new(CircuitBreaker::new("PACER", 3, Duration::from_secs(60), 1));
+        metrics.register_scanner("pacer", Source::Pacer, Duration::from_secs(120), breaker);
+        metrics.increment_scanner_events("unregistered");
+
+        let statuses = metrics.scanner_statuses();
+        assert_eq!(statuses.len(), 1);
+        assert!(!statuses[0].health.is_running);
+        assert!(statuses[0].health.last_poll.is_none());
+        assert_eq!(statuses[0].health.circuit_breaker_state, "CLOSED");
+
+        metrics.set_scanner_running("pacer", true);
+        metrics.record_successful_poll("pacer");
+        metrics.increment_scanner_events("pacer");
+        let health = &metrics.scanner_statuses()[0].health;
+        assert!(health.is_running);
+        assert!(health.last_poll.is_some());
+        assert_eq!(health.events_found, 1);
+    }
+
+    #[test]
+    fn test_queue_depth_reads_registered_channel() {
+        let metrics = MetricsCollector::new();
+        assert!(metrics.queue_depth().is_none());
+
+        let (tx, rx) = crossbeam_channel::bounded(8);
+        metrics.register_queue(rx);
+        tx.send(BankruptcyEvent::new("Acme Freight".into(), Source::Pacer, 0.9)).unwrap();
+
+        let queue = metrics.queue_depth().unwrap();
+        assert_eq!((queue.depth, queue.capacity), (1, Some(8)));
+        assert_eq!(metrics.snapshot().queue_depth, 1);
+    }
+
+    #[test]
+    fn test_prometheus_output_includes_labelled_histograms() {
+        let metrics = MetricsCollector::new();
+        metrics.increment_detected();
+        metrics.increment_scanner_events("pacer");
+        metrics.observe_fetch_latency("pacer", Duration::from_millis(300));
+        metrics.observe_confidence("pacer", Some("txnb"), 0.85);
+        metrics.observe_confidence("edgar", None, 0.42);
+        metrics.observe_confidence("court_listener", Some("United States Bankruptcy Court for the Northern District of Texas"), 0.9);
+        metrics.observe_confidence("fmcsa", Some("FMCSA — Status: INACTIVE | INSURANCE LAPSED"), 0.6);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("# TYPE freight_doom_events_detected_total counter"));
+        assert!(text.contains("freight_doom_events_detected_total 1\n"));
+        assert!(text.contains("freight_doom_scanner_events_total{source=\"pacer\"} 1\n"));
+        assert!(text.contains("freight_doom_fetch_duration_seconds_bucket{source=\"pacer\",le=\"0.25\"} 0\n"));
+        assert!(text.contains("freight_doom_fetch_duration_seconds_bucket{source=\"pacer\",le=\"0.5\"} 1\n"));
+        assert!(text.contains("freight_doom_event_confidence_bucket{source=\"pacer\",court=\"txnb\",le=\"+Inf\"} 1\n"));
+        assert!(text.contains("freight_doom_event_confidence_count{source=\"court_listener\",court=\"txnb\"} 1\n"));
+        assert!(text.contains("freight_doom_event_confidence_count{source=\"fmcsa\",court=\"other\"} 1\n"));
+        assert!(text.contains("freight_doom_event_confidence_count{source=\"edgar\",court=\"none\"} 1\n"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label("line\nbreak"), "line\\nbreak");
     }
 }
@@ -55,37 +55,229 @@ impl fmt::Display for Source {
 
 /// The type of bankruptcy chapter filed.
 /// Because not all financial doom is created equal.
+///
+/// The serialized names of the original four variants ("Chapter7",
+/// "Chapter11", "Chapter13", "Unknown") are part of the Rails contract and
+/// must not change. New variants follow the same pattern.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum BankruptcyChapter {
     /// Chapter 7 — Liquidation. The "sell everything including the office chairs" option.
     /// For freight companies, this means the trucks are getting auctioned off.
     Chapter7,
 
+    /// Chapter 9 — Municipalities. Not a trucking company, but the city that
+    /// owes the trucking company money might be.
+    Chapter9,
+
     /// Chapter 11 — Reorganization. The "we can fix this, we swear" option.
     /// For freight companies, this means they'll try to restructure while
     /// their drivers wonder if they should update their resumes.
     Chapter11,
 
+    /// Chapter 11 under Subchapter V — the small-business fast lane. No
+    /// creditors' committee, no disclosure statement, and a lot of
+    /// ten-truck carriers.
+    Chapter11SubchapterV,
+
+    /// Chapter 12 — Family farmers and fishermen. Occasionally the one with
+    /// the grain trucks.
+    Chapter12,
+
     /// Chapter 13 — Individual debt adjustment (rare for companies but possible
     /// for owner-operators who are technically sole proprietors)
     Chapter13,
 
+    /// Chapter 15 — Recognition of a foreign proceeding. How a Canadian or
+    /// Mexican carrier's insolvency shows up in a US court.
+    Chapter15,
+
     /// We found a bankruptcy filing but couldn't determine the chapter.
     /// This happens more often than you'd think with government data.
     Unknown,
 }
 
+impl BankruptcyChapter {
+    /// The chapter of the Bankruptcy Code, if known. Subchapter V is still
+    /// Chapter 11.
+    pub fn number(&self) -> Option<u8> {
+        match self {
+            BankruptcyChapter::Chapter7 => Some(7),
+            BankruptcyChapter::Chapter9 => Some(9),
+            BankruptcyChapter::Chapter11 | BankruptcyChapter::Chapter11SubchapterV => Some(11),
+            BankruptcyChapter::Chapter12 => Some(12),
+            BankruptcyChapter::Chapter13 => Some(13),
+            BankruptcyChapter::Chapter15 => Some(15),
+            BankruptcyChapter::Unknown => None,
+        }
+    }
+
+    /// The chapter for a chapter number, or `Unknown` for one we don't track.
+    pub fn from_number(number: u32) -> Self {
+        match number {
+            7 => BankruptcyChapter::Chapter7,
+            9 => BankruptcyChapter::Chapter9,
+            11 => BankruptcyChapter::Chapter11,
+            12 => BankruptcyChapter::Chapter12,
+            13 => BankruptcyChapter::Chapter13,
+            15 => BankruptcyChapter::Chapter15,
+            _ => BankruptcyChapter::Unknown,
+        }
+    }
+}
+
 impl fmt::Display for BankruptcyChapter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             BankruptcyChapter::Chapter7 => write!(f, "Chapter 7"),
+            BankruptcyChapter::Chapter9 => write!(f, "Chapter 9"),
             BankruptcyChapter::Chapter11 => write!(f, "Chapter 11"),
+            BankruptcyChapter::Chapter11SubchapterV => write!(f, "Chapter 11 (Subchapter V)"),
+            BankruptcyChapter::Chapter12 => write!(f, "Chapter 12"),
             BankruptcyChapter::Chapter13 => write!(f, "Chapter 13"),
+            BankruptcyChapter::Chapter15 => write!(f, "Chapter 15"),
             BankruptcyChapter::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+/// Who started the case. A voluntary petition is the company giving up;
+/// an involuntary one is its creditors giving up on it.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PetitionType {
+    Voluntary,
+    Involuntary,
+    /// The text didn't say. Events from before this field existed
+    /// deserialize as this.
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for PetitionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PetitionType::Voluntary => write!(f, "Voluntary"),
+            PetitionType::Involuntary => write!(f, "Involuntary"),
+            PetitionType::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// What a detected event is telling you.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EventType {
+    /// A bankruptcy we hadn't seen before (or a new source confirming one).
+    #[default]
+    Detection,
+    /// A case we already know about changed state. `case_update` says how.
+    CaseUpdate,
+}
+
+/// Something that happens to a bankruptcy case after the petition. For a
+/// shipper these matter more than the filing itself: a conversion to
+/// Chapter 7 means the trucks stop, a 363 sale means someone else owns your
+/// freight contract now.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "chapter")]
+pub enum LifecycleEvent {
+    /// The petition itself.
+    Filed,
+    /// The case moved to another chapter, almost always 11 → 7.
+    ConvertedTo(BankruptcyChapter),
+    /// The court threw the case out. The debtor is on its own again.
+    Dismissed,
+    /// A plan of reorganization or liquidation was confirmed.
+    PlanConfirmed,
+    /// The court approved a sale of assets under 11 U.S.C. § 363.
+    SaleOrder363,
+    /// Final decree. The case is over.
+    Closed,
+    /// Affiliates filed under the case after its own petition went out,
+    /// and are jointly administered with it. The event's `affiliates`
+    /// lists them. Unlike the others, this can happen more than once.
+    AffiliatesJoined,
+}
+
+impl fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecycleEvent::Filed => write!(f, "Filed"),
+            LifecycleEvent::ConvertedTo(chapter) => write!(f, "Converted to {}", chapter),
+            LifecycleEvent::Dismissed => write!(f, "Dismissed"),
+            LifecycleEvent::PlanConfirmed => write!(f, "Plan confirmed"),
+            LifecycleEvent::SaleOrder363 => write!(f, "363 sale order"),
+            LifecycleEvent::Closed => write!(f, "Closed"),
+            LifecycleEvent::AffiliatesJoined => write!(f, "Affiliates joined"),
+        }
+    }
+}
+
+/// Where a case stands right now.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CaseStatus {
+    /// Pending, in whatever chapter it's in now.
+    Open,
+    /// A plan was confirmed; the case is still open until the final decree.
+    PlanConfirmed,
+    Dismissed,
+    Closed,
+}
+
+impl fmt::Display for CaseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaseStatus::Open => write!(f, "Open"),
+            CaseStatus::PlanConfirmed => write!(f, "Plan confirmed"),
+            CaseStatus::Dismissed => write!(f, "Dismissed"),
+            CaseStatus::Closed => write!(f, "Closed"),
+        }
+    }
+}
+
+/// How a known case changed. Carried by events with
+/// `event_type: CaseUpdate`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaseUpdate {
+    /// The court + case number key the case is tracked under.
+    pub case_key: String,
+    pub lifecycle_event: LifecycleEvent,
+    pub previous_status: CaseStatus,
+    pub status: CaseStatus,
+    pub previous_chapter: BankruptcyChapter,
+    /// The chapter the case is in after this update.
+    pub chapter: BankruptcyChapter,
+}
+
+/// One debtor jointly administered under another's case. Carried in the
+/// lead debtor's event, so twenty subsidiaries are one event, not twenty.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AffiliateDebtor {
+    pub company_name: String,
+    pub case_number: Option<String>,
+}
+
+/// One feature's share of an event's confidence score: "cross_domain added
+/// 0.2 because it says both 'trucking' and 'chapter 11'". An event's
+/// contributions add up to its `confidence_score`, so the dashboard can
+/// show why we flagged it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScoreContribution {
+    /// `keyword_variety`, `keyword_density`, `cross_domain`, `high_signal`,
+    /// a context kind (`negated`, `hypothetical`, `creditor_role`), `cap`,
+    /// `source` or `corroboration`.
+    pub feature: String,
+    /// The feature's raw value, before weighting. 1.0 for features that
+    /// either apply or don't.
+    pub value: f64,
+    /// The scoring model's weight for the feature; a multiplier for
+    /// context features.
+    pub weight: f64,
+    /// Points added to the score. Negative when the feature lowered it.
+    pub points: f64,
+    /// The keywords, cue words or sources behind it.
+    #[serde(default)]
+    pub evidence: Vec<String>,
+}
+
 /// The classification of the logistics company.
 /// Because "freight company" is about as specific as "food" at a restaurant.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -101,6 +293,23 @@ pub enum CompanyClassification {
     /// Freight forwarder — the international ones who deal with customs
     /// and make sure your container doesn't end up in the wrong ocean
     FreightForwarder,
+    /// Less-than-truckload carrier — terminals, pallets, and a network
+    /// that only works if every other terminal in it is still open
+    Ltl,
+    /// Truckload carrier — one shipper, one trailer, dock to dock
+    Truckload,
+    /// Drayage — hauling containers between the port or the rail ramp
+    /// and somewhere a few miles away, for margins measured in cents
+    Drayage,
+    /// Intermodal — containers that ride a train most of the way
+    Intermodal,
+    /// Last-mile delivery — vans, doorsteps, and the parcel on your porch
+    LastMile,
+    /// Freight-tech platform — a load board with a Series C
+    FreightTech,
+    /// Shipper or manufacturer — the ones whose freight it is. When they
+    /// go under, the carriers hauling for them find out on the invoice.
+    Shipper,
     /// Could be any of the above. The filing didn't specify.
     Unclassified,
 }
@@ -112,17 +321,36 @@ impl fmt::Display for CompanyClassification {
             CompanyClassification::Broker => write!(f, "Broker"),
             CompanyClassification::ThirdPartyLogistics => write!(f, "3PL"),
             CompanyClassification::FreightForwarder => write!(f, "Freight Forwarder"),
+            CompanyClassification::Ltl => write!(f, "LTL"),
+            CompanyClassification::Truckload => write!(f, "Truckload"),
+            CompanyClassification::Drayage => write!(f, "Drayage"),
+            CompanyClassification::Intermodal => write!(f, "Intermodal"),
+            CompanyClassification::LastMile => write!(f, "Last Mile"),
+            CompanyClassification::FreightTech => write!(f, "Freight Tech"),
+            CompanyClassification::Shipper => write!(f, "Shipper"),
             CompanyClassification::Unclassified => write!(f, "Unclassified"),
         }
     }
 }
 
+/// One kind of company an event might be about, and how sure we are.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClassScore {
+    pub classification: CompanyClassification,
+    /// From 0.0 to 1.0. Each class is scored on its own, so a carrier with
+    /// a brokerage arm can be 0.9 of both.
+    pub score: f64,
+    /// The keywords or registry fields behind it.
+    #[serde(default)]
+    pub evidence: Vec<String>,
+}
+
 /// The main event struct. This is what gets published to Redis and consumed
 /// by the Rails app. Every field here represents a piece of the puzzle
 /// in our quest to detect freight company bankruptcy before the trucks
 /// even finish their last delivery.
 ///
-/// Is having 12 fields on a bankruptcy event struct overkill?
+/// Is having 18 fields on a bankruptcy event struct overkill?
 /// The answer is no. We could easily justify 30.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BankruptcyEvent {
@@ -152,9 +380,30 @@ pub struct BankruptcyEvent {
     /// will be something like "S.D.N.Y." or "N.D. Ill."
     pub court: Option<String>,
 
-    /// Chapter 7, 11, 13, or "we have no idea but something bad happened"
+    /// The court's docket number, e.g. "24-10001" or "2:24-bk-10001".
+    /// Together with `court` it's the closest thing a bankruptcy has to
+    /// a primary key.
+    #[serde(default)]
+    pub case_number: Option<String>,
+
+    /// The case this one is jointly administered under, when the filing
+    /// says so. For the lead debtor it's its own case number.
+    #[serde(default)]
+    pub lead_case_number: Option<String>,
+
+    /// The other debtors jointly administered with this one, for events
+    /// that stand for a whole family of filings. Empty for everything else.
+    #[serde(default)]
+    pub affiliates: Vec<AffiliateDebtor>,
+
+    /// Chapter 7, 11, 13, 15, ..., or "we have no idea but something bad
+    /// happened"
     pub chapter: BankruptcyChapter,
 
+    /// Voluntary or involuntary, when the text says which.
+    #[serde(default)]
+    pub petition_type: PetitionType,
+
     /// Where we found this information. See the Source enum above
     /// for the full existential breakdown.
     pub source: Source,
@@ -173,12 +422,42 @@ pub struct BankruptcyEvent {
     pub confidence_score: f64,
 
     /// What type of logistics company this is.
-    /// Carrier, broker, 3PL, freight forwarder, or "beats me"
+    /// Carrier, broker, 3PL, freight forwarder, or "beats me" — the most
+    /// likely of `classifications`.
     pub classification: CompanyClassification,
 
+    /// Every type of company this might be, most likely first, with
+    /// scores. Empty when nothing says.
+    #[serde(default)]
+    pub classifications: Vec<ClassScore>,
+
+    /// Why `confidence_score` is what it is, feature by feature. The
+    /// points add up to the score. Empty for lifecycle updates.
+    #[serde(default)]
+    pub score_breakdown: Vec<ScoreContribution>,
+
     /// The raw URL where we found this filing, so humans can verify
     /// that our robot overlord didn't hallucinate a bankruptcy.
     pub source_url: Option<String>,
+
+    /// The canonical case this event belongs to, shared by every source
+    /// that reported the same bankruptcy. It's the `id` of whichever event
+    /// got there first. Set by the entity resolver.
+    #[serde(default)]
+    pub case_id: Option<String>,
+
+    /// Every source that has reported this case so far, this one included,
+    /// in the order they showed up.
+    #[serde(default)]
+    pub corroborating_sources: Vec<Source>,
+
+    /// A fresh detection, or an update to a case we already reported.
+    #[serde(default)]
+    pub event_type: EventType,
+
+    /// What changed, for `EventType::CaseUpdate` events.
+    #[serde(default)]
+    pub case_update: Option<CaseUpdate>,
 }
 
 impl BankruptcyEvent {
@@ -196,27 +475,24 @@ impl BankruptcyEvent {
             mc_number: None,
             filing_date: None,
             court: None,
+            case_number: None,
+            lead_case_number: None,
+            affiliates: Vec::new(),
             chapter: BankruptcyChapter::Unknown,
+            petition_type: PetitionType::Unknown,
             source,
             detected_at: Utc::now(),
             confidence_score,
             classification: CompanyClassification::Unclassified,
+            classifications: Vec::new(),
+            score_breakdown: Vec::new(),
             source_url: None,
+            case_id: None,
+            corroborating_sources: Vec::new(),
+            event_type: EventType::Detection,
+            case_update: None,
         }
     }
-
-    /// Generate a deduplication key for this event.
-    /// We combine company name + source to create a unique-ish key.
-    /// The bloom filter and LRU cache will use this to decide if we've
-    /// already screamed about this particular bankruptcy into the Redis void.
-    pub fn dedup_key(&self) -> String {
-        format!(
-            "{}:{}:{}",
-            self.company_name.to_lowercase().trim(),
-            self.source,
-            self.chapter
-        )
-    }
 }
 
 impl fmt::Display for BankruptcyEvent {
@@ -331,7 +607,37 @@ pub struct CourtListenerOpinion {
     pub id: Option<u64>,
     pub case_name: Option<String>,
     pub court: Option<String>,
+    #[serde(default, alias = "docketNumber")]
+    pub docket_number: Option<String>,
     pub date_filed: Option<String>,
     pub snippet: Option<String>,
     pub absolute_url: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chapter_names_on_the_wire_are_stable() {
+        // Rails has been reading these strings since day one.
+        assert_eq!(serde_json::to_string(&BankruptcyChapter::Chapter7).unwrap(), "\"Chapter7\"");
+        assert_eq!(serde_json::to_string(&BankruptcyChapter::Chapter11).unwrap(), "\"Chapter11\"");
+        assert_eq!(serde_json::to_string(&BankruptcyChapter::Unknown).unwrap(), "\"Unknown\"");
+        assert_eq!(
+            serde_json::to_string(&BankruptcyChapter::Chapter11SubchapterV).unwrap(),
+            "\"Chapter11SubchapterV\""
+        );
+        assert_eq!(BankruptcyChapter::Chapter11SubchapterV.number(), Some(11));
+        assert_eq!(BankruptcyChapter::from_number(15), BankruptcyChapter::Chapter15);
+    }
+
+    #[test]
+    fn test_events_without_petition_type_still_deserialize() {
+        let mut json = serde_json::to_value(BankruptcyEvent::new("Acme Freight LLC".into(), Source::Pacer, 0.9)).unwrap();
+        json.as_object_mut().unwrap().remove("petition_type");
+
+        let event: BankruptcyEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(event.petition_type, PetitionType::Unknown);
+    }
+}
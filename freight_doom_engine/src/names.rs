@@ -0,0 +1,416 @@
+// =============================================================================
+// names.rs — IS "ACME TRKG, LLC, et al." THE SAME COMPANY AS "Acme Trucking"?
+// =============================================================================
+//
+// Debtor names arrive in every shape the legal profession can invent:
+//
+//   PACER:          "ACME TRUCKING, LLC, et al."
+//   CourtListener:  "In re: Acme Trucking L.L.C."
+//   EDGAR:          "ACME HOLDINGS INC d/b/a Acme Trkg"
+//   FMCSA:          "ACME TRKG LLC"
+//
+// This module turns them into something comparable, in two steps:
+//
+// 1. Normalisation. Lowercase, drop punctuation, expand the abbreviations
+//    trucking companies love ("Trkg", "Frt", "Xpress"), strip legal
+//    suffixes ("Inc", "LLC", "Corp"), "et al." and "Debtor", and split on
+//    d/b/a, a/k/a and f/k/a into separate name variants.
+//
+// 2. Similarity. A token-sort ratio (word order doesn't matter) averaged
+//    with Jaro-Winkler (typos and truncation near the end don't matter
+//    much), taking the best pair of variants. 1.0 is identical after
+//    normalisation, 0.0 is nothing in common.
+//
+// `NameMatcher` wraps the score in a threshold so dedup, entity
+// resolution, watchlists and enrichment all agree on what "same name"
+// means.
+// =============================================================================
+
+/// Similarity at or above which two names are treated as the same company.
+/// High on purpose: "Acme Freight" and "Acme Freightways" are two different
+/// bankruptcies more often than you'd hope.
+pub const DEFAULT_THRESHOLD: f64 = 0.92;
+
+/// Corporate suffixes dropped from the end of a name before matching.
+const CORPORATE_SUFFIXES: &[&str] = &[
+    "inc", "incorporated", "llc", "lc", "llp", "lllp", "lp", "ltd", "limited", "corp",
+    "corporation", "co", "company", "plc", "pllc", "pc", "na",
+];
+
+/// Token sequences that separate a legal name from a trade name.
+const ALIAS_MARKERS: &[&[&str]] = &[
+    &["d", "b", "a"],
+    &["dba"],
+    &["a", "k", "a"],
+    &["aka"],
+    &["f", "k", "a"],
+    &["fka"],
+    &["doing", "business", "as"],
+    &["formerly", "known", "as"],
+];
+
+/// Trailing noise courts attach to debtor names.
+const DEBTOR_NOISE: &[&[&str]] = &[
+    &["et", "al"],
+    &["debtor", "in", "possession"],
+    &["debtors", "in", "possession"],
+    &["debtor"],
+    &["debtors"],
+];
+
+/// Abbreviations carriers put on their trailers and, unfortunately, their
+/// filings. Expanded so "Acme Trkg" and "Acme Trucking" normalise the same.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("trkg", "trucking"),
+    ("trkng", "trucking"),
+    ("trk", "truck"),
+    ("trks", "trucks"),
+    ("xpress", "express"),
+    ("xprss", "express"),
+    ("expr", "express"),
+    ("frt", "freight"),
+    ("frght", "freight"),
+    ("transp", "transportation"),
+    ("trnsp", "transportation"),
+    ("trans", "transport"),
+    ("lgstcs", "logistics"),
+    ("logist", "logistics"),
+    ("svc", "service"),
+    ("svcs", "services"),
+    ("srvcs", "services"),
+    ("dist", "distribution"),
+    ("distr", "distribution"),
+    ("whse", "warehouse"),
+    ("mtr", "motor"),
+    ("intl", "international"),
+    ("natl", "national"),
+    ("bros", "brothers"),
+    ("hldg", "holding"),
+    ("hldgs", "holdings"),
+    ("grp", "group"),
+    ("sys", "systems"),
+    ("ents", "enterprises"),
+    ("entp", "enterprises"),
+];
+
+/// Names scanners fall back on when they couldn't find one. Matching on
+/// these would merge every nameless event into one giant case.
+const PLACEHOLDER_NAMES: &[&str] = &["unknown", "unknown case", "unknown entity", "unknown carrier"];
+
+/// A similarity threshold, applied consistently.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NameMatcher {
+    threshold: f64,
+}
+
+impl Default for NameMatcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_THRESHOLD)
+    }
+}
+
+impl NameMatcher {
+    /// Match names whose similarity is at least `threshold` (clamped to
+    /// 0.0–1.0).
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold: threshold.clamp(0.0, 1.0) }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Are `a` and `b` the same company, as far as names can tell?
+    pub fn matches(&self, a: &str, b: &str) -> bool {
+        let score = similarity(a, b);
+        score > 0.0 && score >= self.threshold
+    }
+
+    /// The candidate most similar to `name`, if any clears the threshold.
+    /// Ties go to the earliest candidate.
+    pub fn best_match<'a, I>(&self, name: &str, candidates: I) -> Option<(&'a str, f64)>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let variants = name_variants(name);
+        if variants.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(&'a str, f64)> = None;
+        for candidate in candidates {
+            let score = variants_similarity(&variants, &name_variants(candidate));
+            if score >= self.threshold && score > 0.0 && best.is_none_or(|(_, b)| score > b) {
+                best = Some((candidate, score));
+            }
+        }
+        best
+    }
+}
+
+/// How similar two raw company names are, from 0.0 to 1.0, after
+/// normalisation. Compares every d/b/a variant of one against every variant
+/// of the other and keeps the best.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    variants_similarity(&name_variants(a), &name_variants(b))
+}
+
+/// Like `similarity`, for names that are already normalised (e.g. the
+/// output of `name_variants`). Skips normalising them again.
+pub fn normalized_similarity(a: &str, b: &str) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    (token_sort_ratio(a, b) + jaro_winkler(a, b)) / 2.0
+}
+
+fn variants_similarity(a: &[String], b: &[String]) -> f64 {
+    a.iter()
+        .flat_map(|x| b.iter().map(move |y| normalized_similarity(x, y)))
+        .fold(0.0, f64::max)
+}
+
+/// The normalised legal name, the part before any d/b/a.
+///
+/// "ACME FREIGHT LINES, INC." → "acme freight lines"
+pub fn normalize_company_name(name: &str) -> String {
+    name_variants(name).into_iter().next().unwrap_or_default()
+}
+
+/// Every normalised name in `name`: the legal name plus any trade names
+/// after d/b/a, a/k/a or f/k/a. Placeholders like "Unknown Entity" yield
+/// nothing.
+pub fn name_variants(name: &str) -> Vec<String> {
+    // Periods and apostrophes vanish ("L.L.C." → "llc", "O'Brien" →
+    // "obrien"); every other non-alphanumeric is a word break.
+    let cleaned: String = name
+        .to_lowercase()
+        .replace('&', " and ")
+        .chars()
+        .filter(|c| *c != '.' && *c != '\'')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let tokens: Vec<&str> = cleaned.split_whitespace().map(expand_abbreviation).collect();
+
+    let mut variants = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        match ALIAS_MARKERS.iter().find(|marker| tokens[i..].starts_with(marker)) {
+            Some(marker) => {
+                push_variant(&mut variants, &tokens[start..i]);
+                i += marker.len();
+                start = i;
+            }
+            None => i += 1,
+        }
+    }
+    push_variant(&mut variants, &tokens[start..]);
+    variants
+}
+
+/// Strip what courts bolt onto a debtor's name, keeping the name itself as
+/// written.
+///
+/// "ACME TRUCKING, LLC, et al." → "ACME TRUCKING, LLC"
+/// "Acme Freight Inc., Debtor-in-Possession" → "Acme Freight Inc."
+pub fn clean_debtor_name(raw: &str) -> String {
+    let mut name = raw.trim();
+    loop {
+        let before = name;
+        let lower = name.to_lowercase();
+        for noise in ["et al.", "et al", "debtors-in-possession", "debtor-in-possession",
+                      "debtors in possession", "debtor in possession", "debtors", "debtor"] {
+            if lower.ends_with(noise) {
+                let rest = &name[..name.len() - noise.len()];
+                // Only at a word boundary: "Acme Debtor Services" keeps its name.
+                if rest.is_empty() || rest.ends_with([' ', ',']) {
+                    name = rest.trim_end_matches([' ', ',']);
+                    break;
+                }
+            }
+        }
+        if name == before || name.is_empty() {
+            break;
+        }
+    }
+
+    if name.is_empty() {
+        raw.trim().to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn expand_abbreviation(token: &str) -> &str {
+    ABBREVIATIONS
+        .iter()
+        .find(|(abbr, _)| *abbr == token)
+        .map_or(token, |(_, full)| full)
+}
+
+fn push_variant(variants: &mut Vec<String>, tokens: &[&str]) {
+    let mut tokens = tokens;
+    if tokens.first() == Some(&"the") {
+        tokens = &tokens[1..];
+    }
+    while let Some(noise) = DEBTOR_NOISE.iter().find(|noise| tokens.len() > noise.len() && tokens.ends_with(noise)) {
+        tokens = &tokens[..tokens.len() - noise.len()];
+    }
+    while let Some((last, rest)) = tokens.split_last() {
+        if rest.is_empty() || !CORPORATE_SUFFIXES.contains(last) {
+            break;
+        }
+        tokens = rest;
+    }
+
+    let name = tokens.join(" ");
+    if !name.is_empty() && !PLACEHOLDER_NAMES.contains(&name.as_str()) && !variants.contains(&name) {
+        variants.push(name);
+    }
+}
+
+/// Similarity of two strings with their words sorted, so "Freight Acme"
+/// and "Acme Freight" score 1.0. Based on the insertion/deletion distance:
+/// `1 - indel / (len(a) + len(b))`.
+pub fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    let sort = |s: &str| {
+        let mut tokens: Vec<&str> = s.split_whitespace().collect();
+        tokens.sort_unstable();
+        tokens.join(" ").chars().collect::<Vec<char>>()
+    };
+    let (a, b) = (sort(a), sort(b));
+    let total = a.len() + b.len();
+    if total == 0 {
+        return 1.0;
+    }
+
+    // Indel distance = len(a) + len(b) - 2·LCS
+    let mut row = vec![0usize; b.len() + 1];
+    for ca in &a {
+        let mut diagonal = 0;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb { diagonal + 1 } else { above.max(row[j]) };
+            diagonal = above;
+        }
+    }
+    let lcs = row[b.len()];
+    2.0 * lcs as f64 / total as f64
+}
+
+/// Jaro-Winkler similarity: Jaro, plus a bonus for a shared prefix of up to
+/// four characters. Forgiving of typos and of one name being cut short.
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let jaro = jaro(&a, &b);
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+
+    for (i, ca) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_are_normalised() {
+        assert_eq!(normalize_company_name("ACME FREIGHT LINES, INC."), "acme freight lines");
+        assert_eq!(normalize_company_name("Acme Freight Lines L.L.C."), "acme freight lines");
+        assert_eq!(normalize_company_name("The Big Truck Co."), "big truck");
+        assert_eq!(normalize_company_name("Smith & Sons Trucking Corp, et al."), "smith and sons trucking");
+        assert_eq!(normalize_company_name("O'Brien Hauling"), "obrien hauling");
+        assert_eq!(normalize_company_name("Acme Freight Inc., Debtor"), "acme freight");
+        // A suffix is only stripped if something is left.
+        assert_eq!(normalize_company_name("Company"), "company");
+    }
+
+    #[test]
+    fn test_trucking_abbreviations_expand() {
+        assert_eq!(normalize_company_name("ACME TRKG LLC"), "acme trucking");
+        assert_eq!(normalize_company_name("Speedy Xpress Frt Svcs"), "speedy express freight services");
+        assert_eq!(normalize_company_name("ACME TRKG, LLC, et al."), normalize_company_name("Acme Trucking"));
+    }
+
+    #[test]
+    fn test_dba_names_split_into_variants() {
+        assert_eq!(
+            name_variants("ACME HOLDINGS, INC. d/b/a Acme Freight"),
+            vec!["acme holdings", "acme freight"]
+        );
+        assert_eq!(name_variants("Road Runner LLC DBA Coyote Logistics"), vec!["road runner", "coyote logistics"]);
+        assert!(name_variants("Unknown Entity").is_empty());
+    }
+
+    #[test]
+    fn test_debtor_names_are_cleaned_for_display() {
+        assert_eq!(clean_debtor_name("ACME TRUCKING, LLC, et al."), "ACME TRUCKING, LLC");
+        assert_eq!(clean_debtor_name("Acme Freight Inc., Debtor-in-Possession"), "Acme Freight Inc.");
+        assert_eq!(clean_debtor_name("Acme Debtor Services"), "Acme Debtor Services");
+        assert_eq!(clean_debtor_name("et al."), "et al.");
+    }
+
+    #[test]
+    fn test_similarity_scores() {
+        assert_eq!(similarity("ACME TRUCKING, LLC, et al.", "Acme Trkg Inc"), 1.0);
+        assert_eq!(token_sort_ratio("freight acme", "acme freight"), 1.0);
+        assert!((jaro_winkler("martha", "marhta") - 0.961).abs() < 0.001);
+        assert!(similarity("Acme Freight Lines", "Acme Freight Line") > 0.95);
+        assert!(similarity("Acme Freight", "Big Truck") < 0.6);
+        assert_eq!(similarity("Unknown", "Unknown"), 0.0);
+    }
+
+    #[test]
+    fn test_matcher_threshold_and_best_match() {
+        let matcher = NameMatcher::default();
+        assert!(matcher.matches("Acme Freight Lines, Inc.", "ACME FREIGHT LINE LLC"));
+        assert!(!matcher.matches("Acme Freight", "Acme Trucking"));
+        assert!(matcher.matches("Acme Holdings d/b/a Acme Freight", "Acme Freight"));
+
+        let watchlist = ["Big Truck Co", "Acme Freight Lines", "Acme Logistics"];
+        let (hit, score) = matcher.best_match("ACME FRT LINES LLC", watchlist).unwrap();
+        assert_eq!(hit, "Acme Freight Lines");
+        assert_eq!(score, 1.0);
+        assert!(matcher.best_match("Coyote Hauling", watchlist).is_none());
+    }
+}
@@ -0,0 +1,842 @@
+// =============================================================================
+// outbox.rs — THE BLACK BOX RECORDER
+// =============================================================================
+//
+// Redis goes down. It happens. Before this module existed, the publisher
+// would log "events may be lost!" and then, with great integrity, lose them.
+// Meanwhile the channel filled up behind the connection retry loop and the
+// scanners' try_send quietly threw away everything else. A bankruptcy we
+// detected and then forgot is worse than one we never saw: we PAID for it.
+//
+// So now every event goes to disk first:
+//
+// 1. The publisher stage appends each batch to the active segment file and
+//    fsyncs it. Only then is the batch considered accepted.
+// 2. Pending events are replayed to the real sink (Redis) in order, oldest
+//    first — right away, on reconnect, and on restart.
+// 3. Once the sink accepts a batch (for Redis: PUBLISH *and* ZADD), the
+//    cursor moves past it and fully-delivered segments are deleted.
+//
+// On-disk layout, one directory:
+//
+//   00000000000000000001.seg   — segment files, append-only
+//   00000000000000000002.seg
+//   cursor                     — "<segment> <offset>" of the first undelivered event
+//
+// Each record in a segment is [len: u32 LE][crc32: u32 LE][JSON event].
+// A record with a bad checksum at the end of the last segment is a torn
+// write from a crash and gets truncated away on open. Anywhere else it's
+// corruption: the rest of that segment is skipped with a loud error,
+// delivery carries on with the next segment, and once the cursor is past
+// the bad one it's renamed to `<id>.seg.corrupt` instead of deleted, so
+// somebody can go and look at it.
+//
+// An append that fails halfway (disk full, EIO) is cut back off the active
+// segment before the error is returned, so the next append doesn't land
+// behind half a record and take the rest of the segment down with it.
+//
+// Delivery runs inline, on the publisher stage's task, so it has to be
+// quick to fail: the Redis sinks give up on a silent Redis after
+// `redis_timeout`, and after a failure the outbox leaves the sink alone for
+// `retry_interval`. Appends wait on at most one timed-out attempt.
+//
+// Delivery is at-least-once. A batch that half-made it into Redis before
+// the connection dropped gets replayed in full, same event ids and all.
+// A duplicate is an annoyance. A lost bankruptcy is a catastrophe.
+// =============================================================================
+
+use std::collections::BTreeSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use portable_atomic::{AtomicU64, Ordering};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::models::BankruptcyEvent;
+use crate::publisher::{EventSink, BATCH_SIZE};
+
+/// Record header: payload length + CRC32 of the payload.
+const HEADER_LEN: usize = 8;
+
+/// Refuse to believe any single event serializes to more than this.
+const MAX_RECORD_LEN: usize = 16 * 1024 * 1024;
+
+const SEGMENT_EXTENSION: &str = "seg";
+const CORRUPT_EXTENSION: &str = "corrupt";
+const CURSOR_FILE: &str = "cursor";
+
+/// A place in the outbox: a segment id and a byte offset within it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OutboxPosition {
+    pub segment: u64,
+    pub offset: u64,
+}
+
+/// Outbox statistics for metrics.
+#[derive(Default)]
+pub struct OutboxStats {
+    pub events_appended: AtomicU64,
+    pub events_delivered: AtomicU64,
+    pub events_pending: AtomicU64,
+    pub segments: AtomicU64,
+    pub corrupt_records: AtomicU64,
+}
+
+/// A serializable snapshot of outbox stats.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OutboxSnapshot {
+    pub events_appended: u64,
+    pub events_delivered: u64,
+    pub events_pending: u64,
+    pub segments: u64,
+    pub corrupt_records: u64,
+}
+
+impl OutboxStats {
+    pub fn snapshot(&self) -> OutboxSnapshot {
+        OutboxSnapshot {
+            events_appended: self.events_appended.load(Ordering::Relaxed),
+            events_delivered: self.events_delivered.load(Ordering::Relaxed),
+            events_pending: self.events_pending.load(Ordering::Relaxed),
+            segments: self.segments.load(Ordering::Relaxed),
+            corrupt_records: self.corrupt_records.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Undelivered events read from the outbox, and where they end.
+#[derive(Debug)]
+pub struct PendingBatch {
+    pub events: Vec<BankruptcyEvent>,
+    /// Records read past that didn't decode. They count as delivered.
+    pub skipped: u64,
+    /// Pass this to `Outbox::ack` once the events are delivered.
+    pub end: OutboxPosition,
+    /// The batch reached the end of the outbox: nothing after `end`.
+    pub exhausted: bool,
+}
+
+/// What we found at the read position in a segment.
+enum Record {
+    /// A valid record's payload.
+    Valid(Vec<u8>),
+    /// Clean end of the segment (for now, if it's the active one).
+    End,
+    /// Not a valid record: torn write, or corruption.
+    Invalid,
+}
+
+/// A segment file being read record by record. `offset` is just past the
+/// last valid record, so a reader left where a batch ended picks up the
+/// next batch without reading the segment again from the top.
+struct SegmentReader {
+    segment: u64,
+    offset: u64,
+    file: BufReader<File>,
+}
+
+impl SegmentReader {
+    fn open(dir: &Path, segment: u64, offset: u64) -> Result<Self> {
+        let mut file = File::open(segment_path(dir, segment))?;
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(Self { segment, offset, file: BufReader::new(file) })
+    }
+
+    /// After `Invalid` the file position is somewhere inside the bad
+    /// record; the reader is no use for that segment any more.
+    fn next(&mut self) -> Result<Record> {
+        let mut header = [0u8; HEADER_LEN];
+        match read_full(&mut self.file, &mut header)? {
+            0 => return Ok(Record::End),
+            HEADER_LEN => {}
+            _ => return Ok(Record::Invalid),
+        }
+        let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+        let crc = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        if len > MAX_RECORD_LEN {
+            return Ok(Record::Invalid);
+        }
+        let mut payload = vec![0u8; len];
+        if read_full(&mut self.file, &mut payload)? < len || crc32fast::hash(&payload) != crc {
+            return Ok(Record::Invalid);
+        }
+        self.offset += (HEADER_LEN + len) as u64;
+        Ok(Record::Valid(payload))
+    }
+}
+
+/// Fill as much of `buf` as the file has; returns how much that was.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn encode_record(out: &mut Vec<u8>, payload: &[u8]) {
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    out.extend_from_slice(payload);
+}
+
+/// The on-disk, append-only, checksummed event log.
+///
+/// All I/O is synchronous. The outbox lives on the publisher stage, which is
+/// a single task whose whole job is waiting on I/O anyway.
+pub struct Outbox {
+    dir: PathBuf,
+    max_segment_bytes: u64,
+    /// Segment ids on disk, ascending. The last one is the active segment.
+    segments: Vec<u64>,
+    active: File,
+    active_len: u64,
+    /// The first undelivered event.
+    cursor: OutboxPosition,
+    /// Where the last `read_pending` stopped.
+    reader: Option<SegmentReader>,
+    /// Segments with a corrupt record, quarantined once delivered past.
+    corrupt: BTreeSet<u64>,
+    stats: Arc<OutboxStats>,
+    /// Tests: write only this many bytes of the next append, then fail.
+    #[cfg(test)]
+    fail_append_after: Option<usize>,
+}
+
+impl Outbox {
+    /// Open (or create) the outbox in `dir`, recovering from whatever state
+    /// the last process left it in.
+    pub fn open(dir: impl Into<PathBuf>, max_segment_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("creating outbox directory {}", dir.display()))?;
+
+        let mut segments = list_segments(&dir)?;
+        if segments.is_empty() {
+            File::create(segment_path(&dir, 1))?;
+            sync_dir(&dir)?;
+            segments.push(1);
+        }
+
+        let mut cursor = read_cursor(&dir)?.unwrap_or(OutboxPosition { segment: segments[0], offset: 0 });
+        if cursor.segment < segments[0] {
+            cursor = OutboxPosition { segment: segments[0], offset: 0 };
+        }
+
+        let stats = Arc::new(OutboxStats::default());
+        let active_id = *segments.last().unwrap();
+        let mut corrupt = BTreeSet::new();
+        let mut pending = 0u64;
+        let mut active_len = 0u64;
+
+        for &id in segments.iter().filter(|&&id| id >= cursor.segment) {
+            let start = if id == cursor.segment { cursor.offset } else { 0 };
+
+            // Validate from the start of the segment so a torn tail is found
+            // even if the cursor is past the middle of it.
+            let mut reader = SegmentReader::open(&dir, id, 0)?;
+            loop {
+                let offset = reader.offset;
+                match reader.next()? {
+                    Record::Valid(_) => {
+                        if offset >= start {
+                            pending += 1;
+                        }
+                    }
+                    Record::End => break,
+                    Record::Invalid if id == active_id => {
+                        let path = segment_path(&dir, id);
+                        warn!(
+                            segment = id,
+                            offset,
+                            discarded_bytes = fs::metadata(&path)?.len() - offset,
+                            "Outbox: truncating torn write at end of active segment"
+                        );
+                        OpenOptions::new().write(true).open(&path)?.set_len(offset)?;
+                        break;
+                    }
+                    Record::Invalid => {
+                        stats.corrupt_records.fetch_add(1, Ordering::Relaxed);
+                        corrupt.insert(id);
+                        error!(
+                            segment = id,
+                            offset,
+                            "Outbox: corrupt record — skipping the rest of this segment"
+                        );
+                        break;
+                    }
+                }
+            }
+            if id == active_id {
+                active_len = reader.offset;
+            }
+        }
+
+        let active = OpenOptions::new().append(true).open(segment_path(&dir, active_id))?;
+
+        stats.events_pending.store(pending, Ordering::Relaxed);
+        stats.segments.store(segments.len() as u64, Ordering::Relaxed);
+
+        if pending > 0 {
+            info!(
+                pending,
+                dir = %dir.display(),
+                "Outbox: found undelivered events from a previous run — they'll be replayed"
+            );
+        }
+
+        Ok(Self {
+            dir,
+            max_segment_bytes,
+            segments,
+            active,
+            active_len,
+            cursor,
+            reader: None,
+            corrupt,
+            stats,
+            #[cfg(test)]
+            fail_append_after: None,
+        })
+    }
+
+    pub fn stats(&self) -> Arc<OutboxStats> {
+        self.stats.clone()
+    }
+
+    /// Number of events appended but not yet acknowledged.
+    pub fn pending(&self) -> u64 {
+        self.stats.events_pending.load(Ordering::Relaxed)
+    }
+
+    /// Durably append a batch. When this returns `Ok`, the events are on
+    /// disk and fsynced.
+    pub fn append(&mut self, events: &[BankruptcyEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        if self.active_len >= self.max_segment_bytes {
+            self.roll()?;
+        }
+
+        let mut buf = Vec::new();
+        for event in events {
+            encode_record(&mut buf, &serde_json::to_vec(event)?);
+        }
+        if let Err(e) = self.write_active(&buf) {
+            // Whatever made it to disk is half a batch at best. Cut it off,
+            // or if even that fails, leave it at the end of a sealed segment
+            // where delivery will skip it, and append to a fresh one.
+            if let Err(truncate) = self.active.set_len(self.active_len) {
+                error!(error = %truncate, "Outbox: can't truncate a failed append — starting a new segment");
+                self.corrupt.insert(*self.segments.last().unwrap());
+                self.roll()?;
+            }
+            return Err(e.into());
+        }
+        self.active_len += buf.len() as u64;
+
+        let count = events.len() as u64;
+        self.stats.events_appended.fetch_add(count, Ordering::Relaxed);
+        self.stats.events_pending.fetch_add(count, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn write_active(&mut self, buf: &[u8]) -> io::Result<()> {
+        #[cfg(test)]
+        if let Some(n) = self.fail_append_after.take() {
+            self.active.write_all(&buf[..n.min(buf.len())])?;
+            return Err(io::Error::other("injected write failure"));
+        }
+        self.active.write_all(buf)?;
+        self.active.sync_data()
+    }
+
+    /// Read up to `limit` undelivered events, oldest first. Doesn't move
+    /// the cursor — call `ack` for that once they're delivered.
+    pub fn read_pending(&mut self, limit: usize) -> Result<PendingBatch> {
+        let mut events = Vec::new();
+        let mut skipped = 0u64;
+        let mut end = self.cursor;
+        let ids: Vec<u64> = self.segments.iter().copied().filter(|&id| id >= self.cursor.segment).collect();
+
+        for id in ids {
+            let start = if id == end.segment { end.offset } else { 0 };
+            let mut reader = match self.reader.take() {
+                Some(reader) if reader.segment == id && reader.offset == start => reader,
+                _ => SegmentReader::open(&self.dir, id, start)?,
+            };
+
+            let mut clean = true;
+            while events.len() < limit {
+                let offset = reader.offset;
+                match reader.next()? {
+                    Record::Valid(payload) => match serde_json::from_slice(&payload) {
+                        Ok(event) => events.push(event),
+                        Err(e) => {
+                            error!(segment = id, offset, error = %e, "Outbox: undecodable event skipped");
+                            skipped += 1;
+                        }
+                    },
+                    Record::End => break,
+                    Record::Invalid => {
+                        clean = false;
+                        break;
+                    }
+                }
+            }
+
+            if !clean {
+                if self.corrupt.insert(id) {
+                    self.stats.corrupt_records.fetch_add(1, Ordering::Relaxed);
+                    error!(
+                        segment = id,
+                        offset = reader.offset,
+                        "Outbox: corrupt record — skipping the rest of this segment"
+                    );
+                }
+                // Never append behind a corrupt record.
+                if id == *self.segments.last().unwrap() {
+                    self.roll()?;
+                }
+                end = OutboxPosition { segment: id + 1, offset: 0 };
+                continue;
+            }
+
+            end = OutboxPosition { segment: id, offset: reader.offset };
+            self.reader = Some(reader);
+            if events.len() >= limit {
+                return Ok(PendingBatch { events, skipped, end, exhausted: false });
+            }
+            // Finished a sealed segment: the next event starts in the next one.
+            if id != *self.segments.last().unwrap() {
+                end = OutboxPosition { segment: id + 1, offset: 0 };
+            }
+        }
+
+        Ok(PendingBatch { events, skipped, end, exhausted: true })
+    }
+
+    /// Mark everything before `end` as delivered: persist the cursor and
+    /// delete segments nobody needs any more.
+    pub fn ack(&mut self, end: OutboxPosition, delivered: u64) -> Result<()> {
+        if end <= self.cursor {
+            return Ok(());
+        }
+        write_cursor(&self.dir, end)?;
+        self.cursor = end;
+
+        let active_id = *self.segments.last().unwrap();
+        let (done, keep): (Vec<u64>, Vec<u64>) = self
+            .segments
+            .iter()
+            .partition(|&&id| id < end.segment && id != active_id);
+        for id in done {
+            let path = segment_path(&self.dir, id);
+            if self.corrupt.remove(&id) {
+                let quarantine = path.with_extension(format!("{}.{}", SEGMENT_EXTENSION, CORRUPT_EXTENSION));
+                warn!(segment = id, path = %quarantine.display(), "Outbox: corrupt segment quarantined");
+                fs::rename(&path, &quarantine)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        self.segments = keep;
+
+        self.stats.events_delivered.fetch_add(delivered, Ordering::Relaxed);
+        let pending = self.pending().saturating_sub(delivered);
+        self.stats.events_pending.store(pending, Ordering::Relaxed);
+        self.stats.segments.store(self.segments.len() as u64, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Seal the active segment and start a new one.
+    fn roll(&mut self) -> Result<()> {
+        let next = self.segments.last().unwrap() + 1;
+        let path = segment_path(&self.dir, next);
+        self.active = OpenOptions::new().create_new(true).append(true).open(&path)?;
+        sync_dir(&self.dir)?;
+        self.segments.push(next);
+        self.active_len = 0;
+        self.stats.segments.store(self.segments.len() as u64, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", id, SEGMENT_EXTENSION))
+}
+
+fn list_segments(dir: &Path) -> Result<Vec<u64>> {
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(id) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse().ok()) {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+fn read_cursor(dir: &Path) -> Result<Option<OutboxPosition>> {
+    let text = match fs::read_to_string(dir.join(CURSOR_FILE)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut parts = text.split_whitespace().map(str::parse::<u64>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(segment)), Some(Ok(offset))) => Ok(Some(OutboxPosition { segment, offset })),
+        _ => anyhow::bail!("malformed outbox cursor file: {:?}", text),
+    }
+}
+
+/// Write the cursor to a temp file and rename it over the old one, so a
+/// crash leaves either the old cursor or the new one, never half of each.
+fn write_cursor(dir: &Path, position: OutboxPosition) -> Result<()> {
+    let tmp = dir.join(format!("{}.tmp", CURSOR_FILE));
+    let mut file = File::create(&tmp)?;
+    writeln!(file, "{} {}", position.segment, position.offset)?;
+    file.sync_all()?;
+    fs::rename(&tmp, dir.join(CURSOR_FILE))?;
+    sync_dir(dir)
+}
+
+fn sync_dir(dir: &Path) -> Result<()> {
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+/// An `EventSink` that writes every batch to an `Outbox` before handing it
+/// to the real sink, and keeps retrying the real sink until it succeeds.
+pub struct OutboxSink {
+    outbox: Outbox,
+    inner: Box<dyn EventSink>,
+    name: String,
+    retry_interval: Duration,
+    /// No delivery attempts before this instant, after a failure.
+    retry_at: Option<Instant>,
+}
+
+impl OutboxSink {
+    pub fn new(outbox: Outbox, inner: impl EventSink + 'static, retry_interval: Duration) -> Self {
+        Self::boxed(outbox, Box::new(inner), retry_interval)
+    }
+
+    pub fn boxed(outbox: Outbox, inner: Box<dyn EventSink>, retry_interval: Duration) -> Self {
+        let name = format!("outbox→{}", inner.name());
+        Self {
+            outbox,
+            inner,
+            name,
+            retry_interval,
+            retry_at: None,
+        }
+    }
+
+    pub fn stats(&self) -> Arc<OutboxStats> {
+        self.outbox.stats()
+    }
+
+    /// Deliver pending events, oldest first, until the outbox is empty or
+    /// the sink fails. On failure, hold off for `retry_interval`.
+    async fn deliver_pending(&mut self) {
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return;
+        }
+        self.retry_at = None;
+
+        while self.outbox.pending() > 0 {
+            let batch = match self.outbox.read_pending(BATCH_SIZE) {
+                Ok(batch) => batch,
+                Err(e) => {
+                    error!(error = %e, "Outbox: failed to read pending events");
+                    self.retry_at = Some(Instant::now() + self.retry_interval);
+                    return;
+                }
+            };
+
+            if !batch.events.is_empty() {
+                if let Err(e) = self.inner.publish(&batch.events).await {
+                    warn!(
+                        sink = self.inner.name(),
+                        error = %e,
+                        pending = self.outbox.pending(),
+                        retry_in_secs = self.retry_interval.as_secs(),
+                        "Outbox: delivery failed — events are safe on disk, will retry"
+                    );
+                    self.retry_at = Some(Instant::now() + self.retry_interval);
+                    return;
+                }
+            }
+
+            let delivered = batch.events.len() as u64 + batch.skipped;
+            if let Err(e) = self.outbox.ack(batch.end, delivered) {
+                error!(error = %e, "Outbox: failed to persist cursor — events may be redelivered");
+                self.retry_at = Some(Instant::now() + self.retry_interval);
+                return;
+            }
+            if batch.exhausted {
+                // Anything still counted was behind a corrupt record. Stop
+                // counting it.
+                self.outbox.stats.events_pending.store(0, Ordering::Relaxed);
+                return;
+            }
+        }
+    }
+}
+
+impl EventSink for OutboxSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Start the inner sink, then replay whatever a previous run left behind.
+    fn start<'a>(&'a mut self, shutdown: &'a watch::Receiver<bool>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.inner.start(shutdown).await?;
+            self.deliver_pending().await;
+            Ok(())
+        })
+    }
+
+    /// Accepted means on disk. Delivery is attempted right away, but a
+    /// delivery failure is the outbox's problem now, not the caller's.
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.outbox.append(batch)?;
+            self.deliver_pending().await;
+            Ok(())
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.deliver_pending().await;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+    use std::sync::atomic::AtomicBool;
+
+    /// A scratch directory that cleans up after itself.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("freight-doom-outbox-{}", uuid::Uuid::new_v4()));
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn events(names: &[&str]) -> Vec<BankruptcyEvent> {
+        names
+            .iter()
+            .map(|n| BankruptcyEvent::new(n.to_string(), Source::Pacer, 0.9))
+            .collect()
+    }
+
+    fn names(events: &[BankruptcyEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.company_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_append_read_ack_roundtrip() {
+        let dir = TempDir::new();
+        let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+        outbox.append(&events(&["Alpha Freight", "Beta Trucking"])).unwrap();
+
+        let batch = outbox.read_pending(10).unwrap();
+        assert_eq!(names(&batch.events), vec!["Alpha Freight", "Beta Trucking"]);
+        outbox.ack(batch.end, 2).unwrap();
+
+        assert_eq!(outbox.pending(), 0);
+        assert!(outbox.read_pending(10).unwrap().events.is_empty());
+    }
+
+    #[test]
+    fn test_pending_events_survive_restart_in_order() {
+        let dir = TempDir::new();
+        {
+            let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+            outbox.append(&events(&["One", "Two", "Three"])).unwrap();
+            let first = outbox.read_pending(1).unwrap();
+            outbox.ack(first.end, 1).unwrap();
+        }
+
+        let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+        assert_eq!(outbox.pending(), 2);
+        assert_eq!(names(&outbox.read_pending(10).unwrap().events), vec!["Two", "Three"]);
+    }
+
+    #[test]
+    fn test_segments_roll_and_compact() {
+        let dir = TempDir::new();
+        // Tiny segments: every append after the first rolls to a new one.
+        let mut outbox = Outbox::open(&dir.0, 1).unwrap();
+        for name in ["A", "B", "C"] {
+            outbox.append(&events(&[name])).unwrap();
+        }
+        assert_eq!(list_segments(&dir.0).unwrap().len(), 3);
+
+        let batch = outbox.read_pending(10).unwrap();
+        assert_eq!(names(&batch.events), vec!["A", "B", "C"]);
+        outbox.ack(batch.end, 3).unwrap();
+
+        assert_eq!(list_segments(&dir.0).unwrap().len(), 1);
+        assert_eq!(outbox.stats().snapshot().events_delivered, 3);
+    }
+
+    #[test]
+    fn test_torn_write_is_truncated_on_open() {
+        let dir = TempDir::new();
+        {
+            let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+            outbox.append(&events(&["Intact Logistics"])).unwrap();
+        }
+        // Simulate a crash halfway through writing the next record.
+        let path = segment_path(&dir.0, 1);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[200, 0, 0, 0, 1, 2, 3, 4, b'{']).unwrap();
+        drop(file);
+
+        let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+        assert_eq!(outbox.pending(), 1);
+        outbox.append(&events(&["After Crash LLC"])).unwrap();
+        assert_eq!(
+            names(&outbox.read_pending(10).unwrap().events),
+            vec!["Intact Logistics", "After Crash LLC"]
+        );
+    }
+
+    #[test]
+    fn test_failed_append_is_cut_off_the_segment() {
+        let dir = TempDir::new();
+        let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+        outbox.append(&events(&["Before LLC"])).unwrap();
+
+        // Disk full twelve bytes into the record.
+        outbox.fail_append_after = Some(12);
+        assert!(outbox.append(&events(&["Lost Freight"])).is_err());
+        outbox.append(&events(&["After LLC"])).unwrap();
+
+        assert_eq!(outbox.pending(), 2);
+        assert_eq!(names(&outbox.read_pending(10).unwrap().events), vec!["Before LLC", "After LLC"]);
+        drop(outbox);
+
+        let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+        assert_eq!(outbox.stats().snapshot().corrupt_records, 0);
+        assert_eq!(names(&outbox.read_pending(10).unwrap().events), vec!["Before LLC", "After LLC"]);
+    }
+
+    #[test]
+    fn test_corrupt_segment_is_skipped_and_quarantined() {
+        let dir = TempDir::new();
+        {
+            let mut outbox = Outbox::open(&dir.0, 1).unwrap();
+            for name in ["A", "B", "C"] {
+                outbox.append(&events(&[name])).unwrap();
+            }
+        }
+        // Flip a byte in the middle of B's payload.
+        let path = segment_path(&dir.0, 2);
+        let mut bytes = fs::read(&path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        fs::write(&path, bytes).unwrap();
+
+        let mut outbox = Outbox::open(&dir.0, 1).unwrap();
+        assert_eq!(outbox.stats().snapshot().corrupt_records, 1);
+        let batch = outbox.read_pending(10).unwrap();
+        assert_eq!(names(&batch.events), vec!["A", "C"]);
+        assert!(batch.exhausted);
+        outbox.ack(batch.end, 2).unwrap();
+
+        assert_eq!(outbox.pending(), 0);
+        assert!(path.with_extension("seg.corrupt").exists());
+        assert_eq!(list_segments(&dir.0).unwrap(), vec![3]);
+        assert_eq!(outbox.stats().snapshot().corrupt_records, 1);
+    }
+
+    #[test]
+    fn test_batches_carry_on_where_the_last_one_ended() {
+        let dir = TempDir::new();
+        let mut outbox = Outbox::open(&dir.0, 1024 * 1024).unwrap();
+        outbox.append(&events(&["One", "Two", "Three"])).unwrap();
+
+        let first = outbox.read_pending(2).unwrap();
+        assert_eq!(names(&first.events), vec!["One", "Two"]);
+        assert!(!first.exhausted);
+        outbox.ack(first.end, 2).unwrap();
+
+        outbox.append(&events(&["Four"])).unwrap();
+        let second = outbox.read_pending(2).unwrap();
+        assert_eq!(names(&second.events), vec!["Three", "Four"]);
+        // Not acked: the next read starts over from the cursor.
+        assert_eq!(names(&outbox.read_pending(10).unwrap().events), vec!["Three", "Four"]);
+    }
+
+    /// Fails until told otherwise.
+    struct FlakySink {
+        up: Arc<AtomicBool>,
+    }
+
+    impl EventSink for FlakySink {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                if !self.up.load(std::sync::atomic::Ordering::SeqCst) {
+                    anyhow::bail!("connection refused");
+                }
+                assert!(!batch.is_empty());
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sink_keeps_events_until_delivery_succeeds() {
+        let dir = TempDir::new();
+        let up = Arc::new(AtomicBool::new(false));
+        let inner = FlakySink { up: up.clone() };
+        let mut sink = OutboxSink::new(Outbox::open(&dir.0, 1024 * 1024).unwrap(), inner, Duration::ZERO);
+
+        sink.publish(&events(&["Doomed Freight"])).await.unwrap();
+        sink.publish(&events(&["Also Doomed"])).await.unwrap();
+        assert_eq!(sink.stats().snapshot().events_pending, 2);
+
+        up.store(true, std::sync::atomic::Ordering::SeqCst);
+        sink.flush().await.unwrap();
+        assert_eq!(sink.stats().snapshot().events_pending, 0);
+        assert_eq!(sink.stats().snapshot().events_delivered, 2);
+    }
+}
@@ -0,0 +1,538 @@
+// =============================================================================
+// pipeline.rs — THE ASSEMBLY LINE
+// =============================================================================
+//
+// Scanners on one end, sinks on the other, a 10,000-slot lock-free
+// crossbeam channel in the middle, and a Bloom filter standing guard
+// against déjà vu. This module wires all of that together so you don't
+// have to clone four Arcs by hand every time you want to detect a
+// bankrupt trucking company.
+//
+// The binary uses it with the built-in scanners and the Redis publisher.
+// Other services can bring their own sources and sinks:
+//
+//     let pipeline = Pipeline::builder(Config::from_env())
+//         .with_builtin_scanners()
+//         .source(MyStateCourtScanner::new())
+//         .sink(MyKafkaSink::new())
+//         .build()?;
+//     pipeline.run(shutdown_rx).await?;
+//
+// It's the same engine either way. Just with a different loading dock.
+// =============================================================================
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::affiliates::AffiliateGrouper;
+use crate::config::Config;
+use crate::dedup::{snapshot, DedupBackend, DedupEngine, DedupTtls, RedisDedup};
+use crate::metrics::MetricsCollector;
+use crate::lifecycle::{self, CaseTracker};
+use crate::models::BankruptcyEvent;
+use crate::publisher::{self, EventSink};
+use crate::resolution::EntityResolver;
+use crate::scanners::{driver, Scanner, ScannerContext, ScannerRegistry};
+use crate::sinks::{self, FanoutSink, RetryPolicy};
+use crate::text_scanner::{self, RelevanceModel, SharedKeywords};
+
+/// Default capacity of the channel between scanners and the sink.
+/// If we're 10,000 events behind, we have bigger problems.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Builder for a `Pipeline`. Start with `Pipeline::builder`.
+pub struct PipelineBuilder {
+    config: Arc<Config>,
+    registry: ScannerRegistry,
+    sinks: Vec<Box<dyn EventSink>>,
+    dedup: Option<Arc<DedupEngine>>,
+    dedup_backend: Option<Arc<dyn DedupBackend>>,
+    metrics: Option<Arc<MetricsCollector>>,
+    channel_capacity: usize,
+}
+
+impl PipelineBuilder {
+    fn new(config: Config) -> Self {
+        Self {
+            config: Arc::new(config),
+            registry: ScannerRegistry::new(),
+            sinks: Vec::new(),
+            dedup: None,
+            dedup_backend: None,
+            metrics: None,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+
+    /// Add the built-in scanners (PACER, EDGAR, FMCSA, CourtListener),
+    /// filtered by `Config::enabled_scanners`.
+    pub fn with_builtin_scanners(mut self) -> Self {
+        let builtin = ScannerRegistry::with_builtin(&self.config);
+        self.registry.extend(builtin);
+        self
+    }
+
+    /// Add a custom source.
+    pub fn source(mut self, scanner: impl Scanner + 'static) -> Self {
+        self.registry.register(scanner);
+        self
+    }
+
+    /// Add a place for detected events to go. Call it more than once to fan
+    /// out to several sinks. With no sinks added, the pipeline builds the
+    /// ones named in `Config::sinks`.
+    pub fn sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Share an existing dedup engine instead of building one from config.
+    /// A shared engine is not loaded from or saved to the dedup snapshot;
+    /// that's up to whoever owns it.
+    pub fn dedup(mut self, dedup: Arc<DedupEngine>) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Make dedup decisions somewhere other than the backend named in
+    /// `Config::dedup_backend`. The local engine still gets snapshotted and
+    /// reported to metrics; it's just not consulted unless `backend` does.
+    pub fn dedup_backend(mut self, backend: Arc<dyn DedupBackend>) -> Self {
+        self.dedup_backend = Some(backend);
+        self
+    }
+
+    /// Share an existing metrics collector instead of creating a new one.
+    pub fn metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Capacity of the channel between scanners and the sink.
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity.max(1);
+        self
+    }
+
+    /// Assemble the pipeline. Fails if a sink can't be set up, the dedup
+    /// backend isn't one we know, or the keyword dictionary or relevance
+    /// model is invalid.
+    pub fn build(self) -> Result<Pipeline> {
+        let config = self.config;
+
+        let keywords = SharedKeywords::default();
+        if let Some(path) = &config.keywords_path {
+            let terms = text_scanner::dictionary::load_into(path, &keywords)?;
+            info!(path = %path.display(), terms, "📖 Keyword dictionary loaded");
+        }
+        let relevance_model = match &config.relevance_model_path {
+            Some(path) => {
+                let model = RelevanceModel::load(path)?;
+                info!(path = %path.display(), ngrams = model.vocabulary(), "🧠 Relevance model loaded");
+                Some(Arc::new(model))
+            }
+            None => None,
+        };
+
+        let (dedup, dedup_snapshot) = match self.dedup {
+            Some(dedup) => (dedup, None),
+            None => {
+                let dedup = Arc::new(
+                    DedupEngine::new(
+                        config.bloom_expected_items,
+                        config.bloom_false_positive_rate,
+                        config.lru_cache_size,
+                        config.bloom_rotation_interval.as_secs(),
+                    )
+                    .with_ttls(DedupTtls::from_config(&config)),
+                );
+                if let Some(path) = &config.dedup_snapshot_path {
+                    snapshot::restore_or_start_fresh(&dedup, path);
+                }
+                (dedup, config.dedup_snapshot_path.clone())
+            }
+        };
+
+        let dedup_backend: Arc<dyn DedupBackend> = match self.dedup_backend {
+            Some(backend) => backend,
+            None => match config.dedup_backend.to_ascii_lowercase().as_str() {
+                "local" => dedup.clone(),
+                "redis" => Arc::new(RedisDedup::from_config(dedup.clone(), &config)?),
+                other => bail!(
+                    "unknown dedup backend {:?} in FREIGHT_DOOM_DEDUP_BACKEND (expected local or redis)",
+                    other
+                ),
+            },
+        };
+
+        let metrics = self
+            .metrics
+            .unwrap_or_else(|| Arc::new(MetricsCollector::new()));
+
+        let sinks = if self.sinks.is_empty() {
+            sinks::from_config(&config, &metrics)?
+        } else {
+            self.sinks
+        };
+        let sink = FanoutSink::new(sinks, RetryPolicy::from_config(&config), metrics.clone());
+        for (name, stats) in sink.stats() {
+            metrics.register_sink(&name, stats);
+        }
+
+        metrics.register_dedup(dedup.clone());
+
+        let resolver = Arc::new(EntityResolver::from_config(&config));
+        metrics.register_resolver(resolver.clone());
+
+        let cases = Arc::new(CaseTracker::from_config(&config));
+        metrics.register_cases(cases.clone());
+
+        let affiliates = Arc::new(AffiliateGrouper::from_config(&config));
+        metrics.register_affiliates(affiliates.clone());
+
+        Ok(Pipeline {
+            config,
+            registry: self.registry,
+            sink,
+            dedup,
+            dedup_backend,
+            dedup_snapshot,
+            resolver,
+            cases,
+            affiliates,
+            metrics,
+            keywords,
+            relevance_model,
+            channel_capacity: self.channel_capacity,
+        })
+    }
+}
+
+/// A fully assembled detection pipeline: scanners → text scanner → dedup →
+/// affiliate grouping → entity resolution → case tracking → channel → sink.
+pub struct Pipeline {
+    config: Arc<Config>,
+    registry: ScannerRegistry,
+    sink: FanoutSink,
+    dedup: Arc<DedupEngine>,
+    dedup_backend: Arc<dyn DedupBackend>,
+    /// Where the dedup state is saved, if the pipeline owns the engine.
+    dedup_snapshot: Option<PathBuf>,
+    resolver: Arc<EntityResolver>,
+    cases: Arc<CaseTracker>,
+    affiliates: Arc<AffiliateGrouper>,
+    metrics: Arc<MetricsCollector>,
+    keywords: SharedKeywords,
+    relevance_model: Option<Arc<RelevanceModel>>,
+    channel_capacity: usize,
+}
+
+impl Pipeline {
+    pub fn builder(config: Config) -> PipelineBuilder {
+        PipelineBuilder::new(config)
+    }
+
+    pub fn config(&self) -> &Arc<Config> {
+        &self.config
+    }
+
+    pub fn dedup(&self) -> &Arc<DedupEngine> {
+        &self.dedup
+    }
+
+    pub fn resolver(&self) -> &Arc<EntityResolver> {
+        &self.resolver
+    }
+
+    pub fn cases(&self) -> &Arc<CaseTracker> {
+        &self.cases
+    }
+
+    pub fn affiliates(&self) -> &Arc<AffiliateGrouper> {
+        &self.affiliates
+    }
+
+    /// The vocabulary this pipeline's scanners score with.
+    pub fn keywords(&self) -> &SharedKeywords {
+        &self.keywords
+    }
+
+    pub fn metrics(&self) -> &Arc<MetricsCollector> {
+        &self.metrics
+    }
+
+    /// Name of the backend making dedup decisions ("local", "redis", ...).
+    pub fn dedup_backend_name(&self) -> &str {
+        self.dedup_backend.name()
+    }
+
+    /// Names of the sinks every event will be delivered to.
+    pub fn sink_names(&self) -> Vec<&str> {
+        self.sink.sink_names()
+    }
+
+    /// Names of the scanners this pipeline will run.
+    pub fn scanner_names(&self) -> Vec<&'static str> {
+        self.registry.names()
+    }
+
+    /// Run every scanner and the publisher stage until `shutdown` flips to
+    /// true, then wait for them all to finish. The dedup state and the case
+    /// store are saved periodically and once more after the scanners stop.
+    pub async fn run(self, shutdown: watch::Receiver<bool>) -> Result<()> {
+        let (event_tx, event_rx) =
+            crossbeam_channel::bounded::<BankruptcyEvent>(self.channel_capacity);
+        self.metrics.register_queue(event_rx.clone());
+
+        let ctx = ScannerContext {
+            config: self.config.clone(),
+            event_tx,
+            dedup: self.dedup_backend.clone(),
+            resolver: self.resolver.clone(),
+            cases: self.cases.clone(),
+            affiliates: self.affiliates.clone(),
+            metrics: self.metrics.clone(),
+            keywords: self.keywords.clone(),
+            relevance_model: self.relevance_model.clone(),
+        };
+
+        let mut scanner_handles = self.registry.spawn_all(&ctx, &shutdown);
+        scanner_handles.push(tokio::spawn(driver::flush_affiliates(ctx.clone(), shutdown.clone())));
+
+        if let Some(path) = &self.config.keywords_path {
+            if !self.config.keywords_reload_interval.is_zero() {
+                tokio::spawn(text_scanner::dictionary::run_reloader(
+                    path.clone(),
+                    self.keywords.clone(),
+                    self.config.keywords_reload_interval,
+                    shutdown.clone(),
+                ));
+            }
+        }
+
+        if let Some(path) = &self.dedup_snapshot {
+            tokio::spawn(snapshot::run_snapshotter(
+                self.dedup.clone(),
+                path.clone(),
+                self.config.dedup_snapshot_interval,
+                shutdown.clone(),
+            ));
+        }
+
+        tokio::spawn(lifecycle::run_saver(
+            self.cases.clone(),
+            self.config.case_store_interval,
+            shutdown.clone(),
+        ));
+
+        // Drop our copy of event_tx so the publisher knows when all senders are gone
+        drop(ctx);
+
+        let sink: Box<dyn EventSink> = Box::new(self.sink);
+        let publisher_shutdown = shutdown.clone();
+        let publisher_handle = tokio::spawn(async move {
+            info!("📤 Publisher ({}): ONLINE", sink.name());
+            let result = publisher::run_publisher(sink, event_rx, publisher_shutdown).await;
+            if let Err(e) = &result {
+                error!("📤 Publisher error: {}", e);
+            }
+            info!("📤 Publisher: OFFLINE");
+            result
+        });
+
+        futures::future::join_all(scanner_handles).await;
+
+        // Nothing else can touch the dedup engine now, so this snapshot is
+        // the complete picture for the next boot.
+        if let Some(path) = &self.dedup_snapshot {
+            match snapshot::save(&self.dedup, path) {
+                Ok(()) => info!(path = %path.display(), "💾 Dedup state saved"),
+                Err(e) => error!("💾 Failed to save dedup state: {:#}", e),
+            }
+        }
+        if let Err(e) = self.cases.save() {
+            error!("💾 Failed to save the case store: {:#}", e);
+        }
+
+        publisher_handle.await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+    use crate::scanners::{Candidate, FetchTarget};
+    use futures::future::BoxFuture;
+    use std::time::Duration;
+
+    struct NoopScanner;
+
+    impl Scanner for NoopScanner {
+        fn name(&self) -> &'static str {
+            "noop"
+        }
+
+        fn source(&self) -> Source {
+            Source::Pacer
+        }
+
+        fn poll_interval(&self) -> Duration {
+            Duration::from_secs(3600)
+        }
+
+        fn targets(&self, _cycle: u64) -> Vec<FetchTarget> {
+            Vec::new()
+        }
+
+        fn parse(&self, _target: &FetchTarget, _body: &str) -> Vec<Candidate> {
+            Vec::new()
+        }
+    }
+
+    /// Config that keeps test runs from leaving a dedup snapshot or a case
+    /// store in the crate.
+    fn test_config() -> Config {
+        let mut config = Config::from_env();
+        config.dedup_snapshot_path = None;
+        config.case_store_path = None;
+        config
+    }
+
+    struct NullSink;
+
+    impl EventSink for NullSink {
+        fn name(&self) -> &str {
+            "null"
+        }
+
+        fn publish<'a>(&'a mut self, _batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[test]
+    fn test_builder_accepts_custom_sources() {
+        let pipeline = Pipeline::builder(test_config())
+            .source(NoopScanner)
+            .sink(NullSink)
+            .build()
+            .unwrap();
+        assert_eq!(pipeline.scanner_names(), vec!["noop"]);
+        assert_eq!(pipeline.sink_names(), vec!["null"]);
+        assert!(pipeline.metrics().snapshot().publishers.is_empty());
+    }
+
+    #[test]
+    fn test_default_sink_reports_to_metrics() {
+        let mut config = test_config();
+        config.sinks = vec!["redis".into()];
+        config.outbox_dir = None;
+        let pipeline = Pipeline::builder(config)
+            .source(NoopScanner)
+            .build()
+            .unwrap();
+        let snapshot = pipeline.metrics().snapshot();
+        assert_eq!(pipeline.sink_names(), vec!["redis"]);
+        assert!(snapshot.publishers.contains_key("redis"));
+        assert!(snapshot.dedup.is_some());
+        assert!(snapshot.outboxes.is_empty());
+        assert!(snapshot.sinks.contains_key("redis"));
+    }
+
+    #[test]
+    fn test_default_sink_uses_outbox_when_configured() {
+        let dir = std::env::temp_dir().join(format!("freight-doom-pipeline-{}", uuid::Uuid::new_v4()));
+        let mut config = test_config();
+        config.sinks = vec!["redis".into(), "redis_stream".into()];
+        config.outbox_dir = Some(dir.clone());
+        let pipeline = Pipeline::builder(config)
+            .source(NoopScanner)
+            .build()
+            .unwrap();
+
+        let outboxes: Vec<String> = pipeline.metrics().snapshot().outboxes.into_keys().collect();
+        assert_eq!(outboxes, vec!["redis", "redis_stream"]);
+        assert!(dir.join("redis").is_dir());
+        assert!(dir.join("redis_stream").is_dir());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_each_pipeline_keeps_its_own_vocabulary() {
+        let path = std::env::temp_dir().join(format!("freight-doom-keywords-{}.toml", uuid::Uuid::new_v4()));
+        let custom = text_scanner::dictionary::BUILTIN.replacen("[freight]\n", "[freight]\n\"zeppelin haulage\" = 1.0\n", 1);
+        std::fs::write(&path, custom).unwrap();
+
+        let mut config = test_config();
+        config.keywords_path = Some(path.clone());
+        let custom = Pipeline::builder(config).source(NoopScanner).sink(NullSink).build().unwrap();
+        let builtin = Pipeline::builder(test_config()).source(NoopScanner).sink(NullSink).build().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let text = "Zeppelin Haulage filed chapter 11";
+        assert!(text_scanner::quick_freight_check_with(&custom.keywords().get(), text));
+        assert!(!text_scanner::quick_freight_check_with(&builtin.keywords().get(), text));
+        assert_eq!(custom.keywords().get().len(), builtin.keywords().get().len() + 1);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_state_is_saved_on_shutdown_and_restored() {
+        let dir = std::env::temp_dir().join(format!("freight-doom-pipeline-{}", uuid::Uuid::new_v4()));
+        let mut config = test_config();
+        config.dedup_snapshot_path = Some(dir.join("dedup.snapshot"));
+
+        let pipeline = Pipeline::builder(config.clone())
+            .source(NoopScanner)
+            .sink(NullSink)
+            .build()
+            .unwrap();
+        assert!(pipeline.dedup().check_and_insert("pacer:acme_freight:24-10001"));
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = tokio::spawn(pipeline.run(shutdown_rx));
+        shutdown_tx.send(true).unwrap();
+        handle.await.unwrap().unwrap();
+
+        let restarted = Pipeline::builder(config)
+            .source(NoopScanner)
+            .sink(NullSink)
+            .build()
+            .unwrap();
+        assert!(!restarted.dedup().check_and_insert("pacer:acme_freight:24-10001"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dedup_backend_is_chosen_by_config() {
+        let mut config = test_config();
+        config.sinks = vec!["stdout".into()];
+        config.dedup_backend = "redis".into();
+        let pipeline = Pipeline::builder(config.clone()).build().unwrap();
+        assert_eq!(pipeline.dedup_backend_name(), "redis");
+
+        config.dedup_backend = "carrier_pigeon".into();
+        let err = Pipeline::builder(config).build().err().unwrap();
+        assert!(err.to_string().contains("carrier_pigeon"));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_stops_on_shutdown() {
+        let pipeline = Pipeline::builder(test_config())
+            .source(NoopScanner)
+            .sink(NullSink)
+            .build()
+            .unwrap();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = tokio::spawn(pipeline.run(shutdown_rx));
+        shutdown_tx.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("pipeline did not shut down")
+            .unwrap()
+            .unwrap();
+    }
+}
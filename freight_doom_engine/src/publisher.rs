@@ -7,21 +7,42 @@
 // other end, presumably with a mix of horror and fascination.
 //
 // Architecture:
-// 1. Consumer loop reads from the lock-free crossbeam channel
-// 2. Events are serialized to JSON (serde does the heavy lifting)
-// 3. Events are published to a Redis pub/sub channel
-// 4. Events are ALSO stored in a Redis sorted set (scored by timestamp)
-//    for persistence, because pub/sub is fire-and-forget
-// 5. Batch publishing to minimize Redis round trips
+// 1. The publisher stage reads from the lock-free crossbeam channel
+// 2. Events are drained into batches to minimize round trips
+// 3. Each batch is handed to an `EventSink` — in the engine, a `FanoutSink`
+//    that passes it on to every configured sink (see sinks/)
+// 4. The default sink, an `OutboxSink`, fsyncs it to disk before anything
+//    else happens (see outbox.rs)
+// 5. Behind the outbox, `RedisPublisher` serializes each event to JSON,
+//    PUBLISHes it to a Redis pub/sub channel, and ALSO stores it in a
+//    Redis sorted set (scored by timestamp) for persistence, because
+//    pub/sub is fire-and-forget
 //
 // The Redis sorted set acts as a durable event log. Even if the Rails
 // app is down when a bankruptcy is detected, the event will be waiting
 // in Redis when it comes back. Like a patient harbinger of doom.
+//
+// Consumers that can't afford to miss a message while they restart should
+// use `RedisStreamSink` instead (FREIGHT_DOOM_SINKS=redis_stream): same
+// events, XADDed to a stream they can read with consumer groups and XACK.
+//
+// The non-Redis sinks, and the fan-out that feeds all of them, live in
+// `sinks/`.
+//
+// Every connect and command the Redis sinks send gets `redis_timeout` to
+// come back. A Redis behind a dead load balancer doesn't refuse anything,
+// it just never answers, and without a deadline the publisher stage would
+// wait on it forever while the channel fills up behind it.
+//
+// Services embedding the engine can bring their own `EventSink` and skip
+// Redis entirely. We won't take it personally.
 // =============================================================================
 
 use anyhow::Result;
 use crossbeam_channel::Receiver;
+use futures::future::BoxFuture;
 use redis::AsyncCommands;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::watch;
@@ -30,13 +51,134 @@ use tracing::{debug, error, info, warn};
 use crate::config::Config;
 use crate::models::BankruptcyEvent;
 
-/// The Redis Publisher. Consumes events from the crossbeam channel
-/// and publishes them to Redis with the urgency of a dispatcher
-/// trying to cover a hot load.
-pub struct RedisPublisher {
-    config: Arc<Config>,
+/// Maximum number of events handed to a sink in one `publish` call.
+pub(crate) const BATCH_SIZE: usize = 50;
+
+/// Somewhere for detected events to go.
+///
+/// The publisher stage drains the channel into batches and hands each batch
+/// to the sink. Implementations decide what "published" means — Redis,
+/// a file, a message queue, a very patient intern.
+pub trait EventSink: Send {
+    /// Short name for logging.
+    fn name(&self) -> &str;
+
+    /// Called once before the first batch. Connect, open files, etc.
+    /// Implementations that retry should give up when `shutdown` flips.
+    fn start<'a>(&'a mut self, _shutdown: &'a watch::Receiver<bool>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Publish a batch of events.
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>>;
+
+    /// Called whenever the channel is empty. Sinks that buffer or retry
+    /// use it to make progress without waiting for the next event.
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Run the publisher stage until shutdown or until every sender is gone.
+///
+/// The loop:
+/// 1. Drains up to BATCH_SIZE events from the channel
+/// 2. Hands them to the sink
+/// 3. Sleeps briefly if no events were available
+/// 4. Repeats until shutdown, then drains whatever is left
+///
+/// Delivery is counted by the sink (`FanoutSink`), which knows which of
+/// its sinks took what.
+pub async fn run_publisher(
+    mut sink: Box<dyn EventSink>,
     receiver: Receiver<BankruptcyEvent>,
     shutdown: watch::Receiver<bool>,
+) -> Result<()> {
+    sink.start(&shutdown).await?;
+
+    let mut batch: Vec<BankruptcyEvent> = Vec::with_capacity(BATCH_SIZE);
+
+    loop {
+        // Check for shutdown signal
+        if *shutdown.borrow() {
+            // Drain remaining events before shutting down
+            info!(sink = sink.name(), "Shutdown signal received — draining remaining events");
+            batch.clear();
+            while let Ok(event) = receiver.try_recv() {
+                batch.push(event);
+            }
+            if !batch.is_empty() {
+                if let Err(e) = sink.publish(&batch).await {
+                    error!(sink = sink.name(), error = %e, "Failed to publish final batch during shutdown");
+                }
+            }
+            if let Err(e) = sink.flush().await {
+                warn!(sink = sink.name(), error = %e, "Sink flush failed during shutdown");
+            }
+            info!(sink = sink.name(), "Publisher shutting down — no more doom to broadcast");
+            return Ok(());
+        }
+
+        // Drain events from the channel into a batch
+        batch.clear();
+        let mut disconnected = false;
+        while batch.len() < BATCH_SIZE {
+            match receiver.try_recv() {
+                Ok(event) => batch.push(event),
+                Err(crossbeam_channel::TryRecvError::Empty) => break,
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            if let Err(e) = sink.publish(&batch).await {
+                error!(
+                    sink = sink.name(),
+                    error = %e,
+                    batch_size = batch.len(),
+                    "Failed to publish batch — events may be lost!"
+                );
+            }
+        }
+
+        if disconnected {
+            info!(sink = sink.name(), "Channel disconnected — publisher shutting down");
+            return Ok(());
+        }
+
+        if batch.is_empty() {
+            // No events to publish. Let the sink catch up, sleep briefly,
+            // and check again.
+            if let Err(e) = sink.flush().await {
+                warn!(sink = sink.name(), error = %e, "Sink flush failed");
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// `op`, or an error if Redis hasn't answered it within `limit`.
+async fn within<T>(limit: Duration, what: &str, op: impl Future<Output = redis::RedisResult<T>>) -> Result<T> {
+    match tokio::time::timeout(limit, op).await {
+        Ok(result) => Ok(result?),
+        Err(_) => anyhow::bail!("Redis {} got no answer within {:?}", what, limit),
+    }
+}
+
+/// Connect to the configured Redis, giving up after `redis_timeout`.
+async fn connect_within(config: &Config) -> Result<redis::aio::MultiplexedConnection> {
+    let client = redis::Client::open(config.redis_url.as_str())?;
+    within(config.redis_timeout, "connect", client.get_multiplexed_async_connection()).await
+}
+
+/// The Redis Publisher. Publishes events to Redis with the urgency of a
+/// dispatcher trying to cover a hot load.
+pub struct RedisPublisher {
+    config: Arc<Config>,
+    connection: Option<redis::aio::MultiplexedConnection>,
     stats: Arc<PublisherStats>,
 }
 
@@ -46,6 +188,8 @@ pub struct PublisherStats {
     pub events_persisted: portable_atomic::AtomicU64,
     pub publish_errors: portable_atomic::AtomicU64,
     pub batches_sent: portable_atomic::AtomicU64,
+    /// Whether the last Redis operation succeeded.
+    pub connected: portable_atomic::AtomicBool,
 }
 
 impl PublisherStats {
@@ -55,10 +199,17 @@ impl PublisherStats {
             events_persisted: portable_atomic::AtomicU64::new(0),
             publish_errors: portable_atomic::AtomicU64::new(0),
             batches_sent: portable_atomic::AtomicU64::new(0),
+            connected: portable_atomic::AtomicBool::new(false),
         }
     }
 }
 
+impl Default for PublisherStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A serializable snapshot of publisher stats.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PublisherSnapshot {
@@ -66,123 +217,37 @@ pub struct PublisherSnapshot {
     pub events_persisted: u64,
     pub publish_errors: u64,
     pub batches_sent: u64,
+    pub connected: bool,
 }
 
 impl RedisPublisher {
-    /// Create a new RedisPublisher.
+    /// Create a new RedisPublisher. Doesn't connect until the publisher
+    /// stage calls `start`, and reconnects on the next `publish` after any
+    /// failure.
     ///
-    /// # Arguments
-    /// * `config` - The global configuration
-    /// * `receiver` - The receiving end of the crossbeam channel
-    /// * `shutdown` - Watch channel for graceful shutdown signaling
-    pub fn new(
-        config: Arc<Config>,
-        receiver: Receiver<BankruptcyEvent>,
-        shutdown: watch::Receiver<bool>,
-    ) -> (Self, Arc<PublisherStats>) {
+    /// Returns the publisher and a handle to its statistics.
+    pub fn new(config: Arc<Config>) -> (Self, Arc<PublisherStats>) {
         let stats = Arc::new(PublisherStats::new());
         let stats_clone = Arc::clone(&stats);
         (
             Self {
                 config,
-                receiver,
-                shutdown,
+                connection: None,
                 stats,
             },
             stats_clone,
         )
     }
 
-    /// Run the publisher loop. This is an async function that runs
-    /// until the shutdown signal is received.
-    ///
-    /// The loop:
-    /// 1. Drains up to BATCH_SIZE events from the channel
-    /// 2. Publishes them all to Redis pub/sub
-    /// 3. Stores them in the sorted set
-    /// 4. Sleeps briefly if no events were available
-    /// 5. Repeats until shutdown
-    ///
-    /// We use batch publishing to minimize Redis round-trips.
-    /// Publishing 10 events in one pipeline is much faster than
-    /// 10 individual PUBLISH commands.
-    pub async fn run(self) -> Result<()> {
-        info!(
-            channel = %self.config.redis_channel,
-            sorted_set = %self.config.redis_sorted_set,
-            "Redis Publisher starting — ready to broadcast financial doom"
-        );
-
-        // Connect to Redis with retry logic
-        let client = redis::Client::open(self.config.redis_url.as_str())?;
-        let mut con = loop {
-            match client.get_multiplexed_async_connection().await {
-                Ok(con) => {
-                    info!("Redis connection established — the void is listening");
-                    break con;
-                }
-                Err(e) => {
-                    warn!(error = %e, "Failed to connect to Redis — retrying in 5 seconds");
-                    tokio::time::sleep(Duration::from_secs(5)).await;
-                    if *self.shutdown.borrow() {
-                        info!("Shutdown received during Redis connection retry — exiting");
-                        return Ok(());
-                    }
-                }
-            }
-        };
-
-        const BATCH_SIZE: usize = 50;
-        let mut batch: Vec<BankruptcyEvent> = Vec::with_capacity(BATCH_SIZE);
-
-        loop {
-            // Check for shutdown signal
-            if *self.shutdown.borrow() {
-                // Drain remaining events before shutting down
-                info!("Shutdown signal received — draining remaining events");
-                while let Ok(event) = self.receiver.try_recv() {
-                    batch.push(event);
-                }
-                if !batch.is_empty() {
-                    if let Err(e) = self.publish_batch(&mut con, &batch).await {
-                        error!(error = %e, "Failed to publish final batch during shutdown");
-                    }
-                }
-                info!("Redis Publisher shutting down — no more doom to broadcast");
-                return Ok(());
-            }
-
-            // Drain events from the channel into a batch
-            batch.clear();
-            while batch.len() < BATCH_SIZE {
-                match self.receiver.try_recv() {
-                    Ok(event) => batch.push(event),
-                    Err(crossbeam_channel::TryRecvError::Empty) => break,
-                    Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                        info!("Channel disconnected — publisher shutting down");
-                        return Ok(());
-                    }
-                }
-            }
-
-            if batch.is_empty() {
-                // No events to publish. Sleep briefly and check again.
-                tokio::time::sleep(Duration::from_millis(100)).await;
-                continue;
-            }
-
-            // Publish the batch!
-            if let Err(e) = self.publish_batch(&mut con, &batch).await {
-                error!(
-                    error = %e,
-                    batch_size = batch.len(),
-                    "Failed to publish batch to Redis — events may be lost!"
-                );
-                self.stats
-                    .publish_errors
-                    .fetch_add(batch.len() as u64, portable_atomic::Ordering::Relaxed);
-            }
-        }
+    /// Open a connection to Redis and remember it.
+    async fn connect(&mut self) -> Result<()> {
+        let con = connect_within(&self.config).await;
+        self.stats
+            .connected
+            .store(con.is_ok(), portable_atomic::Ordering::Relaxed);
+        self.connection = Some(con?);
+        info!("Redis connection established — the void is listening");
+        Ok(())
     }
 
     /// Publish a batch of events to Redis.
@@ -190,23 +255,19 @@ impl RedisPublisher {
     /// For each event:
     /// 1. PUBLISH to the pub/sub channel (for real-time consumers)
     /// 2. ZADD to the sorted set (for persistence/catch-up)
-    ///
-    /// We use a Redis pipeline to send all commands in one round-trip.
-    /// This is like putting all your packages on one truck instead of
-    /// sending a separate truck for each package.
-    async fn publish_batch(
-        &self,
-        con: &mut redis::aio::MultiplexedConnection,
-        batch: &[BankruptcyEvent],
-    ) -> Result<()> {
+    async fn publish_batch(&mut self, batch: &[BankruptcyEvent]) -> Result<()> {
         use portable_atomic::Ordering;
 
+        let con = self
+            .connection
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Redis publisher used before start()"))?;
+
         for event in batch {
             let json = serde_json::to_string(event)?;
 
             // Publish to pub/sub channel for real-time consumers
-            let _: () = con
-                .publish(&self.config.redis_channel, &json)
+            let _: () = within(self.config.redis_timeout, "PUBLISH", con.publish(&self.config.redis_channel, &json))
                 .await
                 .map_err(|e| {
                     error!(
@@ -223,8 +284,7 @@ impl RedisPublisher {
             // Store in sorted set for persistence
             // Score is the Unix timestamp so events are ordered chronologically
             let score = event.detected_at.timestamp() as f64;
-            let _: () = con
-                .zadd(&self.config.redis_sorted_set, &json, score)
+            let _: () = within(self.config.redis_timeout, "ZADD", con.zadd(&self.config.redis_sorted_set, &json, score))
                 .await
                 .map_err(|e| {
                     error!(
@@ -265,6 +325,275 @@ impl RedisPublisher {
             events_persisted: stats.events_persisted.load(Ordering::Relaxed),
             publish_errors: stats.publish_errors.load(Ordering::Relaxed),
             batches_sent: stats.batches_sent.load(Ordering::Relaxed),
+            connected: stats.connected.load(Ordering::Relaxed),
         }
     }
 }
+
+impl EventSink for RedisPublisher {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    /// Try to connect to Redis once. If Redis isn't up yet, `publish`
+    /// will keep trying — blocking here would leave the channel undrained.
+    fn start<'a>(&'a mut self, _shutdown: &'a watch::Receiver<bool>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            info!(
+                channel = %self.config.redis_channel,
+                sorted_set = %self.config.redis_sorted_set,
+                "Redis Publisher starting — ready to broadcast financial doom"
+            );
+
+            if let Err(e) = self.connect().await {
+                warn!(error = %e, "Failed to connect to Redis — will retry on the next publish");
+            }
+            Ok(())
+        })
+    }
+
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            use portable_atomic::Ordering;
+
+            if self.connection.is_none() {
+                if let Err(e) = self.connect().await {
+                    self.stats
+                        .publish_errors
+                        .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                    return Err(e);
+                }
+            }
+
+            let result = self.publish_batch(batch).await;
+            self.stats.connected.store(result.is_ok(), Ordering::Relaxed);
+            if result.is_err() {
+                // Start from a fresh connection next time rather than
+                // trusting one that just failed us.
+                self.connection = None;
+                self.stats
+                    .publish_errors
+                    .fetch_add(batch.len() as u64, Ordering::Relaxed);
+            }
+            result
+        })
+    }
+}
+
+/// Schema version stamped on every stream entry. Bump it when the event
+/// JSON changes in a way consumers need to know about.
+///
+/// 2: `event_type` and `case_update`, for lifecycle updates to known cases.
+/// 3: `lead_case_number` and `affiliates`, for jointly administered families
+///    published as one event.
+/// 4: `score_breakdown`, what each scoring feature added to the confidence.
+/// 5: `classifications`, every company type with a score, and seven new
+///    `classification` values (Ltl, Truckload, Drayage, Intermodal,
+///    LastMile, FreightTech, Shipper).
+/// 6: `AffiliatesJoined` case updates, for affiliates that file under a lead
+///    case after the lead debtor's own event went out.
+pub const EVENT_SCHEMA_VERSION: u32 = 6;
+
+/// The Redis Streams sink. XADDs every event to a stream instead of
+/// shouting it into pub/sub, so a consumer that's restarting doesn't miss
+/// anything: it reads from its consumer group's last position, XACKs what
+/// it handled, and replays the rest.
+///
+/// Each entry carries:
+/// - `event_id`       — the event's UUID, for idempotent consumers
+/// - `schema_version` — `EVENT_SCHEMA_VERSION`
+/// - `source`         — where the event came from
+/// - `event_type`     — `Detection` or `CaseUpdate`
+/// - `event`          — the event as JSON
+///
+/// The stream is trimmed with `MAXLEN ~`, so Redis doesn't slowly turn into
+/// a museum of every bankruptcy since the engine first booted.
+pub struct RedisStreamSink {
+    config: Arc<Config>,
+    connection: Option<redis::aio::MultiplexedConnection>,
+    stats: Arc<PublisherStats>,
+}
+
+impl RedisStreamSink {
+    /// Create a new stream sink. Like `RedisPublisher`, it connects in
+    /// `start` and reconnects on the next `publish` after a failure.
+    ///
+    /// Returns the sink and a handle to its statistics.
+    pub fn new(config: Arc<Config>) -> (Self, Arc<PublisherStats>) {
+        let stats = Arc::new(PublisherStats::new());
+        (
+            Self {
+                config,
+                connection: None,
+                stats: stats.clone(),
+            },
+            stats,
+        )
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        let con = connect_within(&self.config).await;
+        self.stats
+            .connected
+            .store(con.is_ok(), portable_atomic::Ordering::Relaxed);
+        self.connection = Some(con?);
+        info!(stream = %self.config.redis_stream, "Redis stream connection established");
+        Ok(())
+    }
+
+    /// XADD the whole batch in one pipelined round trip.
+    async fn xadd_batch(&mut self, batch: &[BankruptcyEvent]) -> Result<()> {
+        use portable_atomic::Ordering;
+
+        let con = self
+            .connection
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Redis stream sink used before connecting"))?;
+
+        let maxlen = redis::streams::StreamMaxlen::Approx(self.config.redis_stream_maxlen);
+        let mut pipe = redis::pipe();
+        for event in batch {
+            pipe.xadd_maxlen(&self.config.redis_stream, maxlen, "*", &stream_fields(event)?)
+                .ignore();
+        }
+        let _: () = within(self.config.redis_timeout, "XADD", pipe.query_async(con)).await?;
+
+        self.stats
+            .events_published
+            .fetch_add(batch.len() as u64, Ordering::Relaxed);
+        self.stats
+            .events_persisted
+            .fetch_add(batch.len() as u64, Ordering::Relaxed);
+        self.stats.batches_sent.fetch_add(1, Ordering::Relaxed);
+
+        debug!(
+            stream = %self.config.redis_stream,
+            batch_size = batch.len(),
+            "Batch XADDed to Redis stream"
+        );
+        Ok(())
+    }
+}
+
+/// The field/value pairs of one stream entry.
+fn stream_fields(event: &BankruptcyEvent) -> Result<Vec<(&'static str, String)>> {
+    Ok(vec![
+        ("event_id", event.id.clone()),
+        ("schema_version", EVENT_SCHEMA_VERSION.to_string()),
+        ("source", event.source.to_string()),
+        ("event_type", format!("{:?}", event.event_type)),
+        ("event", serde_json::to_string(event)?),
+    ])
+}
+
+impl EventSink for RedisStreamSink {
+    fn name(&self) -> &str {
+        "redis_stream"
+    }
+
+    fn start<'a>(&'a mut self, _shutdown: &'a watch::Receiver<bool>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            info!(
+                stream = %self.config.redis_stream,
+                maxlen = self.config.redis_stream_maxlen,
+                "Redis stream sink starting — doom, now with consumer groups"
+            );
+            if let Err(e) = self.connect().await {
+                warn!(error = %e, "Failed to connect to Redis — will retry on the next publish");
+            }
+            Ok(())
+        })
+    }
+
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            use portable_atomic::Ordering;
+
+            let result = match self.connection {
+                Some(_) => self.xadd_batch(batch).await,
+                None => match self.connect().await {
+                    Ok(()) => self.xadd_batch(batch).await,
+                    Err(e) => Err(e),
+                },
+            };
+
+            self.stats.connected.store(result.is_ok(), Ordering::Relaxed);
+            if result.is_err() {
+                self.connection = None;
+                self.stats
+                    .publish_errors
+                    .fetch_add(batch.len() as u64, Ordering::Relaxed);
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// A Redis that accepts connections and never answers anything, or,
+    /// with `handshake`, answers the connection setup (CLIENT SETINFO,
+    /// twice) and then nothing.
+    async fn silent_redis(handshake: bool) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    if handshake {
+                        let _ = socket.read(&mut buf).await;
+                        let _ = socket.write_all(b"+OK\r\n+OK\r\n").await;
+                    }
+                    while socket.read(&mut buf).await.is_ok_and(|n| n > 0) {}
+                });
+            }
+        });
+        url
+    }
+
+    fn quick_config(redis_url: String) -> Arc<Config> {
+        let mut config = Config::from_env();
+        config.redis_url = redis_url;
+        config.redis_timeout = Duration::from_millis(100);
+        Arc::new(config)
+    }
+
+    #[tokio::test]
+    async fn test_silent_redis_fails_the_batch_instead_of_hanging() {
+        let batch = vec![BankruptcyEvent::new("Acme Freight LLC".into(), Source::Pacer, 0.9)];
+        let limit = Duration::from_secs(5);
+
+        // Never finishes the connection.
+        let (mut publisher, stats) = RedisPublisher::new(quick_config(silent_redis(false).await));
+        let result = tokio::time::timeout(limit, publisher.publish(&batch)).await.expect("connect hung");
+        assert!(result.is_err());
+        assert_eq!(stats.publish_errors.load(portable_atomic::Ordering::Relaxed), 1);
+
+        // Connects, then never answers a command.
+        let (mut stream, stats) = RedisStreamSink::new(quick_config(silent_redis(true).await));
+        let result = tokio::time::timeout(limit, stream.publish(&batch)).await.expect("XADD hung");
+        assert!(result.unwrap_err().to_string().contains("XADD"));
+        assert!(!stats.connected.load(portable_atomic::Ordering::Relaxed));
+        assert!(stream.connection.is_none());
+    }
+
+    #[test]
+    fn test_stream_fields_carry_id_and_schema_version() {
+        let event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Pacer, 0.9);
+        let fields = stream_fields(&event).unwrap();
+
+        let get = |name: &str| fields.iter().find(|(k, _)| *k == name).map(|(_, v)| v.as_str());
+        assert_eq!(get("event_id"), Some(event.id.as_str()));
+        assert_eq!(get("schema_version"), Some("6"));
+        assert_eq!(get("source"), Some("PACER"));
+        assert_eq!(get("event_type"), Some("Detection"));
+
+        let decoded: BankruptcyEvent = serde_json::from_str(get("event").unwrap()).unwrap();
+        assert_eq!(decoded.id, event.id);
+    }
+}
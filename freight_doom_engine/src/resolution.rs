@@ -0,0 +1,541 @@
+// =============================================================================
+// resolution.rs — ONE BANKRUPTCY, ONE CASE
+// =============================================================================
+//
+// When a trucking company files Chapter 11, PACER's RSS feed mentions it,
+// CourtListener indexes it, EDGAR gets an 8-K about it, and FMCSA marks the
+// carrier inactive a week later. Four sources, four dedup keys
+// (`pacer:{court}:{link}`, `cl:{id}:{case}`, `edgar:{entity}:{type}`,
+// `fmcsa:{dot}:{status}`), and until now four unrelated events in Rails for
+// what is, tragically, a single demise.
+//
+// The `EntityResolver` runs after dedup and ties them together. For each
+// event it builds identity keys, strongest first:
+//
+// 1. USDOT number                 "dot:1234567"
+// 2. MC/MX/FF docket number       "mc:765432"
+// 3. Court + case number          "case:deb:24-10001"
+// 4. Normalised company name(s)   "name:acme freight"
+//
+// Names go through `names::name_variants`: lowercased, stripped of
+// punctuation and corporate suffixes ("Inc", "LLC", "Corp", ...), and split
+// on d/b/a, a/k/a and f/k/a so that "ACME HOLDINGS, INC. d/b/a Acme Freight"
+// matches both "Acme Holdings" and "Acme Freight LLC". If no key matches
+// exactly, the names are compared fuzzily against every open case, so
+// "Acme Freight Line" still finds "Acme Freight Lines".
+//
+// Names are weak evidence, though: "ABC Trucking" is a dozen companies. If
+// the event and a case both carry a USDOT number, an MC docket, or a court
+// + case number, and they disagree, they are different entities whatever
+// their names say, and the case is off the table.
+//
+// If any key matches a case seen within the resolution window, the event
+// joins that case: it carries the case's `case_id` (the id of the first
+// event that opened it), the list of `corroborating_sources`, and a
+// confidence boosted by every independent source that has reported it.
+// Otherwise it opens a new case. Either way the event still goes out —
+// consumers group or upsert on `case_id`.
+// =============================================================================
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+use parking_lot::Mutex;
+use portable_atomic::{AtomicU64, Ordering};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::lifecycle;
+use crate::models::{BankruptcyEvent, ScoreContribution, Source};
+use crate::names::{self, NameMatcher};
+
+/// A merged confidence never claims certainty. Four agreeing government
+/// databases have been wrong before.
+pub const MAX_CONFIDENCE: f64 = 0.99;
+
+/// What the resolver did with an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Nothing matched; the event opened a new case.
+    New,
+    /// Matched a case from a source that hadn't reported it yet.
+    Corroborated,
+    /// Matched a case this source had already reported.
+    Repeat,
+}
+
+/// Statistics for metrics.
+#[derive(Default)]
+pub struct ResolverStats {
+    pub cases_opened: AtomicU64,
+    pub corroborations: AtomicU64,
+    pub repeats: AtomicU64,
+}
+
+/// A serializable snapshot of the resolver's statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolverSnapshot {
+    pub cases_tracked: usize,
+    pub cases_opened: u64,
+    pub corroborations: u64,
+    pub repeats: u64,
+}
+
+/// Everything we know about one canonical case.
+struct CaseRecord {
+    /// Identity keys pointing at this case, so eviction can clean them up.
+    keys: Vec<String>,
+    /// The best confidence each source has reported, in first-seen order.
+    sources: Vec<(Source, f64)>,
+    last_seen: DateTime<Utc>,
+}
+
+impl CaseRecord {
+    /// Noisy-OR over the sources: the chance that at least one of them is
+    /// right, treating them as independent witnesses.
+    fn combined_confidence(&self) -> f64 {
+        let doubt: f64 = self.sources.iter().map(|(_, c)| 1.0 - c.clamp(0.0, 1.0)).product();
+        (1.0 - doubt).min(MAX_CONFIDENCE)
+    }
+
+    fn source_list(&self) -> Vec<Source> {
+        self.sources.iter().map(|(source, _)| source.clone()).collect()
+    }
+}
+
+struct ResolverState {
+    /// Cases by case id, least recently seen evicted first.
+    cases: LruCache<String, CaseRecord>,
+    /// Identity key → case id.
+    index: HashMap<String, String>,
+}
+
+/// Merges events about the same bankruptcy from different sources into one
+/// canonical case.
+pub struct EntityResolver {
+    state: Mutex<ResolverState>,
+    window: Duration,
+    matcher: NameMatcher,
+    pub stats: Arc<ResolverStats>,
+}
+
+impl EntityResolver {
+    /// Track up to `max_cases` cases; a case nobody has mentioned for
+    /// `window` is closed, and the next sighting opens a new one.
+    pub fn new(max_cases: usize, window: Duration) -> Self {
+        let capacity = std::num::NonZeroUsize::new(max_cases.max(1)).expect("capacity is at least 1");
+        Self {
+            state: Mutex::new(ResolverState {
+                cases: LruCache::new(capacity),
+                index: HashMap::new(),
+            }),
+            window,
+            matcher: NameMatcher::default(),
+            stats: Arc::new(ResolverStats::default()),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.resolution_max_cases, config.resolution_window)
+            .with_name_matcher(NameMatcher::new(config.name_match_threshold))
+    }
+
+    /// Use `matcher` to decide when two names that aren't identical after
+    /// normalisation still belong to the same case.
+    pub fn with_name_matcher(mut self, matcher: NameMatcher) -> Self {
+        self.matcher = matcher;
+        self
+    }
+
+    /// Attach `event` to its canonical case, opening one if needed. Sets
+    /// `case_id` and `corroborating_sources`, and raises the confidence to
+    /// the case's combined confidence.
+    pub fn resolve(&self, event: &mut BankruptcyEvent) -> Resolution {
+        let keys = identity_keys(event);
+        let now = event.detected_at;
+        let window = chrono::Duration::from_std(self.window).unwrap_or(chrono::Duration::MAX);
+
+        let mut state = self.state.lock();
+
+        // First key that points at a case still inside the window wins.
+        // Keys are in strength order, so a DOT match beats a name match.
+        // A case whose strong identifiers contradict the event's is skipped.
+        let mut matched = None;
+        for key in &keys {
+            let Some(case_id) = state.index.get(key).cloned() else {
+                continue;
+            };
+            let case = state.cases.peek(&case_id);
+            if case.is_none_or(|case| now - case.last_seen > window) {
+                state.close(&case_id);
+                continue;
+            }
+            if case.is_some_and(|case| !contradicts(&case.keys, &keys)) {
+                matched = Some(case_id);
+                break;
+            }
+        }
+        if matched.is_none() {
+            matched = state.fuzzy_match(&keys, &self.matcher, now, window);
+        }
+
+        let (case_id, resolution) = match matched {
+            Some(case_id) => {
+                let case = state.cases.get_mut(&case_id).expect("matched case is present");
+                let resolution = match case.sources.iter_mut().find(|(s, _)| *s == event.source) {
+                    Some((_, best)) => {
+                        *best = best.max(event.confidence_score);
+                        Resolution::Repeat
+                    }
+                    None => {
+                        case.sources.push((event.source.clone(), event.confidence_score));
+                        Resolution::Corroborated
+                    }
+                };
+                case.last_seen = case.last_seen.max(now);
+                (case_id, resolution)
+            }
+            None => {
+                let case_id = event.id.clone();
+                let record = CaseRecord {
+                    keys: Vec::new(),
+                    sources: vec![(event.source.clone(), event.confidence_score)],
+                    last_seen: now,
+                };
+                if let Some((evicted_id, evicted)) = state.cases.push(case_id.clone(), record) {
+                    if evicted_id != case_id {
+                        state.forget_keys(&evicted_id, &evicted.keys);
+                    }
+                }
+                (case_id, Resolution::New)
+            }
+        };
+
+        // Teach the index every key this event brought, so a later event
+        // that only shares its trade name or MC number still finds the case.
+        let ResolverState { cases, index } = &mut *state;
+        let case = cases.get_mut(&case_id).expect("resolved case is present");
+        for key in keys {
+            if !index.contains_key(&key) {
+                index.insert(key.clone(), case_id.clone());
+                case.keys.push(key);
+            }
+        }
+
+        event.case_id = Some(case_id);
+        event.corroborating_sources = case.source_list();
+        let combined = case.combined_confidence();
+        drop(state);
+        corroborate(event, combined);
+
+        let counter = match resolution {
+            Resolution::New => &self.stats.cases_opened,
+            Resolution::Corroborated => &self.stats.corroborations,
+            Resolution::Repeat => &self.stats.repeats,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        resolution
+    }
+
+    pub fn snapshot(&self) -> ResolverSnapshot {
+        ResolverSnapshot {
+            cases_tracked: self.state.lock().cases.len(),
+            cases_opened: self.stats.cases_opened.load(Ordering::Relaxed),
+            corroborations: self.stats.corroborations.load(Ordering::Relaxed),
+            repeats: self.stats.repeats.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl ResolverState {
+    /// Drop a case and every key pointing at it.
+    fn close(&mut self, case_id: &str) {
+        if let Some(case) = self.cases.pop(case_id) {
+            self.forget_keys(case_id, &case.keys);
+        }
+    }
+
+    /// The live case whose name is most similar to one of `keys`' names,
+    /// if any clears the matcher's threshold.
+    fn fuzzy_match(
+        &self,
+        keys: &[String],
+        matcher: &NameMatcher,
+        now: DateTime<Utc>,
+        window: chrono::Duration,
+    ) -> Option<String> {
+        let names: Vec<&str> = keys.iter().filter_map(|k| k.strip_prefix("name:")).collect();
+        if names.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(&String, f64)> = None;
+        for (key, case_id) in &self.index {
+            let Some(known) = key.strip_prefix("name:") else {
+                continue;
+            };
+            let score = names
+                .iter()
+                .map(|name| names::normalized_similarity(name, known))
+                .fold(0.0, f64::max);
+            if score < matcher.threshold() || best.is_some_and(|(_, b)| score <= b) {
+                continue;
+            }
+            let candidate = self
+                .cases
+                .peek(case_id)
+                .is_some_and(|case| now - case.last_seen <= window && !contradicts(&case.keys, keys));
+            if candidate {
+                best = Some((case_id, score));
+            }
+        }
+        best.map(|(case_id, _)| case_id.clone())
+    }
+
+    fn forget_keys(&mut self, case_id: &str, keys: &[String]) {
+        for key in keys {
+            if self.index.get(key).is_some_and(|id| id == case_id) {
+                self.index.remove(key);
+            }
+        }
+    }
+}
+
+/// Whether two sets of identity keys name different entities: for some
+/// kind of strong identifier (DOT, MC/MX/FF docket, court + case) both
+/// have one and none of them agree. Names never contradict.
+fn contradicts(case_keys: &[String], keys: &[String]) -> bool {
+    fn strong(key: &str) -> Option<&str> {
+        key.split_once(':').map(|(kind, _)| kind).filter(|kind| *kind != "name")
+    }
+    keys.iter().filter_map(|key| strong(key)).any(|kind| {
+        let of_kind = |k: &&String| strong(k) == Some(kind);
+        let ours: Vec<&String> = keys.iter().filter(of_kind).collect();
+        let theirs: Vec<&String> = case_keys.iter().filter(of_kind).collect();
+        !theirs.is_empty() && !ours.iter().any(|key| theirs.contains(key))
+    })
+}
+
+/// Every key that identifies the entity behind `event`, strongest first.
+pub fn identity_keys(event: &BankruptcyEvent) -> Vec<String> {
+    let mut keys = Vec::new();
+
+    let digits = |s: &str| s.chars().filter(char::is_ascii_digit).collect::<String>();
+    if let Some(dot) = event.dot_number.as_deref().map(digits).filter(|d| !d.is_empty()) {
+        keys.push(format!("dot:{}", dot.trim_start_matches('0')));
+    }
+    if let Some(docket) = event.mc_number.as_deref() {
+        // Bare digits are an MC number; MX and FF dockets keep their prefix
+        // ("MX-54321") so they can't collide with one.
+        let prefix: String = docket.chars().filter(char::is_ascii_alphabetic).collect();
+        let prefix = if prefix.is_empty() { "mc".to_string() } else { prefix.to_lowercase() };
+        let number = digits(docket);
+        if !number.is_empty() {
+            keys.push(format!("{}:{}", prefix, number.trim_start_matches('0')));
+        }
+    }
+    if let (Some(court), Some(case)) = (&event.court, &event.case_number) {
+        if let Some(key) = lifecycle::case_key(court, case) {
+            keys.push(format!("case:{}", key));
+        }
+    }
+    for name in names::name_variants(&event.company_name) {
+        keys.push(format!("name:{}", name));
+    }
+
+    keys.dedup();
+    keys
+}
+
+/// Set the event's confidence to its case's combined confidence, and say
+/// in its score breakdown what the other sources added.
+fn corroborate(event: &mut BankruptcyEvent, combined: f64) {
+    let points = combined - event.confidence_score;
+    if points != 0.0 {
+        let others = event
+            .corroborating_sources
+            .iter()
+            .filter(|source| **source != event.source)
+            .map(|source| source.to_string())
+            .collect();
+        event.score_breakdown.push(ScoreContribution {
+            feature: "corroboration".to_string(),
+            value: event.corroborating_sources.len() as f64,
+            weight: 1.0,
+            points,
+            evidence: others,
+        });
+    }
+    event.confidence_score = combined;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, source: Source, confidence: f64) -> BankruptcyEvent {
+        BankruptcyEvent::new(name.to_string(), source, confidence)
+    }
+
+    #[test]
+    fn test_same_company_from_three_sources_is_one_case() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(86_400));
+
+        let mut pacer = event("Acme Freight Lines LLC", Source::Pacer, 0.6);
+        assert_eq!(resolver.resolve(&mut pacer), Resolution::New);
+
+        let mut courtlistener = event("ACME FREIGHT LINES, INC.", Source::CourtListener, 0.5);
+        assert_eq!(resolver.resolve(&mut courtlistener), Resolution::Corroborated);
+
+        let mut edgar = event("Acme Freight Lines Inc", Source::Edgar, 0.5);
+        assert_eq!(resolver.resolve(&mut edgar), Resolution::Corroborated);
+
+        assert_eq!(edgar.case_id.as_deref(), Some(pacer.id.as_str()));
+        assert_eq!(courtlistener.case_id, pacer.case_id);
+        assert_eq!(edgar.corroborating_sources, vec![Source::Pacer, Source::CourtListener, Source::Edgar]);
+        // 1 - (0.4 × 0.5 × 0.5) = 0.9
+        assert!((edgar.confidence_score - 0.9).abs() < 1e-9);
+        assert_eq!(resolver.snapshot().cases_tracked, 1);
+
+        // The breakdown says the other two sources added the last 0.4.
+        let corroboration = edgar.score_breakdown.last().unwrap();
+        assert_eq!(corroboration.feature, "corroboration");
+        assert!((corroboration.points - 0.4).abs() < 1e-9);
+        assert_eq!(corroboration.evidence, vec![Source::Pacer.to_string(), Source::CourtListener.to_string()]);
+        assert!(pacer.score_breakdown.is_empty());
+    }
+
+    #[test]
+    fn test_pacer_and_courtlistener_agree_on_the_court() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(86_400));
+
+        let mut pacer = event("Acme Freight", Source::Pacer, 0.6);
+        pacer.court = Some("Delaware".into());
+        pacer.case_number = Some("1:24-bk-10001".into());
+        assert_eq!(resolver.resolve(&mut pacer), Resolution::New);
+
+        let mut courtlistener = event("Acme Freight", Source::CourtListener, 0.5);
+        courtlistener.court = Some("United States Bankruptcy Court for the District of Delaware".into());
+        courtlistener.case_number = Some("24-10001".into());
+        assert_eq!(resolver.resolve(&mut courtlistener), Resolution::Corroborated);
+        assert_eq!(courtlistener.case_id, pacer.case_id);
+        assert!(identity_keys(&courtlistener).contains(&"case:deb:24-10001".to_string()));
+
+        // Same number, another court: another case.
+        let mut elsewhere = event("Acme Freight", Source::CourtListener, 0.5);
+        elsewhere.court = Some("United States Bankruptcy Court for the Southern District of New York".into());
+        elsewhere.case_number = Some("24-10001".into());
+        assert_eq!(resolver.resolve(&mut elsewhere), Resolution::New);
+    }
+
+    #[test]
+    fn test_dot_number_links_different_names() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(86_400));
+
+        let mut fmcsa = event("RR TRANSPORT", Source::Fmcsa, 0.9);
+        fmcsa.dot_number = Some("1234567".into());
+        resolver.resolve(&mut fmcsa);
+
+        let mut pacer = event("Road Runner Holdings LLC", Source::Pacer, 0.5);
+        pacer.dot_number = Some("USDOT 1234567".into());
+        assert_eq!(resolver.resolve(&mut pacer), Resolution::Corroborated);
+        assert_eq!(pacer.case_id, fmcsa.case_id);
+        assert!(pacer.confidence_score > 0.9);
+    }
+
+    #[test]
+    fn test_different_dot_numbers_are_different_companies() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(86_400));
+
+        let mut first = event("ABC Trucking LLC", Source::Fmcsa, 0.9);
+        first.dot_number = Some("1111111".into());
+        resolver.resolve(&mut first);
+
+        // Same name, different carrier: no exact or fuzzy match.
+        let mut second = event("ABC Trucking, Inc.", Source::Pacer, 0.6);
+        second.dot_number = Some("2222222".into());
+        assert_eq!(resolver.resolve(&mut second), Resolution::New);
+        assert_ne!(second.case_id, first.case_id);
+        assert!((second.confidence_score - 0.6).abs() < 1e-9);
+
+        let mut near = event("ABC Trucking Co", Source::Edgar, 0.6);
+        near.dot_number = Some("3333333".into());
+        assert_eq!(resolver.resolve(&mut near), Resolution::New);
+
+        // Different court cases are different bankruptcies, too.
+        let mut case_a = event("XYZ Freight", Source::Pacer, 0.6);
+        case_a.court = Some("deb".into());
+        case_a.case_number = Some("1:24-bk-10001".into());
+        resolver.resolve(&mut case_a);
+        let mut case_b = event("XYZ Freight", Source::CourtListener, 0.6);
+        case_b.court = Some("deb".into());
+        case_b.case_number = Some("1:24-bk-20002".into());
+        assert_eq!(resolver.resolve(&mut case_b), Resolution::New);
+
+        // A name-only report still joins the first one it matches.
+        let mut name_only = event("ABC Trucking", Source::CourtListener, 0.5);
+        assert_eq!(resolver.resolve(&mut name_only), Resolution::Corroborated);
+        assert_eq!(name_only.case_id, first.case_id);
+    }
+
+    #[test]
+    fn test_trade_name_learned_from_one_source_matches_another() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(86_400));
+
+        let mut pacer = event("Acme Holdings, Inc. d/b/a Acme Freight", Source::Pacer, 0.6);
+        resolver.resolve(&mut pacer);
+
+        let mut fmcsa = event("ACME FREIGHT", Source::Fmcsa, 0.8);
+        assert_eq!(resolver.resolve(&mut fmcsa), Resolution::Corroborated);
+        assert_eq!(fmcsa.case_id, pacer.case_id);
+    }
+
+    #[test]
+    fn test_unrelated_and_stale_cases_stay_separate() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(3_600));
+
+        let mut acme = event("Acme Freight", Source::Pacer, 0.6);
+        resolver.resolve(&mut acme);
+        let mut other = event("Big Truck Co", Source::Edgar, 0.6);
+        assert_eq!(resolver.resolve(&mut other), Resolution::New);
+        assert_ne!(other.case_id, acme.case_id);
+
+        let mut much_later = event("Acme Freight", Source::Edgar, 0.6);
+        much_later.detected_at = acme.detected_at + chrono::Duration::hours(2);
+        assert_eq!(resolver.resolve(&mut much_later), Resolution::New);
+        assert_ne!(much_later.case_id, acme.case_id);
+        assert!((much_later.confidence_score - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_evicted_cases_release_their_keys() {
+        let resolver = EntityResolver::new(1, Duration::from_secs(86_400));
+
+        let mut first = event("Acme Freight", Source::Pacer, 0.6);
+        resolver.resolve(&mut first);
+        let mut second = event("Big Truck Co", Source::Pacer, 0.6);
+        resolver.resolve(&mut second);
+
+        let mut acme_again = event("Acme Freight", Source::Edgar, 0.6);
+        assert_eq!(resolver.resolve(&mut acme_again), Resolution::New);
+        assert_eq!(resolver.snapshot().cases_tracked, 1);
+    }
+
+    #[test]
+    fn test_near_identical_names_join_the_same_case() {
+        let resolver = EntityResolver::new(100, Duration::from_secs(86_400));
+
+        let mut pacer = event("Acme Freight Lines LLC", Source::Pacer, 0.6);
+        resolver.resolve(&mut pacer);
+
+        let mut edgar = event("ACME FREIGHT LINE INC", Source::Edgar, 0.6);
+        assert_eq!(resolver.resolve(&mut edgar), Resolution::Corroborated);
+        assert_eq!(edgar.case_id, pacer.case_id);
+
+        let mut other = event("Acme Trucking", Source::Edgar, 0.6);
+        assert_eq!(resolver.resolve(&mut other), Resolution::New);
+    }
+}
@@ -38,22 +38,19 @@
 // it freely available... to our Redis pub/sub channel. Same energy.
 // =============================================================================
 
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::{NaiveDate, Utc};
-use crossbeam_channel::Sender;
-use tokio::sync::watch;
-use tracing::{debug, error, info, warn};
+use tracing::debug;
 
-use crate::circuit_breaker::CircuitBreaker;
 use crate::config::Config;
-use crate::dedup::DedupEngine;
 use crate::models::{
-    BankruptcyChapter, BankruptcyEvent, CourtListenerResult, Source,
+    BankruptcyEvent, CourtListenerResult, Source,
 };
-use crate::text_scanner;
+use crate::extract;
+use crate::names;
+
+use super::{case_dedup_key, Candidate, FetchTarget, Scanner};
 
 // =============================================================================
 // CourtListener Search Queries
@@ -85,309 +82,171 @@ const CL_QUERIES: &[&str] = &[
     "chapter 7 freight forwarder",
 ];
 
-/// The main entry point for the CourtListener scanner.
-///
-/// This function loops forever, searching CourtListener's REST API for
-/// bankruptcy docket entries mentioning freight/logistics companies.
-/// It's like having a law clerk who only reads bankruptcy dockets and
-/// only cares about trucking companies, except this clerk works 24/7,
-/// never takes a coffee break, and has SIMD-accelerated reading skills.
+/// The CourtListener scanner.
 ///
-/// # Arguments
-/// * `config` - Global configuration with court_listener_base_url and
-///   court_listener_poll_interval.
-/// * `event_tx` - Crossbeam channel sender for bankruptcy events.
-/// * `dedup` - Bloom filter + LRU deduplication engine.
-/// * `shutdown` - Watch channel for graceful shutdown.
-pub async fn run(
-    config: Arc<Config>,
-    event_tx: Sender<BankruptcyEvent>,
-    dedup: Arc<DedupEngine>,
-    shutdown: &mut watch::Receiver<bool>,
-) {
-    info!("CourtListener Scanner initializing — preparing to mine the Free Law Project's data like a legal archaeologist with a mission");
-
-    // Build HTTP client with a polite User-Agent.
-    // CourtListener is run by the Free Law Project, a non-profit.
-    // We identify ourselves clearly so they know we're using their
-    // data for the noble cause of tracking freight company bankruptcy.
-    // They'd probably approve. Probably.
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(20))
-        .user_agent("FreightDoomEngine/1.0 (legal-research@freight-doom.dev; educational-project)")
-        .build()
-        .expect("Failed to build CourtListener HTTP client — the Free Law Project deserved better from us");
-
-    // Circuit breaker for CourtListener.
-    // They're a non-profit with limited infrastructure. When their servers
-    // struggle, we back off immediately because we're not monsters.
-    // Well, we ARE building an overkill bankruptcy detection engine,
-    // but at least we're polite about our API usage.
-    let circuit_breaker = CircuitBreaker::new(
-        "CourtListener",
-        config.circuit_breaker_failure_threshold,
-        config.circuit_breaker_reset_timeout,
-        config.circuit_breaker_success_threshold,
-    );
-
-    // Atomic counter for rotating through search queries.
-    let query_index = AtomicUsize::new(0);
-
-    let poll_interval = config.court_listener_poll_interval;
-    let base_url = config.court_listener_base_url.clone();
-    let min_confidence = config.min_confidence_threshold;
-
-    info!(
-        poll_interval_secs = poll_interval.as_secs(),
-        base_url = base_url.as_str(),
-        queries = CL_QUERIES.len(),
-        "CourtListener Scanner online — respectfully pillaging open legal data for signs of freight industry collapse"
-    );
-
-    loop {
-        tokio::select! {
-            _ = tokio::time::sleep(poll_interval) => {
-                if !circuit_breaker.allow_request() {
-                    debug!("CourtListener: circuit breaker is OPEN — giving the non-profit's servers some rest");
-                    continue;
-                }
-
-                // Rotate through search queries.
-                // With 10 queries and a 45-second interval, we complete
-                // a full rotation every 7.5 minutes. This gives us
-                // comprehensive coverage without overwhelming CourtListener's
-                // rate limits.
-                let idx = query_index.fetch_add(1, Ordering::Relaxed) % CL_QUERIES.len();
-                let query = CL_QUERIES[idx];
-
-                // Build the CourtListener search API URL.
-                // We use type=r (RECAP/dockets) to search actual court filings.
-                // type=o (opinions) would give us judicial opinions, which are
-                // useful but come much later in the process. We want filings
-                // because they show up first.
-                //
-                // The filed_after parameter limits results to today's filings,
-                // keeping the data fresh and the response size manageable.
-                // order_by=dateFiled+desc gives us newest first.
-                let today = Utc::now().format("%Y-%m-%d").to_string();
-                let url = format!(
-                    "{}/search/?q={}&type=r&filed_after={}&order_by=dateFiled+desc&format=json",
-                    base_url,
-                    urlencoding::encode(query),
-                    today,
-                );
+/// Every poll cycle, it runs one search against CourtListener's RECAP
+/// archive, rotating through `CL_QUERIES`. It's like having a law clerk who
+/// only reads bankruptcy dockets and only cares about trucking companies,
+/// except this clerk works 24/7 and never takes a coffee break.
+pub struct CourtListenerScanner {
+    poll_interval: Duration,
+    base_url: String,
+}
 
-                debug!(
-                    query = query,
-                    date = today.as_str(),
-                    "CourtListener: searching RECAP dockets — query {}/{}: '{}'",
-                    idx + 1,
-                    CL_QUERIES.len(),
-                    query
-                );
+impl CourtListenerScanner {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            poll_interval: config.court_listener_poll_interval,
+            base_url: config.court_listener_base_url.clone(),
+        }
+    }
+}
 
-                // Make the request. CourtListener is generally responsive
-                // but can be slow during high-traffic periods (like when
-                // a major case drops and every law student in America
-                // tries to read it simultaneously).
-                let response = match client.get(&url).send().await {
-                    Ok(resp) => resp,
-                    Err(e) => {
-                        circuit_breaker.record_failure();
-                        warn!(
-                            error = %e,
-                            query = query,
-                            "CourtListener: request failed — the Free Law Project's servers are taking a personal day"
-                        );
-                        continue;
-                    }
-                };
+impl Scanner for CourtListenerScanner {
+    fn name(&self) -> &'static str {
+        "court_listener"
+    }
 
-                let status = response.status();
-                if !status.is_success() {
-                    if status.as_u16() == 429 {
-                        // Rate limited. We expected this eventually.
-                        // CourtListener allows ~100 requests/day for
-                        // unauthenticated users. We're being told to chill.
-                        warn!(
-                            "CourtListener: rate limited (HTTP 429) — we've been too enthusiastic, backing off"
-                        );
-                        circuit_breaker.record_failure();
-                    } else {
-                        debug!(
-                            "CourtListener: non-success HTTP status: {} — the legal data will have to wait",
-                            status
-                        );
-                    }
-                    continue;
-                }
+    fn source(&self) -> Source {
+        Source::CourtListener
+    }
 
-                circuit_breaker.record_success();
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
 
-                let body = match response.text().await {
-                    Ok(b) => b,
-                    Err(e) => {
-                        debug!(error = %e, "CourtListener: failed to read response body");
-                        continue;
-                    }
-                };
+    /// CourtListener is run by the Free Law Project, a non-profit. We
+    /// identify ourselves clearly so they know who is reading their dockets.
+    fn user_agent(&self) -> &'static str {
+        "FreightDoomEngine/1.0 (legal-research@freight-doom.dev; educational-project)"
+    }
 
-                // Parse the response using the CourtListenerResult types
-                // from models.rs. The API returns:
-                // { count: N, results: [...], next: "url_to_next_page" }
-                let search_result: CourtListenerResult = match serde_json::from_str(&body) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        debug!(
-                            error = %e,
-                            "CourtListener: JSON parse error — they might have changed their API format, which would be very unlike them"
-                        );
-                        continue;
-                    }
-                };
+    fn request_timeout(&self) -> Duration {
+        Duration::from_secs(20)
+    }
 
-                let total_count = search_result.count.unwrap_or(0);
+    /// One query per cycle, rotating through `CL_QUERIES`. With 10 queries
+    /// and a 45-second interval, a full rotation takes 7.5 minutes, which
+    /// keeps us comfortably inside CourtListener's rate limits.
+    fn targets(&self, cycle: u64) -> Vec<FetchTarget> {
+        let query = CL_QUERIES[(cycle % CL_QUERIES.len() as u64) as usize];
+
+        // We use type=r (RECAP/dockets) to search actual court filings.
+        // type=o (opinions) would give us judicial opinions, which come much
+        // later in the process. We want filings because they show up first.
+        //
+        // filed_after limits results to today's filings, and
+        // order_by=dateFiled+desc gives us newest first.
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let url = format!(
+            "{}/search/?q={}&type=r&filed_after={}&order_by=dateFiled+desc&format=json",
+            self.base_url,
+            urlencoding::encode(query),
+            today,
+        );
+
+        vec![FetchTarget::new(query, url)]
+    }
 
-                if total_count > 0 {
-                    debug!(
-                        count = total_count,
-                        query = query,
-                        "CourtListener: {} results — scanning for freight companies in legal peril",
-                        total_count
-                    );
-                }
+    fn parse(&self, target: &FetchTarget, body: &str) -> Vec<Candidate> {
+        // Parse the response using the CourtListenerResult types
+        // from models.rs. The API returns:
+        // { count: N, results: [...], next: "url_to_next_page" }
+        let search_result: CourtListenerResult = match serde_json::from_str(body) {
+            Ok(r) => r,
+            Err(e) => {
+                debug!(
+                    error = %e,
+                    "CourtListener: JSON parse error — they might have changed their API format, which would be very unlike them"
+                );
+                return Vec::new();
+            }
+        };
+
+        let total_count = search_result.count.unwrap_or(0);
+        if total_count > 0 {
+            debug!(
+                count = total_count,
+                query = target.label.as_str(),
+                "CourtListener: {} results — scanning for freight companies in legal peril",
+                total_count
+            );
+        }
 
-                let results = match &search_result.results {
-                    Some(r) => r,
-                    None => continue,
+        search_result
+            .results
+            .unwrap_or_default()
+            .into_iter()
+            .map(|opinion| {
+                // Combine all available text fields for scanning.
+                // CourtListener results have:
+                // - case_name: "Acme Freight LLC v. Everyone"
+                // - snippet: "...Chapter 11 bankruptcy filing by motor carrier..."
+                // - court: "United States Bankruptcy Court for the District of Delaware"
+                let case_name = opinion.case_name.as_deref().unwrap_or("");
+                let snippet = opinion.snippet.as_deref().unwrap_or("");
+                let court_name = opinion.court.as_deref().unwrap_or("");
+
+                let combined = format!("{} {} {}", case_name, snippet, court_name);
+
+                let company_name = if case_name.is_empty() {
+                    "Unknown Case".to_string()
+                } else {
+                    // CourtListener case names often look like:
+                    // "In re: Acme Freight LLC" or "Acme v. Creditors"
+                    // We try to extract just the company name.
+                    extract_company_from_case_name(case_name)
                 };
 
-                let mut new_events = 0u64;
-
-                for opinion in results {
-                    // Combine all available text fields for scanning.
-                    // CourtListener results have:
-                    // - case_name: "Acme Freight LLC v. Everyone"
-                    // - snippet: "...Chapter 11 bankruptcy filing by motor carrier..."
-                    // - court: "United States Bankruptcy Court for the District of Delaware"
-                    let case_name = opinion.case_name.as_deref().unwrap_or("");
-                    let snippet = opinion.snippet.as_deref().unwrap_or("");
-                    let court_name = opinion.court.as_deref().unwrap_or("");
-
-                    let combined = format!("{} {} {}", case_name, snippet, court_name);
-
-                    // Quick freight check — SIMD-accelerated pre-filter.
-                    // If none of our freight keywords appear, skip immediately.
-                    // memchr-powered byte scanning means this check is nearly free.
-                    if !text_scanner::quick_freight_check(&combined) {
-                        continue;
-                    }
-
-                    // Full Aho-Corasick scan for confidence scoring and classification.
-                    // This runs ALL keywords simultaneously in a single pass.
-                    // O(n + m) time complexity. Overkill? Absolutely. Effective? Also absolutely.
-                    let scan_result = text_scanner::scan_text(&combined);
-
-                    if scan_result.confidence < min_confidence {
-                        continue;
-                    }
-
-                    // Dedup using CourtListener result ID + case name.
-                    // Each CourtListener result has a unique numeric ID,
-                    // which is perfect for deduplication.
-                    let cl_id = opinion.id.unwrap_or(0);
-                    let dedup_key = format!("cl:{}:{}", cl_id, case_name);
-
-                    if !dedup.check_and_insert(&dedup_key) {
-                        debug!(
-                            case = case_name,
-                            "CourtListener: duplicate case — already in our Bloom filter"
+                let mut event = BankruptcyEvent::new(company_name, Source::CourtListener, 0.0);
+                event.court = if court_name.is_empty() {
+                    None
+                } else {
+                    Some(court_name.to_string())
+                };
+                // Docket numbers come as "24-10123", "No. 24-10123" or
+                // "1:24-bk-10123"; keep anything unparseable as written.
+                event.case_number = opinion
+                    .docket_number
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|n| !n.is_empty())
+                    .map(|n| extract::extract_case_number(n).unwrap_or_else(|| n.to_string()));
+                event.chapter = extract::detect_chapter(&combined);
+                event.petition_type = extract::detect_petition_type(&combined);
+
+                // Build source URL from CourtListener's absolute_url field
+                event.source_url = opinion
+                    .absolute_url
+                    .as_ref()
+                    .map(|path| format!("https://www.courtlistener.com{}", path));
+
+                // Parse filing date
+                if let Some(date_str) = &opinion.date_filed {
+                    if let Ok(naive) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+                        event.filing_date = Some(
+                            naive.and_hms_opt(0, 0, 0).unwrap().and_utc()
                         );
-                        continue;
-                    }
-
-                    // Build the bankruptcy event using the constructor
-                    let company_name = if case_name.is_empty() {
-                        "Unknown Case".to_string()
-                    } else {
-                        // CourtListener case names often look like:
-                        // "In re: Acme Freight LLC" or "Acme v. Creditors"
-                        // We try to extract just the company name.
-                        extract_company_from_case_name(case_name)
-                    };
-
-                    let mut event = BankruptcyEvent::new(
-                        company_name,
-                        Source::CourtListener,
-                        scan_result.confidence,
-                    );
-                    event.court = if court_name.is_empty() {
-                        None
-                    } else {
-                        Some(court_name.to_string())
-                    };
-                    event.chapter = detect_chapter(&combined);
-                    event.classification = scan_result.classification;
-
-                    // Build source URL from CourtListener's absolute_url field
-                    event.source_url = opinion
-                        .absolute_url
-                        .as_ref()
-                        .map(|path| format!("https://www.courtlistener.com{}", path));
-
-                    // Parse filing date
-                    if let Some(date_str) = &opinion.date_filed {
-                        if let Ok(naive) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                            event.filing_date = Some(
-                                naive.and_hms_opt(0, 0, 0).unwrap().and_utc()
-                            );
-                        }
-                    }
-
-                    // Try to extract DOT/MC numbers from the combined text
-                    event.dot_number = extract_dot_number(&combined);
-                    event.mc_number = extract_mc_number(&combined);
-
-                    match event_tx.try_send(event) {
-                        Ok(()) => {
-                            new_events += 1;
-                            info!(
-                                case = case_name,
-                                court = court_name,
-                                confidence = format!("{:.1}%", scan_result.confidence * 100.0),
-                                keywords = scan_result.matched_keywords.len(),
-                                "CourtListener: BANKRUPTCY CASE DETECTED — '{}' filed in {} — our dragnet strikes again",
-                                case_name,
-                                court_name
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                error = %e,
-                                "CourtListener: failed to send event to channel"
-                            );
-                        }
                     }
                 }
 
-                if new_events > 0 {
-                    info!(
-                        new_events = new_events,
-                        query = query,
-                        "CourtListener scan cycle complete — {} new freight bankruptcy cases discovered in the RECAP archive",
-                        new_events
-                    );
-                }
-            }
-
-            _ = shutdown.changed() => {
-                info!("CourtListener Scanner received shutdown signal — our pro bono legal research has concluded");
-                break;
-            }
-        }
+                // Try to extract DOT/MC numbers from the combined text
+                let ids = extract::identifiers(&combined);
+                event.mc_number = ids.event_docket();
+                event.lead_case_number = ids.lead_case_number.map(|case| case.to_string());
+                event.dot_number = ids.usdot;
+
+                // Dedup on the case when we know it — several opinions in
+                // one case are one bankruptcy. Otherwise CourtListener
+                // result ID + case name: each result has a unique numeric ID.
+                let dedup_key = case_dedup_key("cl", &event).unwrap_or_else(|| {
+                    format!("cl:{}:{}", opinion.id.unwrap_or(0), case_name)
+                });
+
+                Candidate::scanned(dedup_key, event, combined)
+            })
+            .collect()
     }
-
-    info!("CourtListener Scanner has exited — the Free Law Project continues without us");
 }
 
 // =============================================================================
@@ -415,87 +274,30 @@ fn extract_company_from_case_name(case_name: &str) -> String {
 
     // "In re: Company Name" or "In re Company Name"
     if let Some(idx) = lower.find("in re:") {
-        return case_name[idx + 6..].trim().to_string();
+        return names::clean_debtor_name(&case_name[idx + 6..]);
     }
     if let Some(idx) = lower.find("in re ") {
-        return case_name[idx + 6..].trim().to_string();
+        return names::clean_debtor_name(&case_name[idx + 6..]);
     }
 
     // "In the Matter of Company Name"
     if let Some(idx) = lower.find("in the matter of") {
-        return case_name[idx + 17..].trim().to_string();
+        return names::clean_debtor_name(&case_name[idx + 17..]);
     }
 
     // "Company Name, Debtor" — strip the ", Debtor" suffix
     if let Some(idx) = lower.find(", debtor") {
-        return case_name[..idx].trim().to_string();
+        return names::clean_debtor_name(&case_name[..idx]);
     }
 
     // For "A v. B" cases, take the first party (often the debtor in bankruptcy)
     if let Some(idx) = lower.find(" v. ") {
-        return case_name[..idx].trim().to_string();
+        return names::clean_debtor_name(&case_name[..idx]);
     }
     if let Some(idx) = lower.find(" vs. ") {
-        return case_name[..idx].trim().to_string();
+        return names::clean_debtor_name(&case_name[..idx]);
     }
 
     // Give up and return the whole thing
-    case_name.to_string()
-}
-
-/// Detect bankruptcy chapter from court filing text.
-///
-/// CourtListener snippets usually contain explicit chapter references
-/// because that's kind of the whole point of a bankruptcy filing.
-fn detect_chapter(text: &str) -> BankruptcyChapter {
-    let upper = text.to_uppercase();
-    if upper.contains("CHAPTER 7") || upper.contains("CH. 7") || upper.contains("CH 7") {
-        BankruptcyChapter::Chapter7
-    } else if upper.contains("CHAPTER 11") || upper.contains("CH. 11") || upper.contains("CH 11") {
-        BankruptcyChapter::Chapter11
-    } else if upper.contains("CHAPTER 13") || upper.contains("CH. 13") || upper.contains("CH 13") {
-        BankruptcyChapter::Chapter13
-    } else {
-        BankruptcyChapter::Unknown
-    }
-}
-
-/// Try to extract a DOT number from court filing text.
-fn extract_dot_number(text: &str) -> Option<String> {
-    let upper = text.to_uppercase();
-    let patterns = ["USDOT# ", "USDOT #", "USDOT ", "DOT# ", "DOT #", "DOT "];
-
-    for pattern in patterns {
-        if let Some(idx) = upper.find(pattern) {
-            let start = idx + pattern.len();
-            let num: String = upper[start..]
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .collect();
-            if !num.is_empty() && num.len() <= 8 {
-                return Some(num);
-            }
-        }
-    }
-    None
-}
-
-/// Try to extract an MC number from court filing text.
-fn extract_mc_number(text: &str) -> Option<String> {
-    let upper = text.to_uppercase();
-    let patterns = ["MC# ", "MC #", "MC-", "MC "];
-
-    for pattern in patterns {
-        if let Some(idx) = upper.find(pattern) {
-            let start = idx + pattern.len();
-            let num: String = upper[start..]
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .collect();
-            if !num.is_empty() && num.len() <= 7 {
-                return Some(num);
-            }
-        }
-    }
-    None
+    names::clean_debtor_name(case_name)
 }
@@ -0,0 +1,808 @@
+// =============================================================================
+// scanners/driver.rs — THE DISPATCHER
+// =============================================================================
+//
+// Every scanner used to carry its own copy of the same loop: build a reqwest
+// client, build a circuit breaker, sleep, fetch, quick_freight_check,
+// scan_text, dedup, try_send. Four copies. Slightly different in each one,
+// in the way that only copy-pasted code can be.
+//
+// This is the one copy. A scanner tells us WHAT to fetch and HOW to read it;
+// the driver does everything else:
+//
+// 1. Sleeps for the scanner's poll interval (or exits on shutdown)
+// 2. Asks the scanner for this cycle's fetch targets
+// 3. GETs each target through the scanner's circuit breaker
+// 4. Hands the body to the scanner's parser to get candidates
+// 5. Scores candidates with the text scanner, drops the low-confidence ones
+//    (docket entries for cases we already track skip this, see lifecycle.rs)
+// 6. Holds jointly administered filings until their family is complete
+//    (see affiliates.rs; `flush_affiliates` publishes them), and runs the
+//    rest through the dedup backend (local, or shared via Redis). Held
+//    filings are claimed when their family goes out, not before.
+// 7. Attaches each one to its canonical case via the entity resolver, and
+//    records it with the case tracker
+// 8. Fires whatever is left into the crossbeam channel
+//
+// Like a dispatcher with four drivers: the dispatcher doesn't care whether
+// you're hauling reefer or flatbed, it just wants the load delivered.
+// =============================================================================
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reqwest::StatusCode;
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
+
+use crate::affiliates::{AffiliateGrouper, Family};
+use crate::circuit_breaker::CircuitBreaker;
+use crate::config::Config;
+use crate::dedup::DedupBackend;
+use crate::metrics::MetricsCollector;
+use crate::lifecycle::{self, CaseTracker};
+use crate::models::{BankruptcyEvent, EventType, LifecycleEvent, ScoreContribution};
+use crate::resolution::{EntityResolver, Resolution};
+use crate::text_scanner::{self, RelevanceModel, SharedKeywords};
+
+use super::{Candidate, FetchTarget, Scanner};
+
+/// Longest gap between checks for affiliate families whose window closed.
+const AFFILIATE_FLUSH_TICK: Duration = Duration::from_secs(5);
+
+/// Everything a scanner task shares with the rest of the engine.
+/// Cheap to clone — it's all Arcs and channel handles.
+#[derive(Clone)]
+pub struct ScannerContext {
+    pub config: Arc<Config>,
+    pub event_tx: Sender<BankruptcyEvent>,
+    pub dedup: Arc<dyn DedupBackend>,
+    pub resolver: Arc<EntityResolver>,
+    pub cases: Arc<CaseTracker>,
+    pub affiliates: Arc<AffiliateGrouper>,
+    pub metrics: Arc<MetricsCollector>,
+    /// The vocabulary candidates are scored with, reloaded in place.
+    pub keywords: SharedKeywords,
+    /// Blended into every score, if one is loaded.
+    pub relevance_model: Option<Arc<RelevanceModel>>,
+}
+
+/// Why a fetch didn't produce a body.
+#[derive(Debug, thiserror::Error)]
+enum FetchError {
+    /// Couldn't reach the server, or it hung up on us mid-body.
+    #[error("request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// The server answered, just not with anything we wanted to hear.
+    #[error("HTTP {0}")]
+    Status(StatusCode),
+}
+
+impl FetchError {
+    /// Whether this failure says something about the health of the source.
+    /// Transport errors, rate limiting, and 5xx do. A 404 for one carrier's
+    /// DOT number does not, and shouldn't trip the breaker for everyone else.
+    fn trips_breaker(&self) -> bool {
+        match self {
+            FetchError::Transport(_) => true,
+            FetchError::Status(status) => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+        }
+    }
+}
+
+impl ScannerContext {
+    /// Score, deduplicate, resolve, and publish a single candidate.
+    ///
+    /// Returns `true` if the candidate made it into the channel.
+    pub async fn submit(&self, scanner_name: &str, candidate: Candidate) -> bool {
+        let Candidate { dedup_key, mut event, scan_text } = candidate;
+        let min_confidence = self.config.min_confidence_threshold;
+
+        // A docket entry for a case we already track is news whether or not
+        // it mentions trucks.
+        let lifecycle = scan_text.as_deref().and_then(lifecycle::detect_lifecycle);
+        if let Some(lifecycle) = lifecycle.clone().filter(|_| self.cases.is_known(&event)) {
+            return self.submit_case_update(scanner_name, &dedup_key, event, lifecycle).await;
+        }
+
+        // A subsidiary's petition in a family we're already collecting is
+        // news because its lead debtor is, trucks or no trucks.
+        let affiliate = self.affiliates.is_collecting(&event);
+
+        if affiliate {
+            // Relevance was settled by whoever opened the family.
+        } else if let Some(text) = scan_text {
+            // Quick freight check first — memchr SIMD pre-filter. If the text
+            // doesn't even say "freight" or "truck", the automaton can sleep in.
+            let keywords = self.keywords.get();
+            if !text_scanner::quick_freight_check_with(&keywords, &text) {
+                return false;
+            }
+
+            let scan_result = text_scanner::scan_text_with(&keywords, self.relevance_model.as_deref(), &text);
+            if scan_result.confidence < min_confidence {
+                return false;
+            }
+
+            // What the scanner already knew about the company (FMCSA
+            // authority, say) is a prior; the text adds to it.
+            event.confidence_score = scan_result.confidence;
+            event.classifications = text_scanner::company::combine(&event.classifications, &scan_result.classifications);
+            event.classification = text_scanner::company::primary(&event.classifications);
+            event.score_breakdown = scan_result.contributions;
+        } else if event.confidence_score < min_confidence {
+            return false;
+        } else if event.score_breakdown.is_empty() {
+            // The scanner scored it itself; that's the whole explanation.
+            event.score_breakdown.push(ScoreContribution {
+                feature: "source".to_string(),
+                value: event.confidence_score,
+                weight: 1.0,
+                points: event.confidence_score,
+                evidence: vec![scanner_name.to_string()],
+            });
+        }
+
+        // Jointly administered filings wait for the rest of their family
+        // and go out as one event, see flush_affiliates. Their keys are
+        // claimed then, so a family lost in a crash isn't a duplicate when
+        // the feed brings it round again.
+        let Some(event) = self.affiliates.hold(scanner_name, &dedup_key, event) else {
+            debug!(
+                scanner = scanner_name,
+                key = dedup_key.as_str(),
+                "Jointly administered filing held for its lead case"
+            );
+            return false;
+        };
+
+        // claim returns TRUE if the item is NEW (to us, and to any other
+        // engine sharing the backend).
+        if !self.dedup.claim(&dedup_key).await {
+            debug!(
+                scanner = scanner_name,
+                key = dedup_key.as_str(),
+                backend = self.dedup.name(),
+                "Duplicate candidate — the dedup backend said 'been there, done that'"
+            );
+            return false;
+        }
+
+        self.publish(scanner_name, event, lifecycle)
+    }
+
+    /// Resolve, record and send an event that has passed every check.
+    fn publish(&self, scanner_name: &str, mut event: BankruptcyEvent, lifecycle: Option<LifecycleEvent>) -> bool {
+        if self.resolver.resolve(&mut event) == Resolution::Corroborated {
+            info!(
+                scanner = scanner_name,
+                company = event.company_name.as_str(),
+                case_id = event.case_id.as_deref().unwrap_or_default(),
+                sources = event.corroborating_sources.len(),
+                "Another source confirms a known case — the witnesses agree"
+            );
+        }
+        self.cases.record(&event, lifecycle);
+
+        let company = event.company_name.clone();
+        let court = event.court.clone();
+        let confidence = event.confidence_score;
+
+        // try_send is non-blocking — if the channel is full (10,000 events
+        // deep), we log and move on. If we're 10,000 events behind, we have
+        // bigger problems.
+        match self.event_tx.try_send(event) {
+            Ok(()) => {
+                self.metrics.increment_detected();
+                self.metrics.increment_scanner_events(scanner_name);
+                self.metrics.observe_confidence(scanner_name, court.as_deref(), confidence);
+                info!(
+                    scanner = scanner_name,
+                    company = company.as_str(),
+                    confidence = format!("{:.1}%", confidence * 100.0),
+                    "NEW BANKRUPTCY EVENT DETECTED — another one bites the dust"
+                );
+                true
+            }
+            Err(e) => {
+                error!(
+                    scanner = scanner_name,
+                    error = %e,
+                    "Failed to send event to channel — the channel is either full or dead"
+                );
+                false
+            }
+        }
+    }
+
+    /// Publish a `CaseUpdate` for a known case, if `lifecycle` is news to
+    /// it and the docket entry hasn't been seen before.
+    async fn submit_case_update(
+        &self,
+        scanner_name: &str,
+        dedup_key: &str,
+        event: BankruptcyEvent,
+        lifecycle: LifecycleEvent,
+    ) -> bool {
+        // The candidate's key is usually the case's own, already claimed by
+        // its detection; the update is a separate piece of news.
+        if !self.dedup.claim(&format!("{}:{}", dedup_key, lifecycle)).await {
+            return false;
+        }
+        self.send_case_update(scanner_name, event, lifecycle)
+    }
+
+    /// Record `lifecycle` on the event's case and send the event as a
+    /// `CaseUpdate`, if it's news to the case. The event takes the case's
+    /// name, chapter and confidence, since a docket entry like "Order
+    /// Dismissing Case" carries none of them.
+    fn send_case_update(&self, scanner_name: &str, mut event: BankruptcyEvent, lifecycle: LifecycleEvent) -> bool {
+        let Some(update) = self.cases.record(&event, Some(lifecycle)) else {
+            return false;
+        };
+        let Some(case) = self.cases.get(&update.case_key) else {
+            return false;
+        };
+
+        event.company_name = case.company_name;
+        event.case_id = case.case_id;
+        event.confidence_score = case.confidence_score;
+        event.chapter = update.chapter.clone();
+        event.event_type = EventType::CaseUpdate;
+
+        info!(
+            scanner = scanner_name,
+            company = event.company_name.as_str(),
+            case = update.case_key.as_str(),
+            change = %update.lifecycle_event,
+            status = %update.status,
+            "CASE UPDATE — a known bankruptcy just got worse (or, rarely, better)"
+        );
+        event.case_update = Some(update);
+
+        match self.event_tx.try_send(event) {
+            Ok(()) => {
+                self.metrics.increment_scanner_events(scanner_name);
+                true
+            }
+            Err(e) => {
+                error!(
+                    scanner = scanner_name,
+                    error = %e,
+                    "Failed to send case update to channel — the channel is either full or dead"
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Publish each jointly administered family once its window closes, and
+/// whatever is still being collected at shutdown.
+pub async fn flush_affiliates(ctx: ScannerContext, mut shutdown: watch::Receiver<bool>) {
+    let tick = ctx.affiliates.window().min(AFFILIATE_FLUSH_TICK);
+    if tick.is_zero() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tick) => {
+                for family in ctx.affiliates.take_due(Instant::now()) {
+                    publish_family(&ctx, family).await;
+                }
+            }
+
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    for family in ctx.affiliates.take_all() {
+        publish_family(&ctx, family).await;
+    }
+}
+
+/// Claim the family's filings, and publish it with whichever were new: as
+/// one event for the lead debtor, or, if the lead's petition already went
+/// out on its own, as an update adding the affiliates to its case.
+async fn publish_family(ctx: &ScannerContext, mut family: Family) {
+    let mut members = Vec::new();
+    for (key, event) in std::mem::take(&mut family.members) {
+        if ctx.dedup.claim(&key).await {
+            members.push((key, event));
+        }
+    }
+    family.members = members;
+
+    let scanner = family.scanner.clone();
+    let filings = family.members.len();
+    let joins_known_case = !family.has_lead() && ctx.cases.get(family.lead_case_key()).is_some();
+
+    let published = if joins_known_case {
+        let Some(event) = family.into_joined() else {
+            return;
+        };
+        info!(
+            scanner = scanner.as_str(),
+            lead_case = event.case_number.as_deref().unwrap_or_default(),
+            affiliates = event.affiliates.len(),
+            "Affiliates filed under a case we already published — adding them to it"
+        );
+        ctx.send_case_update(&scanner, event, LifecycleEvent::AffiliatesJoined)
+    } else {
+        let Some(event) = family.into_event() else {
+            debug!(scanner = scanner.as_str(), "Jointly administered family was already published");
+            return;
+        };
+        info!(
+            scanner = scanner.as_str(),
+            company = event.company_name.as_str(),
+            lead_case = event.lead_case_number.as_deref().unwrap_or_default(),
+            affiliates = event.affiliates.len(),
+            "Jointly administered family collected — one bankruptcy, many petitions"
+        );
+        ctx.publish(&scanner, event, None)
+    };
+    if published {
+        ctx.affiliates.record_published(filings);
+    }
+}
+
+/// Marks a scanner as running for as long as it's alive — including when
+/// the task dies by panic instead of by shutdown.
+struct RunningGuard<'a> {
+    metrics: &'a MetricsCollector,
+    name: &'static str,
+}
+
+impl<'a> RunningGuard<'a> {
+    fn new(metrics: &'a MetricsCollector, name: &'static str) -> Self {
+        metrics.set_scanner_running(name, true);
+        Self { metrics, name }
+    }
+}
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.set_scanner_running(self.name, false);
+    }
+}
+
+/// Run a scanner until the shutdown signal flips.
+///
+/// This function never returns under normal operation — it loops forever,
+/// polling the scanner's targets every `poll_interval`. Think of it as a
+/// very dedicated, very obsessive court reporter who never sleeps, never
+/// eats, and doesn't care which courthouse you send it to.
+pub async fn drive(
+    scanner: Arc<dyn Scanner>,
+    ctx: ScannerContext,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let name = scanner.name();
+    let poll_interval = scanner.poll_interval();
+
+    let client = reqwest::Client::builder()
+        .timeout(scanner.request_timeout())
+        .user_agent(scanner.user_agent())
+        .build()
+        .expect("Failed to build HTTP client — this is genuinely embarrassing");
+
+    let circuit_breaker = Arc::new(CircuitBreaker::new(
+        scanner.source().to_string(),
+        ctx.config.circuit_breaker_failure_threshold,
+        ctx.config.circuit_breaker_reset_timeout,
+        ctx.config.circuit_breaker_success_threshold,
+    ));
+    ctx.metrics.register_scanner(name, scanner.source(), poll_interval, circuit_breaker.clone());
+    let _running = RunningGuard::new(&ctx.metrics, name);
+
+    info!(
+        scanner = name,
+        poll_interval_secs = poll_interval.as_secs(),
+        "Scanner online — the bloodhound has the scent"
+    );
+
+    let mut cycle: u64 = 0;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(poll_interval) => {
+                let new_events = poll_once(&*scanner, &client, &circuit_breaker, &ctx, cycle).await;
+                cycle = cycle.wrapping_add(1);
+
+                if new_events > 0 {
+                    info!(
+                        scanner = name,
+                        new_events = new_events,
+                        "Scan cycle complete — {} new freight bankruptcy events detected",
+                        new_events
+                    );
+                } else {
+                    debug!(
+                        scanner = name,
+                        "Scan cycle complete — no new freight bankruptcies (the freight industry lives to fight another day)"
+                    );
+                }
+            }
+
+            _ = shutdown.changed() => {
+                info!(scanner = name, "Scanner received shutdown signal — hanging up the leash");
+                break;
+            }
+        }
+    }
+
+    info!(scanner = name, "Scanner has exited the building");
+}
+
+/// One poll cycle: fetch every target, parse, and submit the candidates.
+/// Returns the number of events that made it into the channel.
+async fn poll_once(
+    scanner: &dyn Scanner,
+    client: &reqwest::Client,
+    circuit_breaker: &CircuitBreaker,
+    ctx: &ScannerContext,
+    cycle: u64,
+) -> u64 {
+    let name = scanner.name();
+    let mut new_events = 0u64;
+
+    for target in scanner.targets(cycle) {
+        // Checked per target, not per cycle, so a source that falls over
+        // halfway through twelve court feeds stops getting hammered
+        // immediately instead of at the next cycle.
+        if !circuit_breaker.allow_request() {
+            debug!(scanner = name, "Circuit breaker is OPEN — giving the source a breather");
+            break;
+        }
+
+        let started = Instant::now();
+        let fetched = fetch(scanner, client, &target).await;
+        ctx.metrics.observe_fetch_latency(name, started.elapsed());
+
+        let body = match fetched {
+            Ok(body) => {
+                circuit_breaker.record_success();
+                ctx.metrics.record_successful_poll(name);
+                body
+            }
+            Err(e) => {
+                if e.trips_breaker() {
+                    circuit_breaker.record_failure();
+                }
+                ctx.metrics.increment_scanner_errors(name);
+                warn!(
+                    scanner = name,
+                    target = target.label.as_str(),
+                    error = %e,
+                    "Fetch failed — the server is having an existential crisis"
+                );
+                continue;
+            }
+        };
+
+        for candidate in scanner.parse(&target, &body) {
+            if ctx.submit(name, candidate).await {
+                new_events += 1;
+            }
+        }
+    }
+
+    new_events
+}
+
+/// GET a target and return the body, or explain why not.
+async fn fetch(
+    scanner: &dyn Scanner,
+    client: &reqwest::Client,
+    target: &FetchTarget,
+) -> Result<String, FetchError> {
+    debug!(
+        scanner = scanner.name(),
+        target = target.label.as_str(),
+        url = target.url.as_str(),
+        "Fetching"
+    );
+
+    let mut request = client.get(&target.url);
+    for (key, value) in scanner.headers() {
+        request = request.header(*key, *value);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError::Status(status));
+    }
+
+    Ok(response.text().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dedup::DedupEngine;
+    use crate::models::{ClassScore, CompanyClassification, Source};
+
+    fn test_context() -> (ScannerContext, crossbeam_channel::Receiver<BankruptcyEvent>) {
+        let (event_tx, event_rx) = crossbeam_channel::bounded(16);
+        let dedup = Arc::new(DedupEngine::new(1000, 0.01, 100, 3600));
+        let metrics = Arc::new(MetricsCollector::new());
+        metrics.register_dedup(dedup.clone());
+        let ctx = ScannerContext {
+            config: Arc::new(Config::from_env()),
+            event_tx,
+            dedup,
+            resolver: Arc::new(EntityResolver::new(100, std::time::Duration::from_secs(3600))),
+            cases: Arc::new(CaseTracker::in_memory()),
+            affiliates: Arc::new(AffiliateGrouper::new(std::time::Duration::from_secs(60))),
+            metrics,
+            keywords: SharedKeywords::default(),
+            relevance_model: None,
+        };
+        (ctx, event_rx)
+    }
+
+    #[tokio::test]
+    async fn test_scanned_candidate_is_scored_and_sent() {
+        let (ctx, rx) = test_context();
+        let event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Pacer, 0.0);
+        let text = "Acme Freight LLC, a motor carrier trucking company, filed chapter 11 bankruptcy";
+        let candidate = Candidate::scanned("pacer:test:1".into(), event, text.into());
+
+        assert!(ctx.submit("pacer", candidate).await);
+        let sent = rx.try_recv().unwrap();
+        assert!(sent.confidence_score > 0.0);
+        assert_eq!(sent.classification, CompanyClassification::Carrier);
+        let explained: f64 = sent.score_breakdown.iter().map(|c| c.points).sum();
+        assert!((explained - sent.confidence_score).abs() < 1e-9, "{:?}", sent.score_breakdown);
+        assert!(sent.score_breakdown.iter().any(|c| c.feature == "cross_domain"));
+    }
+
+    #[tokio::test]
+    async fn test_context_relevance_model_is_blended_in() {
+        use crate::text_scanner::classifier::{Example, TrainOptions};
+
+        let text = "Acme Freight LLC, a motor carrier trucking company, filed chapter 11 bankruptcy";
+        let score = |model: Option<Arc<RelevanceModel>>| async move {
+            let (mut ctx, rx) = test_context();
+            ctx.relevance_model = model;
+            let event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Pacer, 0.0);
+            ctx.submit("pacer", Candidate::scanned("pacer:test:1".into(), event, text.into())).await;
+            rx.try_recv().unwrap()
+        };
+
+        // A model that has only ever seen this text marked relevant.
+        let examples = [
+            Example { text: text.to_string(), relevant: true },
+            Example { text: "quarterly revenue was flat".to_string(), relevant: false },
+        ];
+        let model = RelevanceModel::train(&examples, &TrainOptions { min_count: 1, ..TrainOptions::default() }).unwrap();
+
+        let without = score(None).await;
+        let with = score(Some(Arc::new(model))).await;
+        assert!(with.confidence_score > without.confidence_score);
+        assert!(with.score_breakdown.iter().any(|c| c.feature == "model"), "{:?}", with.score_breakdown);
+        assert!(!without.score_breakdown.iter().any(|c| c.feature == "model"));
+    }
+
+    #[tokio::test]
+    async fn test_scanner_priors_are_combined_with_the_text() {
+        let (ctx, rx) = test_context();
+        let mut event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Fmcsa, 0.0);
+        event.classifications = vec![ClassScore {
+            classification: CompanyClassification::Broker,
+            score: 0.95,
+            evidence: vec!["broker authority".into()],
+        }];
+        let text = "Acme Freight LLC, a motor carrier trucking company, filed chapter 11 bankruptcy";
+        let candidate = Candidate::scanned("fmcsa:raw:1".into(), event, text.into());
+
+        assert!(ctx.submit("fmcsa", candidate).await);
+        let sent = rx.try_recv().unwrap();
+        assert_eq!(sent.classification, CompanyClassification::Broker);
+        let labels: Vec<_> = sent.classifications.iter().map(|c| c.classification.clone()).collect();
+        assert_eq!(labels, vec![CompanyClassification::Broker, CompanyClassification::Carrier]);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_candidate_is_dropped() {
+        let (ctx, rx) = test_context();
+        let event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Fmcsa, 0.9);
+
+        assert!(ctx.submit("fmcsa", Candidate::scored("fmcsa:1:REVOKED".into(), event.clone())).await);
+        assert!(!ctx.submit("fmcsa", Candidate::scored("fmcsa:1:REVOKED".into(), event)).await);
+        assert_eq!(rx.len(), 1);
+        let sent = rx.try_recv().unwrap();
+        assert_eq!(sent.score_breakdown.len(), 1);
+        assert_eq!(sent.score_breakdown[0].feature, "source");
+        assert_eq!(sent.score_breakdown[0].evidence, vec!["fmcsa".to_string()]);
+        assert_eq!(ctx.metrics.snapshot().total_events_deduplicated, 1);
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_candidate_is_dropped() {
+        let (ctx, rx) = test_context();
+        let event = BankruptcyEvent::new("Acme Freight LLC".into(), Source::Fmcsa, 0.01);
+
+        assert!(!ctx.submit("fmcsa", Candidate::scored("fmcsa:2:INACTIVE".into(), event)).await);
+        assert!(rx.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_docket_entry_for_known_case_becomes_case_update() {
+        let (ctx, rx) = test_context();
+        let docket = |title: &str| {
+            let mut event = BankruptcyEvent::new("Unknown Case".into(), Source::Pacer, 0.0);
+            event.court = Some("D. Del.".into());
+            event.case_number = Some("24-10001".into());
+            (event, title.to_string())
+        };
+
+        let key = |event: &BankruptcyEvent| crate::scanners::case_dedup_key("pacer", event).unwrap();
+
+        let (mut filing, _) = docket("");
+        filing.company_name = "Acme Freight LLC".into();
+        let text = "24-10001 Acme Freight LLC, a motor carrier trucking company, chapter 11 voluntary petition";
+        assert!(ctx.submit("pacer", Candidate::scanned(key(&filing), filing.clone(), text.into())).await);
+        assert_eq!(rx.try_recv().unwrap().event_type, EventType::Detection);
+
+        // The next docket entry in the same case is the same bankruptcy.
+        filing.case_number = Some("1:24-bk-10001-KBO".into());
+        assert!(!ctx.submit("pacer", Candidate::scanned(key(&filing), filing, text.into())).await);
+
+        // No freight keywords, same case key, and still published.
+        let (event, text) = docket("24-10001 Order Granting Motion to Convert Case to Chapter 7");
+        assert!(ctx.submit("pacer", Candidate::scanned(key(&event), event.clone(), text.clone())).await);
+        let update = rx.try_recv().unwrap();
+        assert_eq!(update.event_type, EventType::CaseUpdate);
+        assert_eq!(update.company_name, "Acme Freight LLC");
+        assert_eq!(update.chapter, crate::models::BankruptcyChapter::Chapter7);
+        assert_eq!(
+            update.case_update.unwrap().lifecycle_event,
+            LifecycleEvent::ConvertedTo(crate::models::BankruptcyChapter::Chapter7)
+        );
+        assert!(!ctx.submit("pacer", Candidate::scanned(key(&event), event, text)).await);
+
+        // A motion is not an order, and an unknown case isn't ours to update.
+        let (event, text) = docket("24-10001 Motion to Dismiss Case");
+        assert!(!ctx.submit("pacer", Candidate::scanned("pacer:del:3".into(), event, text)).await);
+        let (mut event, text) = docket("24-99999 Order Dismissing Case");
+        event.case_number = Some("24-99999".into());
+        assert!(!ctx.submit("pacer", Candidate::scanned("pacer:del:4".into(), event, text)).await);
+        assert!(rx.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_jointly_administered_family_is_published_once() {
+        let (ctx, rx) = test_context();
+        let petition = |name: &str, case: &str| {
+            let mut event = BankruptcyEvent::new(name.into(), Source::Pacer, 0.0);
+            event.court = Some("D. Del.".into());
+            event.case_number = Some(case.into());
+            event.lead_case_number = Some("24-10123".into());
+            event
+        };
+
+        let lead = petition("Acme Freight Holdings, Inc.", "24-10123");
+        let text = "Acme Freight Holdings, a motor carrier trucking company, chapter 11 voluntary petition, Lead Case No. 24-10123";
+        assert!(!ctx.submit("pacer", Candidate::scanned("pacer:a".into(), lead, text.into())).await);
+
+        // No trucks in the subsidiary's petition; it rides on the lead's.
+        let leasing = petition("Acme Leasing LLC", "24-10124");
+        let text = "Acme Leasing LLC voluntary petition, jointly administered under Case No. 24-10123";
+        assert!(!ctx.submit("pacer", Candidate::scanned("pacer:b".into(), leasing, text.into())).await);
+        assert!(rx.is_empty());
+
+        for family in ctx.affiliates.take_all() {
+            publish_family(&ctx, family).await;
+        }
+        let parent = rx.try_recv().unwrap();
+        assert_eq!(parent.company_name, "Acme Freight Holdings, Inc.");
+        assert_eq!(parent.affiliates.len(), 1);
+        assert_eq!(parent.affiliates[0].case_number.as_deref(), Some("24-10124"));
+        assert!(parent.confidence_score > 0.0);
+        assert!(ctx.cases.is_known(&parent));
+        assert!(rx.is_empty());
+        assert_eq!(ctx.affiliates.snapshot().groups_published, 1);
+        assert_eq!(ctx.affiliates.snapshot().filings_grouped, 2);
+    }
+
+    #[tokio::test]
+    async fn test_affiliates_of_a_published_lead_update_its_case() {
+        let (ctx, rx) = test_context();
+        let petition = |name: &str, case: &str, lead: Option<&str>| {
+            let mut event = BankruptcyEvent::new(name.into(), Source::Pacer, 0.0);
+            event.court = Some("D. Del.".into());
+            event.case_number = Some(case.into());
+            event.lead_case_number = lead.map(str::to_string);
+            let text = format!("{} trucking company chapter 11 voluntary petition", name);
+            Candidate::scanned(format!("pacer:case:{}", case), event, text)
+        };
+
+        // The lead's petition doesn't say "lead case": out it goes, alone.
+        assert!(ctx.submit("pacer", petition("Acme Freight Holdings, Inc.", "24-10123", None)).await);
+        let lead = rx.try_recv().unwrap();
+
+        // Its affiliates name it a day later.
+        for (name, case) in [("Acme Trucking East LLC", "24-10124"), ("Acme Trucking West LLC", "24-10125")] {
+            assert!(!ctx.submit("pacer", petition(name, case, Some("24-10123"))).await);
+        }
+        for family in ctx.affiliates.take_all() {
+            publish_family(&ctx, family).await;
+        }
+
+        let update = rx.try_recv().unwrap();
+        assert_eq!(update.event_type, EventType::CaseUpdate);
+        assert_eq!(update.company_name, "Acme Freight Holdings, Inc.");
+        assert_eq!(update.case_id, lead.case_id);
+        assert_eq!(update.case_number.as_deref(), Some("24-10123"));
+        assert_eq!(update.affiliates.len(), 2);
+        assert_eq!(update.case_update.unwrap().lifecycle_event, LifecycleEvent::AffiliatesJoined);
+        assert!(rx.is_empty());
+
+        // The next poll brings the same affiliates round again: nothing new.
+        for (name, case) in [("Acme Trucking East LLC", "24-10124"), ("Acme Trucking West LLC", "24-10125")] {
+            ctx.submit("pacer", petition(name, case, Some("24-10123"))).await;
+        }
+        for family in ctx.affiliates.take_all() {
+            publish_family(&ctx, family).await;
+        }
+        assert!(rx.is_empty());
+        assert_eq!(ctx.affiliates.snapshot().groups_published, 1);
+        assert_eq!(ctx.affiliates.snapshot().filings_grouped, 2);
+    }
+
+    #[tokio::test]
+    async fn test_family_lost_in_a_crash_is_collected_again() {
+        let (mut ctx, rx) = test_context();
+        let petitions = || {
+            let petition = |name: &str, case: &str, text: &str| {
+                let mut event = BankruptcyEvent::new(name.into(), Source::Pacer, 0.0);
+                event.court = Some("D. Del.".into());
+                event.case_number = Some(case.into());
+                event.lead_case_number = Some("24-10123".into());
+                Candidate::scanned(format!("pacer:case:{}", case), event, text.into())
+            };
+            vec![
+                petition(
+                    "Acme Freight Holdings, Inc.",
+                    "24-10123",
+                    "Acme Freight Holdings, a motor carrier trucking company, chapter 11 voluntary petition, Lead Case No. 24-10123",
+                ),
+                petition("Acme Leasing LLC", "24-10124", "Acme Leasing LLC voluntary petition, Lead Case No. 24-10123"),
+            ]
+        };
+
+        for candidate in petitions() {
+            ctx.submit("pacer", candidate).await;
+        }
+        // The process dies with the family still collecting. Dedup state
+        // survives (snapshot, or Redis); the grouper doesn't.
+        ctx.affiliates = Arc::new(AffiliateGrouper::new(std::time::Duration::from_secs(60)));
+
+        for candidate in petitions() {
+            ctx.submit("pacer", candidate).await;
+        }
+        for family in ctx.affiliates.take_all() {
+            publish_family(&ctx, family).await;
+        }
+        let parent = rx.try_recv().unwrap();
+        assert_eq!(parent.company_name, "Acme Freight Holdings, Inc.");
+        assert_eq!(parent.affiliates.len(), 1);
+
+        // Once it's out, the next poll of the same feed is a duplicate.
+        for candidate in petitions() {
+            ctx.submit("pacer", candidate).await;
+        }
+        for family in ctx.affiliates.take_all() {
+            publish_family(&ctx, family).await;
+        }
+        assert!(rx.is_empty());
+    }
+}
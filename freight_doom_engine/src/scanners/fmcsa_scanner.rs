@@ -36,22 +36,18 @@
 // The question answers itself.
 // =============================================================================
 
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
 use std::time::Duration;
 
-use crossbeam_channel::Sender;
-use tokio::sync::watch;
-use tracing::{debug, error, info, warn};
+use tracing::debug;
 
-use crate::circuit_breaker::CircuitBreaker;
 use crate::config::Config;
-use crate::dedup::DedupEngine;
 use crate::models::{
-    BankruptcyChapter, BankruptcyEvent, CompanyClassification, Source,
+    BankruptcyChapter, BankruptcyEvent, ClassScore, CompanyClassification, Source,
 };
 use crate::text_scanner;
 
+use super::{Candidate, FetchTarget, Scanner};
+
 // =============================================================================
 // Monitored Carrier DOT Numbers
 // =============================================================================
@@ -113,6 +109,10 @@ struct QcMobileCarrier {
     dot_number: Option<String>,
     mc_number: Option<String>,
     carrier_operation: Option<String>,
+    /// Operating authority held: "A" active, "I" inactive, "N" never had it.
+    common_authority_status: Option<String>,
+    contract_authority_status: Option<String>,
+    broker_authority_status: Option<String>,
     status_code: Option<String>,
     oos_date: Option<String>,
     #[serde(alias = "bipd_insurance_required")]
@@ -125,219 +125,120 @@ struct QcMobileCarrier {
     total_power_units: Option<String>,
 }
 
-/// The main entry point for the FMCSA scanner.
+/// The FMCSA scanner.
 ///
-/// This function runs an infinite loop, rotating through monitored carriers
-/// and checking their status with the FMCSA QCMobile API. It's like having
+/// Every poll cycle, it checks a small batch of monitored carriers against
+/// the FMCSA QCMobile API, rotating through the watchlist. It's like having
 /// a fleet manager who does nothing but refresh the SAFER website all day,
-/// except this fleet manager is an async Rust function with a circuit breaker
-/// and can check 15 carriers in the time it takes a human to load one page.
-///
-/// # Arguments
-/// * `config` - Global configuration with fmcsa_base_url and fmcsa_poll_interval.
-/// * `event_tx` - Crossbeam channel sender for detected events.
-/// * `dedup` - Bloom filter + LRU deduplication engine.
-/// * `shutdown` - Watch channel for graceful shutdown.
-pub async fn run(
-    config: Arc<Config>,
-    event_tx: Sender<BankruptcyEvent>,
-    dedup: Arc<DedupEngine>,
-    shutdown: &mut watch::Receiver<bool>,
-) {
-    info!("FMCSA Scanner initializing — preparing to stalk the operating authority status of every major carrier in America");
-
-    // Build HTTP client. FMCSA doesn't have strict User-Agent requirements
-    // like the SEC, but we identify ourselves anyway because we were raised right.
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(15))
-        .user_agent("FreightDoomEngine/1.0 (carrier-monitoring; educational-project)")
-        .build()
-        .expect("Failed to build FMCSA HTTP client — the Department of Transportation will never know");
-
-    // Circuit breaker for FMCSA endpoints.
-    // FMCSA APIs can be temperamental, especially the QCMobile endpoint
-    // which occasionally decides that HTTP 500 is an acceptable response
-    // to a perfectly valid request.
-    let circuit_breaker = CircuitBreaker::new(
-        "FMCSA",
-        config.circuit_breaker_failure_threshold,
-        config.circuit_breaker_reset_timeout,
-        config.circuit_breaker_success_threshold,
-    );
-
-    // Atomic index for rotating through the carrier watchlist.
-    // We check a batch of 3 carriers per cycle to spread the load
-    // and avoid hammering FMCSA with 15 simultaneous requests.
-    // We're obsessive, not rude.
-    let carrier_index = AtomicUsize::new(0);
-
-    let poll_interval = config.fmcsa_poll_interval;
-    let fmcsa_base_url = config.fmcsa_base_url.clone();
-    let min_confidence = config.min_confidence_threshold;
-
-    info!(
-        poll_interval_secs = poll_interval.as_secs(),
-        monitored_carriers = MONITORED_CARRIERS.len(),
-        base_url = fmcsa_base_url.as_str(),
-        "FMCSA Scanner online — monitoring {} carriers like a very concerned insurance adjuster",
-        MONITORED_CARRIERS.len()
-    );
-
-    loop {
-        tokio::select! {
-            _ = tokio::time::sleep(poll_interval) => {
-                if !circuit_breaker.allow_request() {
-                    debug!("FMCSA: circuit breaker is OPEN — FMCSA needs time to recover from our affection");
-                    continue;
-                }
-
-                // Check a batch of carriers per cycle.
-                // We rotate through the list so every carrier gets checked
-                // eventually. With 15 carriers and batches of 3, we check
-                // the entire list every 5 cycles. At a 120-second interval,
-                // that's a full sweep every 10 minutes.
-                //
-                // In a production system with thousands of carriers, you'd
-                // want much larger batches and possibly parallel requests.
-                // But for 15 carriers, sequential is fine and much friendlier
-                // to FMCSA's servers.
-                let batch_size = 3;
-                let start_idx = carrier_index.fetch_add(batch_size, Ordering::Relaxed);
-
-                for i in 0..batch_size {
-                    let idx = (start_idx + i) % MONITORED_CARRIERS.len();
-                    let (dot_number, fallback_name) = MONITORED_CARRIERS[idx];
-
-                    check_carrier(
-                        &client,
-                        &circuit_breaker,
-                        &fmcsa_base_url,
-                        dot_number,
-                        fallback_name,
-                        &event_tx,
-                        &dedup,
-                        min_confidence,
-                    )
-                    .await;
-                }
-            }
+/// except this fleet manager never needs a coffee refill.
+pub struct FmcsaScanner {
+    poll_interval: Duration,
+    base_url: String,
+}
 
-            _ = shutdown.changed() => {
-                info!("FMCSA Scanner received shutdown signal — our operating authority has been voluntarily revoked");
-                break;
-            }
+impl FmcsaScanner {
+    /// How many carriers to check per cycle. With 15 carriers and batches
+    /// of 3, we check the entire list every 5 cycles. At a 120-second
+    /// interval, that's a full sweep every 10 minutes. We're obsessive,
+    /// not rude.
+    const BATCH_SIZE: u64 = 3;
+
+    pub fn new(config: &Config) -> Self {
+        Self {
+            poll_interval: config.fmcsa_poll_interval,
+            base_url: config.fmcsa_base_url.clone(),
         }
     }
-
-    info!("FMCSA Scanner has exited — the carriers are on their own now");
 }
 
-/// Check a single carrier's status via the FMCSA QCMobile API.
-///
-/// This function hits the QCMobile API for a specific DOT number, parses
-/// the response, and evaluates whether the carrier's status indicates
-/// financial distress (INACTIVE, REVOKED, OUT OF SERVICE, insurance lapse).
-///
-/// If a carrier's status looks bad, we generate a BankruptcyEvent and
-/// fire it into the crossbeam channel. The confidence score is based on
-/// how bad the status is:
-/// - REVOKED: 0.90 confidence (this is pretty definitive)
-/// - INACTIVE: 0.80 confidence (could be voluntary, could be bad)
-/// - OUT OF SERVICE: 0.85 confidence (the government took their keys)
-/// - Insurance lapsed: 0.70 confidence (the death spiral has begun)
-///
-/// We also run the carrier's name through the text scanner to classify
-/// their operation type (carrier vs broker vs 3PL vs freight forwarder).
-async fn check_carrier(
-    client: &reqwest::Client,
-    circuit_breaker: &CircuitBreaker,
-    base_url: &str,
-    dot_number: &str,
-    fallback_name: &str,
-    event_tx: &Sender<BankruptcyEvent>,
-    dedup: &Arc<DedupEngine>,
-    min_confidence: f64,
-) {
-    // Build the QCMobile API URL.
-    // The real endpoint is: https://mobile.fmcsa.dot.gov/qc/services/carriers/{DOT}
-    // It returns JSON with the carrier's full registration details.
-    let url = format!("{}/{}", base_url, dot_number);
+impl Scanner for FmcsaScanner {
+    fn name(&self) -> &'static str {
+        "fmcsa"
+    }
 
-    debug!(
-        dot_number = dot_number,
-        carrier = fallback_name,
-        "FMCSA: checking carrier status — praying for ACTIVE, bracing for REVOKED"
-    );
+    fn source(&self) -> Source {
+        Source::Fmcsa
+    }
 
-    let response = match client
-        .get(&url)
-        .header("Accept", "application/json")
-        .send()
-        .await
-    {
-        Ok(resp) => {
-            circuit_breaker.record_success();
-            resp
-        }
-        Err(e) => {
-            circuit_breaker.record_failure();
-            debug!(
-                dot_number = dot_number,
-                error = %e,
-                "FMCSA: failed to fetch carrier data — the DOT's servers are napping"
-            );
-            return;
-        }
-    };
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
 
-    if !response.status().is_success() {
-        debug!(
-            dot_number = dot_number,
-            status = %response.status(),
-            "FMCSA: non-success response for DOT# {} — carrier may not exist or API is grumpy",
-            dot_number
-        );
-        return;
+    /// FMCSA doesn't have strict User-Agent requirements like the SEC, but
+    /// we identify ourselves anyway because we were raised right.
+    fn user_agent(&self) -> &'static str {
+        "FreightDoomEngine/1.0 (carrier-monitoring; educational-project)"
     }
 
-    let body = match response.text().await {
-        Ok(b) => b,
-        Err(e) => {
-            debug!(error = %e, "FMCSA: failed to read response body");
-            return;
-        }
-    };
+    fn headers(&self) -> &'static [(&'static str, &'static str)] {
+        &[("Accept", "application/json")]
+    }
+
+    /// The next `BATCH_SIZE` carriers on the watchlist. The target label
+    /// is the DOT number, which `parse` uses to find the fallback name.
+    fn targets(&self, cycle: u64) -> Vec<FetchTarget> {
+        let start = cycle.wrapping_mul(Self::BATCH_SIZE);
+        (0..Self::BATCH_SIZE)
+            .map(|i| {
+                let idx = (start.wrapping_add(i) % MONITORED_CARRIERS.len() as u64) as usize;
+                let (dot_number, _) = MONITORED_CARRIERS[idx];
+                // The real endpoint is: https://mobile.fmcsa.dot.gov/qc/services/carriers/{DOT}
+                FetchTarget::new(dot_number, format!("{}/{}", self.base_url, dot_number))
+            })
+            .collect()
+    }
 
-    // Try to parse the QCMobile JSON response.
-    // The API wraps carrier data in { content: { carrier: { ... } } }
-    // because simplicity is the enemy of government API design.
-    let qc_response: QcMobileResponse = match serde_json::from_str(&body) {
-        Ok(r) => r,
-        Err(_) => {
+    fn parse(&self, target: &FetchTarget, body: &str) -> Vec<Candidate> {
+        let dot_number = target.label.as_str();
+        let fallback_name = MONITORED_CARRIERS
+            .iter()
+            .find(|(dot, _)| *dot == dot_number)
+            .map(|(_, name)| *name)
+            .unwrap_or("Unknown Carrier");
+
+        // Try to parse the QCMobile JSON response.
+        // The API wraps carrier data in { content: { carrier: { ... } } }
+        // because simplicity is the enemy of government API design.
+        let candidate = match serde_json::from_str::<QcMobileResponse>(body) {
+            Ok(qc_response) => match qc_response.content.and_then(|c| c.carrier) {
+                Some(carrier) => evaluate_carrier(&carrier, dot_number, fallback_name),
+                None => {
+                    debug!(
+                        dot_number = dot_number,
+                        "FMCSA: no carrier data in response for DOT# {} — carrier might be a ghost",
+                        dot_number
+                    );
+                    None
+                }
+            },
             // If JSON parsing fails, try scanning the raw text.
             // Sometimes the API returns HTML or XML instead of JSON
             // because consistency is overrated.
-            scan_raw_carrier_text(&body, dot_number, fallback_name, event_tx, dedup, min_confidence);
-            return;
-        }
-    };
+            Err(_) => raw_carrier_candidate(body, dot_number, fallback_name),
+        };
 
-    // Extract the carrier record from the nested response
-    let carrier = match qc_response
-        .content
-        .and_then(|c| c.carrier)
-    {
-        Some(c) => c,
-        None => {
-            debug!(
-                dot_number = dot_number,
-                "FMCSA: no carrier data in response for DOT# {} — carrier might be a ghost",
-                dot_number
-            );
-            return;
-        }
-    };
+        candidate.into_iter().collect()
+    }
+}
 
+/// Evaluate a carrier record from the FMCSA QCMobile API.
+///
+/// If the carrier's status looks bad (INACTIVE, REVOKED, OUT OF SERVICE,
+/// insurance lapse), we build a candidate event. The confidence score is
+/// based on how bad the status is:
+/// - REVOKED: 0.90 confidence (this is pretty definitive)
+/// - INACTIVE: 0.80 confidence (could be voluntary, could be bad)
+/// - OUT OF SERVICE: 0.85 confidence (the government took their keys)
+/// - Insurance lapsed: 0.70 confidence (the death spiral has begun)
+///
+/// We classify the company from FMCSA's own authority and carrier_operation
+/// fields rather than the text scanner, because FMCSA knows better than we
+/// do. They go on the event as priors, which any text scanned later adds to.
+fn evaluate_carrier(
+    carrier: &QcMobileCarrier,
+    dot_number: &str,
+    fallback_name: &str,
+) -> Option<Candidate> {
     // Determine the carrier's display name
     let carrier_name = carrier
         .legal_name
@@ -402,21 +303,13 @@ async fn check_carrier(
             "FMCSA: {} is ACTIVE — still trucking along",
             carrier_name
         );
-        return;
+        return None;
     }
 
-    // Something is wrong. Build a dedup key and check if we've already reported this.
+    // Something is wrong. The dedup key includes the status, so a carrier
+    // going INACTIVE and later REVOKED is reported twice — as it should be.
     let dedup_key = format!("fmcsa:{}:{}", dot_number, status);
 
-    if !dedup.check_and_insert(&dedup_key) {
-        debug!(
-            dot_number = dot_number,
-            "FMCSA: already reported status change for DOT# {} — our Bloom filter has a good memory",
-            dot_number
-        );
-        return;
-    }
-
     // Calculate confidence score based on the type of death signal.
     let confidence = if is_status_dead {
         match status.as_str() {
@@ -432,14 +325,8 @@ async fn check_carrier(
         0.65
     };
 
-    if confidence < min_confidence {
-        return;
-    }
-
-    // Classify the carrier operation type
-    let classification = classify_carrier_operation(
-        carrier.carrier_operation.as_deref().unwrap_or(""),
-    );
+    // Classify the company from what it's registered to do
+    let classifications = registry_priors(carrier);
 
     // Build the bankruptcy event
     let mut event = BankruptcyEvent::new(
@@ -450,7 +337,8 @@ async fn check_carrier(
     event.dot_number = Some(dot_number.to_string());
     event.mc_number = carrier.mc_number.clone().filter(|mc| !mc.is_empty());
     event.chapter = BankruptcyChapter::Unknown; // FMCSA doesn't know about chapters
-    event.classification = classification;
+    event.classification = text_scanner::company::primary(&classifications);
+    event.classifications = classifications;
     event.source_url = Some(format!(
         "https://safer.fmcsa.dot.gov/query.asp?searchtype=ANY&query_type=queryCarrierSnapshot&query_param=USDOT&query_string={}",
         dot_number
@@ -467,55 +355,29 @@ async fn check_carrier(
     let drivers = carrier.total_drivers.as_deref().unwrap_or("?");
     let units = carrier.total_power_units.as_deref().unwrap_or("?");
 
-    match event_tx.try_send(event) {
-        Ok(()) => {
-            info!(
-                dot_number = dot_number,
-                carrier = carrier_name,
-                status = status.as_str(),
-                city = city,
-                state = state,
-                drivers = drivers,
-                power_units = units,
-                confidence = format!("{:.1}%", confidence * 100.0),
-                "FMCSA: CARRIER STATUS CHANGE DETECTED — {} (DOT# {}) is now {} — {} drivers, {} power units, based in {}, {}",
-                carrier_name, dot_number, status, drivers, units, city, state
-            );
-        }
-        Err(e) => {
-            error!(
-                error = %e,
-                dot_number = dot_number,
-                "FMCSA: failed to send event to channel — the bankruptcy news will have to wait"
-            );
-        }
-    }
+    debug!(
+        dot_number = dot_number,
+        carrier = carrier_name,
+        status = status.as_str(),
+        city = city,
+        state = state,
+        drivers = drivers,
+        power_units = units,
+        "FMCSA: carrier status change — {} (DOT# {}) is now {} — {} drivers, {} power units, based in {}, {}",
+        carrier_name, dot_number, status, drivers, units, city, state
+    );
+
+    Some(Candidate::scored(dedup_key, event))
 }
 
-/// Fallback: scan raw response text when JSON parsing fails.
+/// Fallback: build a candidate from raw response text when JSON parsing fails.
 ///
 /// Sometimes the FMCSA API returns HTML or XML instead of JSON,
 /// because government API consistency is a myth. In those cases,
-/// we do a raw text scan for status keywords. It's not as reliable
-/// as proper JSON parsing, but it's better than nothing.
-fn scan_raw_carrier_text(
-    text: &str,
-    dot_number: &str,
-    fallback_name: &str,
-    event_tx: &Sender<BankruptcyEvent>,
-    dedup: &Arc<DedupEngine>,
-    min_confidence: f64,
-) {
-    // First check if this text is even about freight/logistics
-    if !text_scanner::quick_freight_check(text) {
-        return;
-    }
-
-    let scan_result = text_scanner::scan_text(text);
-    if scan_result.confidence < min_confidence {
-        return;
-    }
-
+/// we look for status keywords in the raw text and let the text scanner
+/// decide the confidence. It's not as reliable as proper JSON parsing,
+/// but it's better than nothing.
+fn raw_carrier_candidate(text: &str, dot_number: &str, fallback_name: &str) -> Option<Candidate> {
     let upper = text.to_uppercase();
     let has_death_signal = upper.contains("REVOKED")
         || upper.contains("INACTIVE")
@@ -523,57 +385,127 @@ fn scan_raw_carrier_text(
         || upper.contains("NOT AUTHORIZED");
 
     if !has_death_signal {
-        return;
+        return None;
     }
 
-    let dedup_key = format!("fmcsa:raw:{}", dot_number);
-    if !dedup.check_and_insert(&dedup_key) {
-        return;
-    }
-
-    let mut event = BankruptcyEvent::new(
-        fallback_name.to_string(),
-        Source::Fmcsa,
-        scan_result.confidence,
-    );
+    let mut event = BankruptcyEvent::new(fallback_name.to_string(), Source::Fmcsa, 0.0);
     event.dot_number = Some(dot_number.to_string());
-    event.classification = scan_result.classification;
     event.court = Some("FMCSA (raw text parse)".to_string());
     event.source_url = Some(format!(
         "https://safer.fmcsa.dot.gov/query.asp?searchtype=ANY&query_type=queryCarrierSnapshot&query_param=USDOT&query_string={}",
         dot_number
     ));
 
-    if let Err(e) = event_tx.try_send(event) {
-        error!(error = %e, "FMCSA: failed to send raw-text event");
-    } else {
-        warn!(
-            dot_number = dot_number,
-            carrier = fallback_name,
-            "FMCSA: raw text indicates status change for DOT# {} — parsed from non-JSON response like a true detective",
-            dot_number
-        );
-    }
+    debug!(
+        dot_number = dot_number,
+        carrier = fallback_name,
+        "FMCSA: raw text indicates status change for DOT# {} — parsed from non-JSON response like a true detective",
+        dot_number
+    );
+
+    Some(Candidate::scanned(format!("fmcsa:raw:{}", dot_number), event, text.to_string()))
 }
 
-/// Classify a carrier's operation type based on FMCSA's carrier_operation field.
+/// How sure an operating authority makes us of the company type. Strong,
+/// but not 1.0: plenty of carriers hold broker authority they never use.
+const AUTHORITY_PRIOR: f64 = 0.9;
+
+/// How sure FMCSA's carrier_operation field makes us.
+const OPERATION_PRIOR: f64 = 0.8;
+
+/// How sure we are that something registered with FMCSA and nothing else
+/// is a carrier. Most are.
+const REGISTERED_PRIOR: f64 = 0.5;
+
+/// Classify a company from the authority it holds and FMCSA's
+/// carrier_operation field, strongest first.
 ///
-/// FMCSA categorizes carriers into operation types like "Interstate" or
-/// "Intrastate" and broker/carrier designations. We map these to our
+/// Common or contract authority means it hauls; broker authority means it
+/// arranges. Inactive authority counts too — a company that just lost its
+/// authority is exactly who we're looking at. We map these to our
 /// CompanyClassification enum, which the Rails app uses to categorize
 /// bankruptcies. Because even in death, we must be organized.
-fn classify_carrier_operation(operation: &str) -> CompanyClassification {
+fn registry_priors(carrier: &QcMobileCarrier) -> Vec<ClassScore> {
+    let held = |status: &Option<String>| {
+        matches!(status.as_deref().map(|s| s.trim().to_uppercase()).as_deref(), Some("A" | "I"))
+    };
+    let prior = |classification, score, evidence: String| ClassScore { classification, score, evidence: vec![evidence] };
+
+    let mut priors = Vec::new();
+    if held(&carrier.broker_authority_status) {
+        priors.push(prior(CompanyClassification::Broker, AUTHORITY_PRIOR, "FMCSA broker authority".to_string()));
+    }
+    for (name, status) in [("common", &carrier.common_authority_status), ("contract", &carrier.contract_authority_status)] {
+        if held(status) {
+            priors.push(prior(CompanyClassification::Carrier, AUTHORITY_PRIOR, format!("FMCSA {} authority", name)));
+        }
+    }
+
+    let operation = carrier.carrier_operation.as_deref().unwrap_or("").trim();
     let upper = operation.to_uppercase();
-    if upper.contains("BROKER") {
-        CompanyClassification::Broker
-    } else if upper.contains("FREIGHT FORWARDER") || upper.contains("FORWARDER") {
-        CompanyClassification::FreightForwarder
-    } else if upper.contains("CARRIER") || upper.contains("MOTOR") || upper.contains("INTERSTATE") {
-        CompanyClassification::Carrier
+    let operation_class = if upper.contains("BROKER") {
+        Some(CompanyClassification::Broker)
+    } else if upper.contains("FORWARDER") {
+        Some(CompanyClassification::FreightForwarder)
+    } else if upper.contains("CARRIER") || upper.contains("MOTOR") || upper.contains("INTERSTATE") || upper.contains("INTRASTATE") {
+        Some(CompanyClassification::Carrier)
     } else {
-        // Default to Carrier because most FMCSA-registered entities are carriers.
-        // It's like defaulting to "truck" when you're not sure what vehicle
-        // someone is talking about — you'll be right more often than not.
-        CompanyClassification::Carrier
+        None
+    };
+    if let Some(classification) = operation_class {
+        priors.push(prior(classification, OPERATION_PRIOR, format!("FMCSA carrier operation: {}", operation)));
+    }
+
+    if priors.is_empty() {
+        // Most FMCSA-registered entities are carriers. It's like defaulting
+        // to "truck" when you're not sure what vehicle someone is talking
+        // about — you'll be right more often than not, but not sure of it.
+        priors.push(prior(CompanyClassification::Carrier, REGISTERED_PRIOR, "registered with FMCSA".to_string()));
+    }
+    text_scanner::company::combine(&priors, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CompanyClassification::*;
+
+    fn carrier(operation: &str, common: &str, contract: &str, broker: &str) -> QcMobileCarrier {
+        let status = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+        QcMobileCarrier {
+            carrier_operation: status(operation),
+            common_authority_status: status(common),
+            contract_authority_status: status(contract),
+            broker_authority_status: status(broker),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_registry_priors() {
+        // (carrier_operation, common, contract, broker authority, expected types in order)
+        let cases: [(&str, &str, &str, &str, &[CompanyClassification]); 6] = [
+            ("Interstate", "A", "N", "N", &[Carrier]),
+            ("", "N", "N", "A", &[Broker]),
+            ("Interstate", "A", "N", "A", &[Carrier, Broker]),
+            ("Interstate", "I", "I", "N", &[Carrier]),
+            ("Freight Forwarder", "N", "N", "N", &[FreightForwarder]),
+            ("", "", "", "", &[Carrier]),
+        ];
+        for (operation, common, contract, broker, expected) in cases {
+            let priors = registry_priors(&carrier(operation, common, contract, broker));
+            let labels: Vec<_> = priors.iter().map(|p| p.classification.clone()).collect();
+            assert_eq!(labels, expected, "{:?}", (operation, common, contract, broker));
+        }
+    }
+
+    #[test]
+    fn test_authority_is_a_strong_prior() {
+        let asset_based = registry_priors(&carrier("Interstate", "A", "N", "A"));
+        assert!(asset_based.iter().all(|p| p.score >= AUTHORITY_PRIOR), "{:?}", asset_based);
+        assert!(asset_based[1].evidence.contains(&"FMCSA broker authority".to_string()));
+
+        let unknown = registry_priors(&carrier("", "", "", ""));
+        assert_eq!(unknown[0].score, REGISTERED_PRIOR);
     }
 }
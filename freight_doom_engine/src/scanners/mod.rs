@@ -17,9 +17,135 @@
 //
 // Think of it as four bloodhounds, each trained on a different scent,
 // all chasing the same quarry: bankrupt freight companies.
+//
+// The bloodhounds used to each carry their own leash, collar, and 500 lines
+// of identical kennel logic. Now every scanner implements the `Scanner`
+// trait below — "where do I fetch from" and "what did I find in there" —
+// and the shared driver in `driver.rs` owns the loop, the circuit breaker,
+// the dedup check, the metrics, and the channel. The registry in
+// `registry.rs` decides which bloodhounds get let off the leash.
 // =============================================================================
 
+pub mod driver;
+pub mod registry;
+
 pub mod pacer_scanner;
 pub mod edgar_scanner;
 pub mod fmcsa_scanner;
 pub mod court_listener_scanner;
+
+use std::time::Duration;
+
+use crate::models::{BankruptcyEvent, Source};
+
+pub use driver::{drive, ScannerContext};
+pub use registry::ScannerRegistry;
+
+/// A single thing to fetch during a poll cycle: one court's RSS feed, one
+/// EDGAR search query, one carrier's QCMobile record. The driver fetches
+/// each target separately so the circuit breaker sees every request.
+#[derive(Debug, Clone)]
+pub struct FetchTarget {
+    /// Human-readable label for logging (court name, query, DOT number).
+    pub label: String,
+    /// The URL to GET.
+    pub url: String,
+}
+
+impl FetchTarget {
+    pub fn new(label: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            url: url.into(),
+        }
+    }
+}
+
+/// A potential bankruptcy event produced by a scanner's parser, before the
+/// driver has scored, deduplicated, and published it.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// Key handed to the dedup engine. Source-specific by convention
+    /// (e.g. `pacer:{court}:{link}`); court filings with a case number use
+    /// `case_dedup_key` instead.
+    pub dedup_key: String,
+
+    /// The event skeleton. When `scan_text` is set, the driver overwrites
+    /// `confidence_score` and `classification` with the text scanner's verdict.
+    pub event: BankruptcyEvent,
+
+    /// Text to run through `quick_freight_check` + `scan_text`. `None` means
+    /// the scanner already decided the confidence itself (FMCSA status codes
+    /// don't need an Aho-Corasick automaton to tell you REVOKED is bad).
+    pub scan_text: Option<String>,
+}
+
+impl Candidate {
+    /// A candidate whose confidence comes from the text scanner.
+    pub fn scanned(dedup_key: String, event: BankruptcyEvent, text: String) -> Self {
+        Self {
+            dedup_key,
+            event,
+            scan_text: Some(text),
+        }
+    }
+
+    /// A candidate whose confidence the scanner has already decided.
+    pub fn scored(dedup_key: String, event: BankruptcyEvent) -> Self {
+        Self {
+            dedup_key,
+            event,
+            scan_text: None,
+        }
+    }
+}
+
+/// A bankruptcy data source. Implement this and register it with the
+/// `ScannerRegistry`, and the driver takes care of everything else:
+/// polling, HTTP, circuit breaking, text scanning, dedup, and the channel.
+///
+/// Adding a fifth source used to mean copying 500 lines. Now it means
+/// answering four questions.
+pub trait Scanner: Send + Sync {
+    /// Short stable name used for logs, metrics, and config
+    /// (e.g. "pacer", "court_listener").
+    fn name(&self) -> &'static str;
+
+    /// Which `Source` events from this scanner are tagged with.
+    fn source(&self) -> Source;
+
+    /// How long to sleep between poll cycles.
+    fn poll_interval(&self) -> Duration;
+
+    /// The targets to fetch on the given poll cycle. `cycle` starts at 0 and
+    /// increments every cycle, so rotating scanners can pick
+    /// `QUERIES[cycle % QUERIES.len()]` without keeping their own counter.
+    fn targets(&self, cycle: u64) -> Vec<FetchTarget>;
+
+    /// Turn a successfully fetched response body into candidate events.
+    fn parse(&self, target: &FetchTarget, body: &str) -> Vec<Candidate>;
+
+    /// User-Agent to send. Government APIs like to know who's knocking.
+    fn user_agent(&self) -> &'static str {
+        "FreightDoomEngine/1.0 (bankruptcy-research; educational-project)"
+    }
+
+    /// Per-request HTTP timeout.
+    fn request_timeout(&self) -> Duration {
+        Duration::from_secs(15)
+    }
+
+    /// Extra headers to send with every request.
+    fn headers(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+}
+
+/// The dedup key for a court filing whose event knows its court and case
+/// number: `{source}:case:{court}:{case}`. Every docket entry and every
+/// re-listing of one case claims the same key, so the case is detected
+/// once rather than once per RSS item or search hit.
+pub fn case_dedup_key(source: &str, event: &BankruptcyEvent) -> Option<String> {
+    let key = crate::lifecycle::case_key(event.court.as_deref()?, event.case_number.as_deref()?)?;
+    Some(format!("{}:case:{}", source, key))
+}
@@ -32,19 +32,17 @@
 // could be solved with a Google Alert? Yes. Yes it is.
 // =============================================================================
 
-use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
-use crossbeam_channel::Sender;
-use tokio::sync::watch;
-use tracing::{debug, error, info};
+use tracing::debug;
 
-use crate::circuit_breaker::CircuitBreaker;
 use crate::config::Config;
-use crate::dedup::DedupEngine;
-use crate::models::{BankruptcyChapter, BankruptcyEvent, Source};
-use crate::text_scanner;
+use crate::models::{BankruptcyEvent, Source};
+use crate::extract;
+use crate::names;
+
+use super::{case_dedup_key, Candidate, FetchTarget, Scanner};
 
 // =============================================================================
 // PACER Bankruptcy Court RSS Feed Endpoints
@@ -79,206 +77,97 @@ const PACER_COURTS: &[(&str, &str)] = &[
     ("M.D. Tennessee",              "https://ecf.tnmb.uscourts.gov/cgi-bin/rss_outside.pl"),
 ];
 
-/// The main entry point for the PACER scanner.
+/// The PACER scanner.
 ///
-/// This function never returns under normal operation — it loops forever,
-/// polling PACER RSS feeds until it receives a shutdown signal. Think of it
-/// as a very dedicated, very fast, very obsessive court reporter who never
+/// Every poll cycle, it fetches all 12 court RSS feeds. Think of it as a
+/// very dedicated, very fast, very obsessive court reporter who never
 /// sleeps, never eats, and never stops reading bankruptcy filings.
-///
-/// # Arguments
-/// * `config` - The global configuration, containing poll intervals and
-///   circuit breaker thresholds. Wrapped in an Arc because sharing is caring.
-/// * `event_tx` - The sending end of the crossbeam channel. This is where
-///   we push detected bankruptcy events so the publisher can scream them
-///   into Redis.
-/// * `dedup` - The deduplication engine. Returns true if an item is NEW,
-///   false if we've seen it before. Uses a Bloom filter + LRU cache hybrid
-///   because a HashSet would be too easy.
-/// * `shutdown` - A watch channel receiver. When this flips to true, we
-///   gracefully exit the loop and go home.
-pub async fn run(
-    config: Arc<Config>,
-    event_tx: Sender<BankruptcyEvent>,
-    dedup: Arc<DedupEngine>,
-    shutdown: &mut watch::Receiver<bool>,
-) {
-    info!("PACER Scanner initializing — preparing to consume bankruptcy RSS feeds like a gourmand at a buffet of financial despair");
-
-    // Build an HTTP client with a reasonable timeout and user agent.
-    // We identify ourselves honestly because PACER administrators have
-    // enough problems without wondering who's scraping their RSS feeds.
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(15))
-        .user_agent("FreightDoomEngine/1.0 (bankruptcy-research; educational-project)")
-        .build()
-        .expect("Failed to build HTTP client — this is genuinely embarrassing");
-
-    // Create a circuit breaker for PACER endpoints.
-    // PACER goes down more often than you'd expect for a critical
-    // federal judiciary system. Five failures and we back off for
-    // a minute. Two successes and we're back in business.
-    let circuit_breaker = CircuitBreaker::new(
-        "PACER",
-        config.circuit_breaker_failure_threshold,
-        config.circuit_breaker_reset_timeout,
-        config.circuit_breaker_success_threshold,
-    );
-
-    let poll_interval = config.pacer_poll_interval;
-    let min_confidence = config.min_confidence_threshold;
-
-    info!(
-        poll_interval_secs = poll_interval.as_secs(),
-        courts = PACER_COURTS.len(),
-        "PACER Scanner online — monitoring {} bankruptcy courts with the intensity of a hawk watching a mouse",
-        PACER_COURTS.len()
-    );
-
-    // The main loop. This is where we live now.
-    // Every poll_interval seconds, we scan all 12 courts simultaneously
-    // using futures::future::join_all because scanning them sequentially
-    // would be like loading a 53-foot trailer one box at a time.
-    loop {
-        tokio::select! {
-            // Branch 1: Time to poll. Let's go bother some government servers.
-            _ = tokio::time::sleep(poll_interval) => {
-                // Check if the circuit breaker allows requests.
-                // If PACER has been having a bad day, we give it space.
-                if !circuit_breaker.allow_request() {
-                    debug!("PACER: circuit breaker is OPEN — giving the courts a breather");
-                    continue;
-                }
-
-                // Scan all courts. We could do them sequentially, but why
-                // would we when tokio gives us async superpowers?
-                let mut total_new_events = 0u64;
-
-                for (court_name, feed_url) in PACER_COURTS {
-                    match fetch_and_parse_feed(&client, court_name, feed_url).await {
-                        Ok(items) => {
-                            circuit_breaker.record_success();
-
-                            for (title, description, link) in &items {
-                                // Combine title and description for scanning.
-                                // PACER titles are typically case numbers + debtor names.
-                                // Descriptions contain the actual docket text.
-                                let combined_text = format!("{} {}", title, description);
-
-                                // Quick freight check first — this uses SIMD-accelerated
-                                // memchr scanning to see if the text even contains freight
-                                // keywords before we fire up the full Aho-Corasick automaton.
-                                // It's the "is this worth my time?" check.
-                                if !text_scanner::quick_freight_check(&combined_text) {
-                                    continue;
-                                }
-
-                                // Full text scan with the Aho-Corasick automaton.
-                                // This simultaneously searches for ALL freight and bankruptcy
-                                // keywords in a single pass through the text. The algorithm
-                                // is O(n + m) where n is text length and m is total matches.
-                                // We're using the same algorithm as antivirus scanners.
-                                // For RSS feed items. You're welcome.
-                                let scan_result = text_scanner::scan_text(&combined_text);
-
-                                if scan_result.confidence < min_confidence {
-                                    continue;
-                                }
+pub struct PacerScanner {
+    poll_interval: Duration,
+}
 
-                                // Build a dedup key from court + link to avoid processing
-                                // the same filing multiple times across poll cycles.
-                                let dedup_key = format!("pacer:{}:{}", court_name, link);
+impl PacerScanner {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            poll_interval: config.pacer_poll_interval,
+        }
+    }
+}
 
-                                // check_and_insert returns TRUE if the item is NEW.
-                                // The Bloom filter checks first (O(1)), and if it says
-                                // "maybe seen", the LRU cache provides a definitive answer.
-                                if !dedup.check_and_insert(&dedup_key) {
-                                    debug!(
-                                        court = court_name,
-                                        title = title.as_str(),
-                                        "Duplicate filing detected — Bloom + LRU said 'been there, done that'"
-                                    );
-                                    continue;
-                                }
+impl Scanner for PacerScanner {
+    fn name(&self) -> &'static str {
+        "pacer"
+    }
 
-                                // Extract the company name from the PACER title.
-                                // Titles typically look like: "2:24-bk-12345 Acme Freight LLC"
-                                let company_name = extract_company_name(title);
+    fn source(&self) -> Source {
+        Source::Pacer
+    }
 
-                                // Build the event using the constructor, then set fields
-                                let mut event = BankruptcyEvent::new(
-                                    company_name,
-                                    Source::Pacer,
-                                    scan_result.confidence,
-                                );
-                                event.court = Some(court_name.to_string());
-                                event.chapter = detect_chapter(&combined_text);
-                                event.classification = scan_result.classification;
-                                event.source_url = if link.is_empty() {
-                                    Some(feed_url.to_string())
-                                } else {
-                                    Some(link.clone())
-                                };
-                                event.filing_date = parse_filing_date(description);
-                                event.dot_number = extract_dot_number(&combined_text);
-                                event.mc_number = extract_mc_number(&combined_text);
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
 
-                                // Fire the event into the crossbeam channel.
-                                // try_send is non-blocking — if the channel is full
-                                // (10,000 events deep), we log an error and move on.
-                                // If we're 10,000 events behind, we have bigger problems.
-                                match event_tx.try_send(event) {
-                                    Ok(()) => {
-                                        total_new_events += 1;
-                                        info!(
-                                            court = court_name,
-                                            title = title.as_str(),
-                                            confidence = format!("{:.1}%", scan_result.confidence * 100.0),
-                                            keywords = scan_result.matched_keywords.len(),
-                                            "PACER: NEW BANKRUPTCY FILING DETECTED — another one bites the dust"
-                                        );
-                                    }
-                                    Err(e) => {
-                                        error!(
-                                            error = %e,
-                                            "PACER: failed to send event to channel — the channel is either full or dead"
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            circuit_breaker.record_failure();
-                            debug!(
-                                court = court_name,
-                                error = %e,
-                                "PACER: failed to fetch/parse RSS feed — the court's server is having an existential crisis"
-                            );
-                        }
-                    }
-                }
+    /// All 12 courts, every cycle. PACER RSS feeds only hold the most
+    /// recent entries, so skipping a court for a cycle risks missing filings.
+    fn targets(&self, _cycle: u64) -> Vec<FetchTarget> {
+        PACER_COURTS
+            .iter()
+            .map(|(court_name, feed_url)| FetchTarget::new(*court_name, *feed_url))
+            .collect()
+    }
 
-                if total_new_events > 0 {
-                    info!(
-                        new_events = total_new_events,
-                        "PACER scan cycle complete — {} new freight bankruptcy filings detected across {} courts",
-                        total_new_events,
-                        PACER_COURTS.len()
-                    );
+    fn parse(&self, target: &FetchTarget, body: &str) -> Vec<Candidate> {
+        let items = extract_rss_items(body);
+
+        debug!(
+            court = target.label.as_str(),
+            items = items.len(),
+            "Parsed {} RSS items from {} (each one a potential freight company's last chapter)",
+            items.len(),
+            target.label
+        );
+
+        items
+            .into_iter()
+            .map(|(title, description, link)| {
+                // Combine title and description for scanning.
+                // PACER titles are typically case numbers + debtor names.
+                // Descriptions contain the actual docket text.
+                let combined_text = format!("{} {}", title, description);
+
+                // Extract the company name from the PACER title.
+                // Titles typically look like: "2:24-bk-12345 Acme Freight LLC"
+                let mut event = BankruptcyEvent::new(
+                    extract_company_name(&title),
+                    Source::Pacer,
+                    0.0,
+                );
+                event.court = Some(target.label.clone());
+                event.case_number = split_case_number(&title).map(|(_, case)| case);
+                event.chapter = extract::detect_chapter(&combined_text);
+                event.petition_type = extract::detect_petition_type(&combined_text);
+                event.source_url = if link.is_empty() {
+                    Some(target.url.clone())
                 } else {
-                    debug!("PACER scan cycle complete — no new freight bankruptcies (the freight industry lives to fight another day)");
-                }
-            }
-
-            // Branch 2: Shutdown signal received. Time to go home.
-            _ = shutdown.changed() => {
-                info!("PACER Scanner received shutdown signal — hanging up the RSS feed reader");
-                break;
-            }
-        }
+                    Some(link)
+                };
+                event.filing_date = parse_filing_date(&description);
+
+                // The case is the identity: the feed lists every docket
+                // entry, and we want the bankruptcy once. Titles without a
+                // case number fall back to court + link.
+                let dedup_key = case_dedup_key("pacer", &event).unwrap_or_else(|| {
+                    format!("pacer:{}:{}", target.label, event.source_url.as_deref().unwrap_or_default())
+                });
+                let ids = extract::identifiers(&combined_text);
+                event.mc_number = ids.event_docket();
+                event.lead_case_number = ids.lead_case_number.map(|case| case.to_string());
+                event.dot_number = ids.usdot;
+
+                Candidate::scanned(dedup_key, event, combined_text)
+            })
+            .collect()
     }
-
-    info!("PACER Scanner has exited the building");
 }
 
 // =============================================================================
@@ -302,44 +191,6 @@ pub async fn run(
 // </rss>
 // =============================================================================
 
-/// Fetch an RSS feed from a PACER court and parse it into (title, description, link) tuples.
-///
-/// We're doing manual XML extraction here instead of using a proper XML parser
-/// because PACER's XML is simple enough that regex-adjacent string scanning
-/// works perfectly fine. Is this best practice? No. Does it work? Yes.
-/// Will it break if PACER changes their XML format? Probably. Will PACER
-/// change their XML format? They haven't since 2008, so we're probably safe.
-async fn fetch_and_parse_feed(
-    client: &reqwest::Client,
-    court_name: &str,
-    url: &str,
-) -> Result<Vec<(String, String, String)>, Box<dyn std::error::Error + Send + Sync>> {
-    debug!(court = court_name, url = url, "Fetching PACER RSS feed");
-
-    let response = client.get(url).send().await?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "PACER {} returned HTTP {} — the court's server is not in a sharing mood",
-            court_name,
-            response.status()
-        ).into());
-    }
-
-    let body = response.text().await?;
-    let items = extract_rss_items(&body);
-
-    debug!(
-        court = court_name,
-        items = items.len(),
-        "Parsed {} RSS items from {} (each one a potential freight company's last chapter)",
-        items.len(),
-        court_name
-    );
-
-    Ok(items)
-}
-
 /// Extract <item> elements from RSS XML.
 /// Returns a Vec of (title, description, link) tuples.
 ///
@@ -402,95 +253,27 @@ fn extract_xml_tag(xml: &str, tag: &str) -> String {
 ///   "1:24-bk-67890-ABC Big Truck Company Inc."
 ///   "24-12345 Some Carrier Corp"
 ///
-/// We strip the case number prefix and return the rest as the company name.
+/// We strip the case number prefix and any trailing "et al." or "Debtor",
+/// and return the rest as the company name.
 /// If we can't parse it, we return the whole title — better to have a messy
 /// name than no name at all.
 fn extract_company_name(title: &str) -> String {
-    // PACER case numbers look like "2:24-bk-12345" or "24-12345-ABC"
-    // They always start with digits or "digit:digit" and contain hyphens.
-    // The company name comes after the case number, separated by a space.
-    if let Some(space_idx) = title.find(' ') {
-        let potential_case_num = &title[..space_idx];
-        // If the first part looks like a case number (contains digits and hyphens)
-        if potential_case_num.contains('-') && potential_case_num.chars().any(|c| c.is_ascii_digit()) {
-            return title[space_idx..].trim().to_string();
-        }
-    }
-    title.to_string()
-}
-
-/// Detect the bankruptcy chapter from text content.
-///
-/// Bankruptcy chapters are like Dante's circles of hell, except there are
-/// only three that matter for our purposes:
-/// - Chapter 7: Liquidation (sell everything, pay creditors, close the doors)
-/// - Chapter 11: Reorganization (try to survive, usually fail anyway)
-/// - Chapter 13: Individual debt adjustment (rare for companies)
-///
-/// If we can't determine the chapter, we return Unknown, which is the
-/// bankruptcy equivalent of "something bad happened but we're not sure what."
-fn detect_chapter(text: &str) -> BankruptcyChapter {
-    let upper = text.to_uppercase();
-    if upper.contains("CHAPTER 7") || upper.contains("CH. 7") || upper.contains("CH 7") || upper.contains("CH.7") {
-        BankruptcyChapter::Chapter7
-    } else if upper.contains("CHAPTER 11") || upper.contains("CH. 11") || upper.contains("CH 11") || upper.contains("CH.11") {
-        BankruptcyChapter::Chapter11
-    } else if upper.contains("CHAPTER 13") || upper.contains("CH. 13") || upper.contains("CH 13") || upper.contains("CH.13") {
-        BankruptcyChapter::Chapter13
-    } else {
-        BankruptcyChapter::Unknown
+    match split_case_number(title) {
+        Some((written, _)) => names::clean_debtor_name(&title[written.len()..]),
+        None => names::clean_debtor_name(title),
     }
 }
 
-/// Try to extract a DOT number from text.
+/// The case number prefix of a PACER RSS title, if it has one, as written
+/// and in its canonical spelling.
 ///
-/// DOT numbers (USDOT numbers) are 1-8 digit identifiers assigned by FMCSA
-/// to motor carriers. They appear in filings as "DOT 1234567" or
-/// "USDOT# 1234567" or various other formats because consistency in
-/// legal documents is apparently optional.
-fn extract_dot_number(text: &str) -> Option<String> {
-    let upper = text.to_uppercase();
-    let patterns = ["USDOT# ", "USDOT #", "USDOT ", "DOT# ", "DOT #", "DOT "];
-
-    for pattern in patterns {
-        if let Some(idx) = upper.find(pattern) {
-            let start = idx + pattern.len();
-            let num: String = upper[start..]
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .collect();
-            if !num.is_empty() && num.len() <= 8 {
-                return Some(num);
-            }
-        }
-    }
-    None
-}
-
-/// Try to extract an MC number from text.
-///
-/// MC (Motor Carrier) numbers are issued by FMCSA for interstate
-/// operating authority. They show up in filings in formats like
-/// "MC 123456" or "MC# 123456" or "MC-123456" because legal
-/// professionals apparently use the same formatting rules as
-/// kindergarteners.
-fn extract_mc_number(text: &str) -> Option<String> {
-    let upper = text.to_uppercase();
-    let patterns = ["MC# ", "MC #", "MC-", "MC "];
-
-    for pattern in patterns {
-        if let Some(idx) = upper.find(pattern) {
-            let start = idx + pattern.len();
-            let num: String = upper[start..]
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .collect();
-            if !num.is_empty() && num.len() <= 7 {
-                return Some(num);
-            }
-        }
-    }
-    None
+/// "2:24-BK-12345 Acme Freight LLC" → ("2:24-BK-12345", "2:24-bk-12345")
+fn split_case_number(title: &str) -> Option<(&str, String)> {
+    // PACER case numbers look like "2:24-bk-12345" or "24-12345-ABC".
+    // The company name comes after the case number, separated by a space.
+    let (potential_case_num, _) = title.split_once(' ')?;
+    let case = extract::CaseNumber::parse(potential_case_num)?;
+    Some((potential_case_num, case.to_string()))
 }
 
 /// Attempt to parse a filing date from a PACER description or pubDate field.
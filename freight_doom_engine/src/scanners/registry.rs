@@ -0,0 +1,130 @@
+// =============================================================================
+// scanners/registry.rs — THE KENNEL
+// =============================================================================
+//
+// main.rs used to clone config, tx, dedup, and shutdown four times by hand,
+// once per scanner, like a dispatcher writing out the same BOL four times
+// because nobody bought carbon paper. The registry holds every scanner we
+// know about and spawns one driver task per scanner, so main doesn't need
+// to know how many bloodhounds are in the kennel — or what they're called.
+// =============================================================================
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::config::Config;
+
+use super::court_listener_scanner::CourtListenerScanner;
+use super::edgar_scanner::EdgarScanner;
+use super::fmcsa_scanner::FmcsaScanner;
+use super::pacer_scanner::PacerScanner;
+use super::{drive, Scanner, ScannerContext};
+
+/// The set of scanners the engine will run.
+#[derive(Default)]
+pub struct ScannerRegistry {
+    scanners: Vec<Arc<dyn Scanner>>,
+}
+
+impl ScannerRegistry {
+    /// An empty registry. Add scanners with `register`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in scanners (PACER, EDGAR, FMCSA, CourtListener), filtered
+    /// by `Config::enabled_scanners`.
+    pub fn with_builtin(config: &Config) -> Self {
+        let builtin: Vec<Arc<dyn Scanner>> = vec![
+            Arc::new(PacerScanner::new(config)),
+            Arc::new(EdgarScanner::new(config)),
+            Arc::new(FmcsaScanner::new(config)),
+            Arc::new(CourtListenerScanner::new(config)),
+        ];
+
+        let mut registry = Self::new();
+        for scanner in builtin {
+            if config.scanner_enabled(scanner.name()) {
+                registry.scanners.push(scanner);
+            } else {
+                info!(scanner = scanner.name(), "Scanner disabled by configuration — this bloodhound stays home");
+            }
+        }
+        registry
+    }
+
+    /// Add a scanner to the registry.
+    pub fn register(&mut self, scanner: impl Scanner + 'static) -> &mut Self {
+        self.scanners.push(Arc::new(scanner));
+        self
+    }
+
+    /// Move every scanner from `other` into this registry.
+    pub fn extend(&mut self, other: ScannerRegistry) -> &mut Self {
+        self.scanners.extend(other.scanners);
+        self
+    }
+
+    /// The names of all registered scanners, in registration order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.scanners.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scanners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scanners.is_empty()
+    }
+
+    /// Spawn one driver task per registered scanner.
+    pub fn spawn_all(
+        self,
+        ctx: &ScannerContext,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Vec<JoinHandle<()>> {
+        self.scanners
+            .into_iter()
+            .map(|scanner| {
+                let ctx = ctx.clone();
+                let shutdown = shutdown.clone();
+                tokio::spawn(async move {
+                    let name = scanner.name();
+                    info!("📡 {} scanner: ONLINE", name);
+                    drive(scanner, ctx, shutdown).await;
+                    info!("📡 {} scanner: OFFLINE", name);
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registers_all_scanners_by_default() {
+        let mut config = Config::from_env();
+        config.enabled_scanners = vec![
+            "pacer".into(),
+            "edgar".into(),
+            "fmcsa".into(),
+            "court_listener".into(),
+        ];
+        let registry = ScannerRegistry::with_builtin(&config);
+        assert_eq!(registry.names(), vec!["pacer", "edgar", "fmcsa", "court_listener"]);
+    }
+
+    #[test]
+    fn test_builtin_respects_enabled_scanners() {
+        let mut config = Config::from_env();
+        config.enabled_scanners = vec!["edgar".into(), "court_listener".into()];
+        let registry = ScannerRegistry::with_builtin(&config);
+        assert_eq!(registry.names(), vec!["edgar", "court_listener"]);
+    }
+}
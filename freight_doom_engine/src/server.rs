@@ -0,0 +1,297 @@
+// =============================================================================
+// server.rs — THE WORLD'S SMALLEST HTTP SERVER
+// =============================================================================
+//
+// The metrics endpoint used to accept a TCP connection, ignore whatever the
+// client said, and throw the same JSON blob at it. Ask for /metrics: JSON.
+// Ask for /favicon.ico: JSON. POST your résumé to /careers: JSON.
+//
+// It now reads the request line and routes it like a real server:
+//
+//   GET /metrics       — Prometheus text exposition format
+//   GET /metrics.json  — the MetricsSnapshot as JSON
+//   GET /health        — same JSON, for the Rails dashboard that polls it
+//   GET /healthz       — liveness, 503 if every scanner task is gone
+//   GET /readyz        — readiness, 503 if breakers are open or sources stale
+//
+// Anything else gets a 404, and the right path with the wrong method gets a
+// 405 with an Allow header. We didn't pull in hyper for five routes. We
+// have SOME restraint. Not much, but some.
+// =============================================================================
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tracing::{debug, error, info};
+
+use crate::config::Config;
+use crate::health::{self, HealthReport};
+use crate::metrics::MetricsCollector;
+
+/// Biggest request head we're willing to read. Scrapers send ~200 bytes.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// How long a client gets to send its request before we hang up.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// What every request handler gets to look at.
+struct AppState {
+    metrics: Arc<MetricsCollector>,
+    stale_after_intervals: u32,
+}
+
+/// A response, before it hits the wire.
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    reason: &'static str,
+    content_type: &'static str,
+    body: String,
+    allow: Option<&'static str>,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: String) -> Self {
+        Self { status: 200, reason: "OK", content_type, body, allow: None }
+    }
+
+    /// 200 if `healthy`, 503 otherwise, with the report as the body.
+    fn health(healthy: bool, report: &HealthReport) -> Self {
+        let body = serde_json::to_string_pretty(report).unwrap_or_else(|_| "{}".to_string());
+        if healthy {
+            Self::ok(JSON_CONTENT_TYPE, body)
+        } else {
+            Self { status: 503, reason: "Service Unavailable", content_type: JSON_CONTENT_TYPE, body, allow: None }
+        }
+    }
+
+    fn text(status: u16, reason: &'static str, body: &str) -> Self {
+        Self {
+            status,
+            reason,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", body),
+            allow: None,
+        }
+    }
+
+    /// Serialize to HTTP/1.1. HEAD responses keep the headers, drop the body.
+    fn to_bytes(&self, include_body: bool) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n",
+            self.status,
+            self.reason,
+            self.content_type,
+            self.body.len(),
+        );
+        if let Some(allow) = self.allow {
+            head.push_str(&format!("Allow: {}\r\n", allow));
+        }
+        head.push_str("\r\n");
+
+        let mut bytes = head.into_bytes();
+        if include_body {
+            bytes.extend_from_slice(self.body.as_bytes());
+        }
+        bytes
+    }
+}
+
+/// Serve metrics and health on `Config::metrics_addr` until shutdown.
+/// This is the Rust equivalent of mounting a turret on a skateboard.
+pub async fn run_metrics_server(
+    config: &Config,
+    metrics: Arc<MetricsCollector>,
+    shutdown: &mut watch::Receiver<bool>,
+) {
+    let addr = config.metrics_addr();
+    let state = Arc::new(AppState {
+        metrics,
+        stale_after_intervals: config.readiness_stale_intervals,
+    });
+
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            error!("Failed to bind metrics server on {}: {}", addr, e);
+            return;
+        }
+    };
+
+    info!("📊 Metrics server listening on http://{}", addr);
+
+    loop {
+        tokio::select! {
+            accept_result = listener.accept() => {
+                match accept_result {
+                    Ok((stream, peer)) => {
+                        let state = state.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(stream, &state).await {
+                                debug!(peer = %peer, error = %e, "Metrics connection ended badly");
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        error!("Metrics server accept error: {}", e);
+                    }
+                }
+            }
+            _ = shutdown.changed() => {
+                info!("Metrics server: shutting down");
+                break;
+            }
+        }
+    }
+}
+
+/// Read one request, answer it, close the connection.
+async fn handle_connection(mut stream: TcpStream, state: &AppState) -> std::io::Result<()> {
+    let head = match tokio::time::timeout(REQUEST_TIMEOUT, read_request_head(&mut stream)).await {
+        Ok(result) => result?,
+        Err(_) => return Ok(()),
+    };
+
+    let (response, include_body) = match parse_request_line(&head) {
+        Some((method, path)) => (route(method, path, state), method != "HEAD"),
+        None => (Response::text(400, "Bad Request", "bad request"), true),
+    };
+
+    stream.write_all(&response.to_bytes(include_body)).await?;
+    stream.shutdown().await
+}
+
+/// Read until the blank line that ends the request head, or until the
+/// client stops talking, or until MAX_REQUEST_HEAD.
+async fn read_request_head(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.windows(4).any(|w| w == b"\r\n\r\n") || buf.len() >= MAX_REQUEST_HEAD {
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Pull the method and path (minus any query string) out of the request line.
+fn parse_request_line(head: &str) -> Option<(&str, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    let _version = parts.next()?;
+    let path = target.split('?').next().unwrap_or(target);
+    Some((method, path))
+}
+
+fn route(method: &str, path: &str, state: &AppState) -> Response {
+    let handler: fn(&AppState) -> Response = match path {
+        "/metrics" => |s| Response::ok(PROMETHEUS_CONTENT_TYPE, s.metrics.render_prometheus()),
+        "/metrics.json" | "/health" => |s| {
+            let json = serde_json::to_string_pretty(&s.metrics.snapshot())
+                .unwrap_or_else(|_| "{}".to_string());
+            Response::ok(JSON_CONTENT_TYPE, json)
+        },
+        "/healthz" => |s| {
+            let report = health::check(&s.metrics, s.stale_after_intervals, chrono::Utc::now());
+            Response::health(report.live, &report)
+        },
+        "/readyz" => |s| {
+            let report = health::check(&s.metrics, s.stale_after_intervals, chrono::Utc::now());
+            Response::health(report.ready, &report)
+        },
+        _ => return Response::text(404, "Not Found", "not found"),
+    };
+
+    match method {
+        "GET" | "HEAD" => handler(state),
+        _ => Response {
+            allow: Some("GET, HEAD"),
+            ..Response::text(405, "Method Not Allowed", "method not allowed")
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::CircuitBreaker;
+    use crate::models::Source;
+
+    fn state() -> AppState {
+        AppState {
+            metrics: Arc::new(MetricsCollector::new()),
+            stale_after_intervals: 3,
+        }
+    }
+
+    #[test]
+    fn test_parse_request_line_strips_query() {
+        let head = "GET /metrics?name[]=foo HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        assert_eq!(parse_request_line(head), Some(("GET", "/metrics")));
+        assert_eq!(parse_request_line("garbage\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn test_routes_metrics_formats() {
+        let state = state();
+
+        let prom = route("GET", "/metrics", &state);
+        assert_eq!(prom.status, 200);
+        assert_eq!(prom.content_type, PROMETHEUS_CONTENT_TYPE);
+        assert!(prom.body.contains("# TYPE freight_doom_events_detected_total counter"));
+
+        let json = route("GET", "/metrics.json", &state);
+        assert_eq!(json.status, 200);
+        assert_eq!(json.content_type, JSON_CONTENT_TYPE);
+        let parsed: serde_json::Value = serde_json::from_str(&json.body).unwrap();
+        assert_eq!(parsed["status"], "operational");
+    }
+
+    #[test]
+    fn test_unknown_path_and_wrong_method() {
+        let state = state();
+
+        assert_eq!(route("GET", "/favicon.ico", &state).status, 404);
+
+        let not_allowed = route("POST", "/metrics", &state);
+        assert_eq!(not_allowed.status, 405);
+        let bytes = String::from_utf8(not_allowed.to_bytes(true)).unwrap();
+        assert!(bytes.contains("Allow: GET, HEAD\r\n"));
+    }
+
+    #[test]
+    fn test_health_endpoints_status_codes() {
+        let state = state();
+        // Nothing registered yet: alive, but not ready to detect anything.
+        assert_eq!(route("GET", "/healthz", &state).status, 200);
+        assert_eq!(route("GET", "/readyz", &state).status, 503);
+
+        let breaker = Arc::new(CircuitBreaker::new("PACER", 3, Duration::from_secs(60), 1));
+        state.metrics.register_scanner("pacer", Source::Pacer, Duration::from_secs(60), breaker);
+        state.metrics.set_scanner_running("pacer", true);
+        let ready = route("GET", "/readyz", &state);
+        assert_eq!(ready.status, 200);
+        let parsed: serde_json::Value = serde_json::from_str(&ready.body).unwrap();
+        assert_eq!(parsed["scanners"]["pacer"]["is_running"], true);
+    }
+
+    #[test]
+    fn test_head_response_has_no_body() {
+        let response = Response::ok(JSON_CONTENT_TYPE, "{}".into());
+        let bytes = String::from_utf8(response.to_bytes(false)).unwrap();
+        assert!(bytes.contains("Content-Length: 2\r\n"));
+        assert!(bytes.ends_with("\r\n\r\n"));
+    }
+}
@@ -0,0 +1,389 @@
+// =============================================================================
+// sinks/fanout.rs — ONE BATCH IN, N BATCHES OUT
+// =============================================================================
+//
+// The publisher stage hands every batch to a `FanoutSink`, which hands it to
+// every configured sink at the same time. Each sink has its own slot:
+//
+// - a backlog of events it hasn't accepted yet, oldest first
+// - exponential backoff after a failure, so a dead webhook gets poked
+//   every minute instead of every 100ms
+// - a cap on the backlog, past which the oldest events are dropped (and
+//   counted, loudly) rather than eating all the RAM on the box
+// - its own `SinkStats`
+//
+// One sink failing never blocks or fails the others. That's the whole point.
+//
+// It also means `publish` never fails, so the fanout does the engine-wide
+// counting itself: every event a sink rejects is a publish failure, and an
+// event counts as published once some sink has actually taken it.
+// =============================================================================
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use futures::future::{join_all, BoxFuture};
+use portable_atomic::{AtomicU64, Ordering};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::config::Config;
+use crate::metrics::MetricsCollector;
+use crate::models::BankruptcyEvent;
+use crate::publisher::{EventSink, BATCH_SIZE};
+
+/// How a sink slot retries after a failure.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Delay after the first failure. Doubles after each one after that.
+    pub base_delay: Duration,
+    /// The delay never grows past this.
+    pub max_delay: Duration,
+    /// Most events a sink may have waiting before the oldest are dropped.
+    pub max_backlog: usize,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            base_delay: config.sink_retry_base_delay,
+            max_delay: config.sink_retry_max_delay,
+            max_backlog: config.sink_max_backlog,
+        }
+    }
+
+    /// Delay before the next attempt after `failures` consecutive failures.
+    fn delay(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            max_backlog: 10_000,
+        }
+    }
+}
+
+/// Per-sink statistics for metrics.
+#[derive(Default)]
+pub struct SinkStats {
+    pub events_delivered: AtomicU64,
+    pub batches_delivered: AtomicU64,
+    pub failed_attempts: AtomicU64,
+    pub events_dropped: AtomicU64,
+    pub backlog: AtomicU64,
+}
+
+/// A serializable snapshot of one sink's stats.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SinkSnapshot {
+    pub events_delivered: u64,
+    pub batches_delivered: u64,
+    pub failed_attempts: u64,
+    pub events_dropped: u64,
+    pub backlog: u64,
+}
+
+impl SinkStats {
+    pub fn snapshot(&self) -> SinkSnapshot {
+        SinkSnapshot {
+            events_delivered: self.events_delivered.load(Ordering::Relaxed),
+            batches_delivered: self.batches_delivered.load(Ordering::Relaxed),
+            failed_attempts: self.failed_attempts.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+            backlog: self.backlog.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// One sink, its backlog, and its retry state.
+struct SinkSlot {
+    sink: Box<dyn EventSink>,
+    name: String,
+    stats: Arc<SinkStats>,
+    policy: RetryPolicy,
+    backlog: VecDeque<BankruptcyEvent>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl SinkSlot {
+    fn enqueue(&mut self, batch: &[BankruptcyEvent]) {
+        self.backlog.extend(batch.iter().cloned());
+        let overflow = self.backlog.len().saturating_sub(self.policy.max_backlog);
+        if overflow > 0 {
+            self.backlog.drain(..overflow);
+            self.stats.events_dropped.fetch_add(overflow as u64, Ordering::Relaxed);
+            error!(
+                sink = self.name.as_str(),
+                dropped = overflow,
+                "Sink backlog full — dropping the oldest events"
+            );
+        }
+        self.stats.backlog.store(self.backlog.len() as u64, Ordering::Relaxed);
+    }
+
+    /// Deliver as much of the backlog as the sink will take, unless we're
+    /// still backing off from the last failure.
+    async fn drain(&mut self, metrics: &MetricsCollector) {
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return;
+        }
+        self.retry_at = None;
+
+        while !self.backlog.is_empty() {
+            let n = self.backlog.len().min(BATCH_SIZE);
+            let chunk = &self.backlog.make_contiguous()[..n];
+
+            match self.sink.publish(chunk).await {
+                Ok(()) => {
+                    self.backlog.drain(..n);
+                    self.failures = 0;
+                    self.stats.events_delivered.fetch_add(n as u64, Ordering::Relaxed);
+                    self.stats.batches_delivered.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    self.failures += 1;
+                    let delay = self.policy.delay(self.failures);
+                    self.retry_at = Some(Instant::now() + delay);
+                    self.stats.failed_attempts.fetch_add(1, Ordering::Relaxed);
+                    metrics.add_publish_failures(n as u64);
+                    warn!(
+                        sink = self.name.as_str(),
+                        error = %e,
+                        backlog = self.backlog.len(),
+                        retry_in_ms = delay.as_millis() as u64,
+                        "Sink delivery failed — will retry"
+                    );
+                    break;
+                }
+            }
+        }
+        self.stats.backlog.store(self.backlog.len() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Delivers every batch to every sink, each with its own retry and stats.
+pub struct FanoutSink {
+    slots: Vec<SinkSlot>,
+    metrics: Arc<MetricsCollector>,
+    /// Events already counted as published in `metrics`.
+    published: u64,
+}
+
+impl FanoutSink {
+    pub fn new(sinks: Vec<Box<dyn EventSink>>, policy: RetryPolicy, metrics: Arc<MetricsCollector>) -> Self {
+        let slots = sinks
+            .into_iter()
+            .map(|sink| SinkSlot {
+                name: sink.name().to_string(),
+                sink,
+                stats: Arc::new(SinkStats::default()),
+                policy,
+                backlog: VecDeque::new(),
+                failures: 0,
+                retry_at: None,
+            })
+            .collect();
+        Self { slots, metrics, published: 0 }
+    }
+
+    /// Every sink's name and stats, in configuration order.
+    pub fn stats(&self) -> Vec<(String, Arc<SinkStats>)> {
+        self.slots
+            .iter()
+            .map(|slot| (slot.name.clone(), slot.stats.clone()))
+            .collect()
+    }
+
+    pub fn sink_names(&self) -> Vec<&str> {
+        self.slots.iter().map(|slot| slot.name.as_str()).collect()
+    }
+
+    /// Count as published what the sink that has delivered the most has
+    /// delivered, so an event two sinks took counts once and an event no
+    /// sink took yet doesn't count at all.
+    fn report_published(&mut self) {
+        let delivered = self
+            .slots
+            .iter()
+            .map(|slot| slot.stats.events_delivered.load(Ordering::Relaxed))
+            .max()
+            .unwrap_or(0);
+        if delivered > self.published {
+            self.metrics.add_published(delivered - self.published);
+            self.published = delivered;
+        }
+    }
+}
+
+impl EventSink for FanoutSink {
+    fn name(&self) -> &str {
+        "fanout"
+    }
+
+    fn start<'a>(&'a mut self, shutdown: &'a watch::Receiver<bool>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            for slot in &mut self.slots {
+                slot.sink.start(shutdown).await?;
+                info!(sink = slot.name.as_str(), "📤 Sink online");
+            }
+            Ok(())
+        })
+    }
+
+    /// Queue the batch on every sink and let each deliver what it can.
+    /// Failures stay in that sink's backlog, so this never fails.
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let metrics = &*self.metrics;
+            join_all(self.slots.iter_mut().map(|slot| async move {
+                slot.enqueue(batch);
+                slot.drain(metrics).await;
+            }))
+            .await;
+            self.report_published();
+            Ok(())
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let metrics = &*self.metrics;
+            join_all(self.slots.iter_mut().map(|slot| async move {
+                slot.drain(metrics).await;
+                if let Err(e) = slot.sink.flush().await {
+                    warn!(sink = slot.name.as_str(), error = %e, "Sink flush failed");
+                }
+            }))
+            .await;
+            self.report_published();
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+    use std::sync::atomic::AtomicBool;
+
+    struct CountingSink {
+        up: Arc<AtomicBool>,
+        received: Arc<AtomicU64>,
+    }
+
+    impl EventSink for CountingSink {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                if !self.up.load(std::sync::atomic::Ordering::SeqCst) {
+                    anyhow::bail!("sink is down");
+                }
+                self.received.fetch_add(batch.len() as u64, Ordering::SeqCst);
+                Ok(())
+            })
+        }
+    }
+
+    fn counting(up: bool) -> (Box<dyn EventSink>, Arc<AtomicBool>, Arc<AtomicU64>) {
+        let up = Arc::new(AtomicBool::new(up));
+        let received = Arc::new(AtomicU64::new(0));
+        let sink = CountingSink { up: up.clone(), received: received.clone() };
+        (Box::new(sink), up, received)
+    }
+
+    fn batch(n: usize) -> Vec<BankruptcyEvent> {
+        (0..n)
+            .map(|i| BankruptcyEvent::new(format!("Carrier {}", i), Source::Fmcsa, 0.9))
+            .collect()
+    }
+
+    fn immediate_retry() -> RetryPolicy {
+        RetryPolicy { base_delay: Duration::ZERO, max_delay: Duration::ZERO, max_backlog: 100 }
+    }
+
+    #[tokio::test]
+    async fn test_failing_sink_does_not_block_the_others() {
+        let (healthy, _, healthy_received) = counting(true);
+        let (flaky, flaky_up, flaky_received) = counting(false);
+        let mut fanout = FanoutSink::new(vec![healthy, flaky], immediate_retry(), Arc::new(MetricsCollector::new()));
+
+        fanout.publish(&batch(3)).await.unwrap();
+        assert_eq!(healthy_received.load(Ordering::SeqCst), 3);
+        assert_eq!(flaky_received.load(Ordering::SeqCst), 0);
+
+        let stats = fanout.stats();
+        assert_eq!(stats[1].1.snapshot().backlog, 3);
+        assert_eq!(stats[1].1.snapshot().failed_attempts, 1);
+
+        flaky_up.store(true, std::sync::atomic::Ordering::SeqCst);
+        fanout.flush().await.unwrap();
+        assert_eq!(flaky_received.load(Ordering::SeqCst), 3);
+        assert_eq!(stats[1].1.snapshot().backlog, 0);
+    }
+
+    #[tokio::test]
+    async fn test_backlog_cap_drops_oldest() {
+        let (flaky, _, _) = counting(false);
+        let policy = RetryPolicy { max_backlog: 5, ..immediate_retry() };
+        let mut fanout = FanoutSink::new(vec![flaky], policy, Arc::new(MetricsCollector::new()));
+
+        fanout.publish(&batch(8)).await.unwrap();
+        let snapshot = fanout.stats()[0].1.snapshot();
+        assert_eq!(snapshot.backlog, 5);
+        assert_eq!(snapshot.events_dropped, 3);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_only_what_a_sink_took() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let (first, first_up, _) = counting(false);
+        let (second, second_up, _) = counting(false);
+        let mut fanout = FanoutSink::new(vec![first, second], immediate_retry(), metrics.clone());
+
+        fanout.publish(&batch(3)).await.unwrap();
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_events_published, 0);
+        assert_eq!(snapshot.redis_publish_failures, 6);
+
+        // The retry reaches the first sink; the second is still down.
+        first_up.store(true, std::sync::atomic::Ordering::SeqCst);
+        fanout.flush().await.unwrap();
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_events_published, 3);
+        assert_eq!(snapshot.redis_publish_failures, 9);
+
+        // The second sink catching up is the same three events, not three more.
+        second_up.store(true, std::sync::atomic::Ordering::SeqCst);
+        fanout.publish(&batch(2)).await.unwrap();
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_events_published, 5);
+        assert_eq!(snapshot.redis_publish_failures, 9);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            max_backlog: 10,
+        };
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(4), Duration::from_secs(5));
+        assert_eq!(policy.delay(40), Duration::from_secs(5));
+    }
+}
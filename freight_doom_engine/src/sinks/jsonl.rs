@@ -0,0 +1,220 @@
+// =============================================================================
+// sinks/jsonl.rs — THE LEDGER
+// =============================================================================
+//
+// For teams without Redis: every event appended to a JSON Lines file, one
+// object per line, ready for `tail -f`, `jq`, Spark, or whatever your data
+// team is calling "the lake" this quarter.
+//
+// Files are named events-YYYY-MM-DD.NNNN.jsonl. A new file starts at UTC
+// midnight or when the current one reaches `max_bytes`, whichever comes
+// first. Only the newest `max_files` are kept (0 keeps them all), because
+// disks are finite even if corporate distress is not.
+// =============================================================================
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use futures::future::BoxFuture;
+use tracing::{info, warn};
+
+use super::stdout::to_json_lines;
+use crate::models::BankruptcyEvent;
+use crate::publisher::EventSink;
+
+const FILE_PREFIX: &str = "events-";
+const FILE_SUFFIX: &str = ".jsonl";
+
+/// The file currently being appended to.
+struct ActiveFile {
+    file: File,
+    date: NaiveDate,
+    seq: u32,
+    len: u64,
+}
+
+/// Appends events to rotating JSON Lines files in a directory.
+pub struct JsonlSink {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    active: Option<ActiveFile>,
+}
+
+impl JsonlSink {
+    /// Create the sink, creating `dir` if needed. The first file is opened
+    /// on the first publish.
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("creating JSONL sink directory {}", dir.display()))?;
+        Ok(Self {
+            dir,
+            max_bytes,
+            max_files,
+            active: None,
+        })
+    }
+
+    /// Make sure `active` is a file for today with room left in it.
+    fn ensure_active(&mut self, today: NaiveDate) -> Result<&mut ActiveFile> {
+        let needs_new = match &self.active {
+            Some(active) => active.date != today || active.len >= self.max_bytes,
+            None => true,
+        };
+
+        if needs_new {
+            let seq = match &self.active {
+                Some(active) if active.date == today => active.seq + 1,
+                // Starting up, or a new day: carry on from the newest file
+                // for today if there's room, so restarts don't litter.
+                _ => match latest_seq(&self.dir, today)? {
+                    Some((seq, len)) if len < self.max_bytes => seq,
+                    Some((seq, _)) => seq + 1,
+                    None => 0,
+                },
+            };
+
+            let path = file_path(&self.dir, today, seq);
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let len = file.metadata()?.len();
+            info!(path = %path.display(), "JSONL sink: writing to new file");
+            self.active = Some(ActiveFile { file, date: today, seq, len });
+            self.prune()?;
+        }
+
+        Ok(self.active.as_mut().expect("active file was just set"))
+    }
+
+    /// Delete the oldest files beyond `max_files`.
+    fn prune(&self) -> Result<()> {
+        if self.max_files == 0 {
+            return Ok(());
+        }
+        let files = list_files(&self.dir)?;
+        for path in files.iter().take(files.len().saturating_sub(self.max_files)) {
+            if let Err(e) = fs::remove_file(path) {
+                warn!(path = %path.display(), error = %e, "JSONL sink: failed to remove old file");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn file_path(dir: &Path, date: NaiveDate, seq: u32) -> PathBuf {
+    dir.join(format!("{}{}.{:04}{}", FILE_PREFIX, date.format("%Y-%m-%d"), seq, FILE_SUFFIX))
+}
+
+/// Every events file in `dir`, oldest first. Zero-padded names sort by
+/// date, then sequence.
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(FILE_PREFIX) && n.ends_with(FILE_SUFFIX))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The highest sequence number already used for `date`, and that file's size.
+fn latest_seq(dir: &Path, date: NaiveDate) -> Result<Option<(u32, u64)>> {
+    let prefix = format!("{}{}.", FILE_PREFIX, date.format("%Y-%m-%d"));
+    let mut latest = None;
+    for path in list_files(dir)? {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let seq = name
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(FILE_SUFFIX))
+            .and_then(|seq| seq.parse::<u32>().ok());
+        if let Some(seq) = seq {
+            let len = fs::metadata(&path)?.len();
+            if latest.is_none_or(|(best, _)| seq > best) {
+                latest = Some((seq, len));
+            }
+        }
+    }
+    Ok(latest)
+}
+
+impl EventSink for JsonlSink {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let buf = to_json_lines(batch)?;
+            let active = self.ensure_active(Utc::now().date_naive())?;
+            active.file.write_all(&buf)?;
+            active.file.flush()?;
+            active.len += buf.len() as u64;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("freight-doom-jsonl-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn event(name: &str) -> BankruptcyEvent {
+        BankruptcyEvent::new(name.to_string(), Source::CourtListener, 0.8)
+    }
+
+    #[tokio::test]
+    async fn test_writes_one_json_object_per_line() {
+        let dir = temp_dir();
+        let mut sink = JsonlSink::open(&dir, 1024 * 1024, 0).unwrap();
+        sink.publish(&[event("Alpha Freight"), event("Beta Trucking")]).await.unwrap();
+
+        let files = list_files(&dir).unwrap();
+        assert_eq!(files.len(), 1);
+        let text = fs::read_to_string(&files[0]).unwrap();
+        let names: Vec<String> = text
+            .lines()
+            .map(|line| serde_json::from_str::<BankruptcyEvent>(line).unwrap().company_name)
+            .collect();
+        assert_eq!(names, vec!["Alpha Freight", "Beta Trucking"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rotates_by_size_and_keeps_newest_files() {
+        let dir = temp_dir();
+        // Every batch overflows a 1-byte file, so each one gets a new file.
+        let mut sink = JsonlSink::open(&dir, 1, 2).unwrap();
+        for name in ["One", "Two", "Three"] {
+            sink.publish(&[event(name)]).await.unwrap();
+        }
+
+        let files = list_files(&dir).unwrap();
+        assert_eq!(files.len(), 2);
+        let newest = fs::read_to_string(files.last().unwrap()).unwrap();
+        assert!(newest.contains("\"Three\""));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart_continues_latest_file() {
+        let dir = temp_dir();
+        JsonlSink::open(&dir, 1024 * 1024, 0).unwrap().publish(&[event("Before")]).await.unwrap();
+        JsonlSink::open(&dir, 1024 * 1024, 0).unwrap().publish(&[event("After")]).await.unwrap();
+
+        let files = list_files(&dir).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(fs::read_to_string(&files[0]).unwrap().lines().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
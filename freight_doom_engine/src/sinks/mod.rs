@@ -0,0 +1,120 @@
+// =============================================================================
+// sinks/mod.rs — THE LOADING DOCKS
+// =============================================================================
+//
+// Redis used to be the only way out of the engine. If your team didn't run
+// Redis, your team didn't get bankruptcies. That's gatekeeping, and we're
+// against it (except for the gatekeeping we do on confidence scores).
+//
+// Now the publisher stage fans every batch out to as many sinks as you like:
+//
+// - `redis`         — pub/sub + sorted set, the original (publisher.rs)
+// - `redis_stream`  — XADD to a stream with consumer groups (publisher.rs)
+// - `jsonl`         — rotating JSON Lines files on disk (jsonl.rs)
+// - `webhook`       — HTTP POST with an HMAC-SHA256 signature (webhook.rs)
+// - `stdout`        — one JSON object per line, pipe it wherever (stdout.rs)
+//
+// Pick them with FREIGHT_DOOM_SINKS=redis,jsonl,webhook. Every sink gets its
+// own retry backlog, its own backoff, and its own stats (fanout.rs), so a
+// webhook that's having a bad afternoon doesn't hold up Redis. The Redis
+// sinks also sit behind their own durable outbox when one is configured.
+// =============================================================================
+
+pub mod fanout;
+pub mod jsonl;
+pub mod stdout;
+pub mod webhook;
+
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::metrics::MetricsCollector;
+use crate::outbox::{Outbox, OutboxSink};
+use crate::publisher::{EventSink, RedisPublisher, RedisStreamSink};
+
+pub use fanout::{FanoutSink, RetryPolicy, SinkSnapshot, SinkStats};
+pub use jsonl::JsonlSink;
+pub use stdout::StdoutSink;
+pub use webhook::WebhookSink;
+
+/// Build the sinks named in `Config::sinks`, registering whatever stats
+/// they keep with `metrics`.
+pub fn from_config(config: &Arc<Config>, metrics: &MetricsCollector) -> Result<Vec<Box<dyn EventSink>>> {
+    let mut sinks = Vec::new();
+    for name in &config.sinks {
+        let sink: Box<dyn EventSink> = match name.to_ascii_lowercase().as_str() {
+            "redis" => {
+                let (sink, stats) = RedisPublisher::new(config.clone());
+                metrics.register_publisher("redis", stats);
+                with_outbox(config, metrics, "redis", Box::new(sink))?
+            }
+            "redis_stream" => {
+                let (sink, stats) = RedisStreamSink::new(config.clone());
+                metrics.register_publisher("redis_stream", stats);
+                with_outbox(config, metrics, "redis_stream", Box::new(sink))?
+            }
+            "jsonl" => Box::new(JsonlSink::open(
+                &config.jsonl_dir,
+                config.jsonl_max_bytes,
+                config.jsonl_max_files,
+            )?),
+            "webhook" => Box::new(WebhookSink::from_config(config)?),
+            "stdout" => Box::new(StdoutSink::new()),
+            other => bail!(
+                "unknown sink {:?} in FREIGHT_DOOM_SINKS (expected redis, redis_stream, jsonl, webhook, or stdout)",
+                other
+            ),
+        };
+        sinks.push(sink);
+    }
+    Ok(sinks)
+}
+
+/// Put a sink behind its own outbox in `<outbox_dir>/<name>`, if the outbox
+/// is enabled.
+fn with_outbox(
+    config: &Config,
+    metrics: &MetricsCollector,
+    name: &str,
+    sink: Box<dyn EventSink>,
+) -> Result<Box<dyn EventSink>> {
+    match &config.outbox_dir {
+        Some(dir) => {
+            let outbox = Outbox::open(dir.join(name), config.outbox_segment_bytes)?;
+            metrics.register_outbox(name, outbox.stats());
+            Ok(Box::new(OutboxSink::boxed(outbox, sink, config.outbox_retry_interval)))
+        }
+        None => Ok(sink),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config_builds_named_sinks() {
+        let mut config = Config::from_env();
+        config.sinks = vec!["redis".into(), "redis_stream".into(), "stdout".into()];
+        config.outbox_dir = None;
+        let metrics = MetricsCollector::new();
+
+        let sinks = from_config(&Arc::new(config), &metrics).unwrap();
+        let names: Vec<&str> = sinks.iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["redis", "redis_stream", "stdout"]);
+        let publishers: Vec<String> = metrics.snapshot().publishers.into_keys().collect();
+        assert_eq!(publishers, vec!["redis", "redis_stream"]);
+    }
+
+    #[test]
+    fn test_from_config_rejects_unknown_sink() {
+        let mut config = Config::from_env();
+        config.sinks = vec!["carrier_pigeon".into()];
+        let metrics = MetricsCollector::new();
+
+        let err = from_config(&Arc::new(config), &metrics).err().unwrap();
+        assert!(err.to_string().contains("carrier_pigeon"));
+    }
+}
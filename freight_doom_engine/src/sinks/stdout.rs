@@ -0,0 +1,52 @@
+// =============================================================================
+// sinks/stdout.rs — THE TOWN CRIER
+// =============================================================================
+//
+// One JSON object per line on stdout. Pipe it into jq, into Vector, into a
+// shell script held together with hope. When this sink is enabled, the
+// engine sends its logs to stderr so the two don't end up in the same pipe.
+// =============================================================================
+
+use std::io::Write;
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+
+use crate::models::BankruptcyEvent;
+use crate::publisher::EventSink;
+
+/// Writes each event to stdout as a JSON line.
+#[derive(Debug, Default)]
+pub struct StdoutSink;
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Serialize a batch as JSON Lines.
+pub(crate) fn to_json_lines(batch: &[BankruptcyEvent]) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    for event in batch {
+        serde_json::to_writer(&mut buf, event)?;
+        buf.push(b'\n');
+    }
+    Ok(buf)
+}
+
+impl EventSink for StdoutSink {
+    fn name(&self) -> &str {
+        "stdout"
+    }
+
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let buf = to_json_lines(batch)?;
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&buf)?;
+            stdout.flush()?;
+            Ok(())
+        })
+    }
+}
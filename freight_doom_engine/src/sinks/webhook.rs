@@ -0,0 +1,144 @@
+// =============================================================================
+// sinks/webhook.rs — THE SINGING TELEGRAM
+// =============================================================================
+//
+// POSTs each batch as JSON to a URL of your choosing:
+//
+//   POST <FREIGHT_DOOM_WEBHOOK_URL>
+//   Content-Type: application/json
+//   X-Freight-Doom-Timestamp: 1767225600
+//   X-Freight-Doom-Signature: sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">
+//
+//   {"schema_version": 1, "events": [ ... ]}
+//
+// The receiver recomputes the HMAC with the shared secret and compares.
+// The timestamp is part of the signed payload, so receivers can reject old
+// requests and nobody gets to replay last Tuesday's bankruptcies at you.
+// Anything other than a 2xx counts as a failure and gets retried.
+// =============================================================================
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use futures::future::BoxFuture;
+use ring::hmac;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::models::BankruptcyEvent;
+use crate::publisher::{EventSink, EVENT_SCHEMA_VERSION};
+
+pub const SIGNATURE_HEADER: &str = "X-Freight-Doom-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Freight-Doom-Timestamp";
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    schema_version: u32,
+    events: &'a [BankruptcyEvent],
+}
+
+/// POSTs batches of events to an HTTP endpoint, signed with HMAC-SHA256.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    key: hmac::Key,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>, secret: &[u8], timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent("FreightDoomEngine/1.0 (webhook-sink)")
+            .build()?;
+        Ok(Self {
+            client,
+            url: url.into(),
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+        })
+    }
+
+    /// Build from `FREIGHT_DOOM_WEBHOOK_URL` and `FREIGHT_DOOM_WEBHOOK_SECRET`.
+    /// Both are required — an unsigned webhook is just a very polite DDoS.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let url = config
+            .webhook_url
+            .as_deref()
+            .context("webhook sink enabled but FREIGHT_DOOM_WEBHOOK_URL is not set")?;
+        let secret = config
+            .webhook_secret
+            .as_deref()
+            .context("webhook sink enabled but FREIGHT_DOOM_WEBHOOK_SECRET is not set")?;
+        Self::new(url, secret.as_bytes(), config.webhook_timeout)
+    }
+
+    /// The signature header value for `body` sent at `timestamp`.
+    pub fn sign(&self, timestamp: i64, body: &[u8]) -> String {
+        let mut message = format!("{}.", timestamp).into_bytes();
+        message.extend_from_slice(body);
+        let tag = hmac::sign(&self.key, &message);
+        format!("sha256={}", hex(tag.as_ref()))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl EventSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn publish<'a>(&'a mut self, batch: &'a [BankruptcyEvent]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(&WebhookPayload {
+                schema_version: EVENT_SCHEMA_VERSION,
+                events: batch,
+            })?;
+            let timestamp = chrono::Utc::now().timestamp();
+
+            let response = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, self.sign(timestamp, &body))
+                .body(body)
+                .send()
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                bail!("webhook returned HTTP {}", status);
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_matches_reference_hmac() {
+        let sink = WebhookSink::new("http://localhost/hook", b"shh", Duration::from_secs(1)).unwrap();
+        let signature = sink.sign(1_700_000_000, br#"{"events":[]}"#);
+
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"shh");
+        let expected = hmac::sign(&key, br#"1700000000.{"events":[]}"#);
+        assert_eq!(signature, format!("sha256={}", hex(expected.as_ref())));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+    }
+
+    #[test]
+    fn test_from_config_requires_url_and_secret() {
+        let mut config = Config::from_env();
+        config.webhook_url = Some("http://localhost/hook".into());
+        config.webhook_secret = None;
+        assert!(WebhookSink::from_config(&config).is_err());
+
+        config.webhook_secret = Some("shh".into());
+        assert!(WebhookSink::from_config(&config).is_ok());
+    }
+}
@@ -0,0 +1,342 @@
+// =============================================================================
+// text_scanner/classifier.rs — THE ENGINE FINALLY LISTENS TO THE ANALYSTS
+// =============================================================================
+//
+// Every morning somebody opens the dashboard and marks yesterday's events
+// "confirmed" or "false alarm". Until now that was a one-way conversation:
+// the engine flagged the same frozen-pizza distributor the next day, and the
+// day after.
+//
+// This is a naive Bayes relevance model over words and word pairs, trained
+// offline by `freight_doom_train` from a JSONL file of labelled texts:
+//
+//   {"text": "Acme Trucking filed Chapter 11 ...", "label": true}
+//   {"text": "Claim filed by Acme Trucking ...", "label": "false_alarm"}
+//
+// (`label` is a boolean or a dashboard status: confirmed and resolved are
+// relevant, false_alarm isn't, anything else is skipped.)
+//
+// It's binarised multinomial naive Bayes — each n-gram counts once per text
+// — with Laplace smoothing, which is about as lightweight as learning gets:
+// one pass over the corpus, a log-odds weight per n-gram, and a JSON model
+// file a human can open and read. At scan time the model's probability is
+// blended with the keyword heuristics (`model_weight` in [scoring]), and
+// the n-grams that swayed it most go into the score breakdown.
+// =============================================================================
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Model file format version.
+const MODEL_VERSION: u32 = 1;
+
+/// How many n-grams a prediction names as its evidence.
+const EVIDENCE_NGRAMS: usize = 3;
+
+/// One labelled text from the training corpus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub text: String,
+    pub relevant: bool,
+}
+
+/// A corpus line as written.
+#[derive(Deserialize)]
+struct CorpusLine {
+    text: String,
+    label: Label,
+}
+
+/// A triage label: a boolean or a dashboard status.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(super) enum Label {
+    Relevant(bool),
+    Status(String),
+}
+
+impl Label {
+    /// Relevant, not relevant, or not triaged yet.
+    pub(super) fn relevant(&self) -> Option<bool> {
+        match self {
+            Label::Relevant(relevant) => Some(*relevant),
+            Label::Status(status) => match status.to_ascii_lowercase().as_str() {
+                "confirmed" | "resolved" | "true_positive" | "relevant" => Some(true),
+                "false_alarm" | "false_positive" | "irrelevant" => Some(false),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Read a JSONL corpus, skipping blank lines and texts nobody has triaged.
+pub fn read_corpus(path: &Path) -> Result<Vec<Example>> {
+    let text = fs::read_to_string(path).with_context(|| format!("failed to read corpus {}", path.display()))?;
+    let mut examples = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line: CorpusLine = serde_json::from_str(line)
+            .with_context(|| format!("{} line {}: expected {{\"text\": ..., \"label\": ...}}", path.display(), number + 1))?;
+        if let Some(relevant) = line.label.relevant() {
+            examples.push(Example { text: line.text, relevant });
+        }
+    }
+    Ok(examples)
+}
+
+/// Training knobs.
+#[derive(Debug, Clone)]
+pub struct TrainOptions {
+    /// Longest word run used as a feature: 1 is words only, 2 adds pairs.
+    pub ngrams: usize,
+    /// N-grams in fewer texts than this are left out of the model.
+    pub min_count: usize,
+    /// Laplace smoothing added to every count.
+    pub smoothing: f64,
+}
+
+impl Default for TrainOptions {
+    fn default() -> Self {
+        Self { ngrams: 2, min_count: 2, smoothing: 1.0 }
+    }
+}
+
+/// A trained relevance model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelevanceModel {
+    version: u32,
+    pub ngrams: usize,
+    /// Texts the model was trained on, relevant and not.
+    pub relevant_examples: usize,
+    pub irrelevant_examples: usize,
+    /// Log odds of relevance before reading a word.
+    prior: f64,
+    /// Log odds each n-gram adds when a text contains it.
+    weights: BTreeMap<String, f64>,
+}
+
+/// What the model thinks of a text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    /// Probability the text is about a freight bankruptcy.
+    pub probability: f64,
+    /// The n-grams that pushed hardest toward the answer, strongest first.
+    pub evidence: Vec<String>,
+}
+
+impl RelevanceModel {
+    /// Train on `examples`. Needs at least one of each label.
+    pub fn train(examples: &[Example], options: &TrainOptions) -> Result<Self> {
+        let ngrams = options.ngrams.max(1);
+        let relevant_examples = examples.iter().filter(|e| e.relevant).count();
+        let irrelevant_examples = examples.len() - relevant_examples;
+        if relevant_examples == 0 || irrelevant_examples == 0 {
+            bail!(
+                "need both relevant and irrelevant examples to train (got {} and {})",
+                relevant_examples,
+                irrelevant_examples
+            );
+        }
+
+        // Texts containing each n-gram: [irrelevant, relevant]
+        let mut counts: HashMap<String, [usize; 2]> = HashMap::new();
+        for example in examples {
+            for gram in features(&example.text, ngrams) {
+                counts.entry(gram).or_default()[example.relevant as usize] += 1;
+            }
+        }
+        counts.retain(|_, [no, yes]| *no + *yes >= options.min_count.max(1));
+
+        let alpha = options.smoothing;
+        let vocabulary = counts.len() as f64;
+        let total = |class: usize| counts.values().map(|c| c[class]).sum::<usize>() as f64 + alpha * vocabulary;
+        let (total_no, total_yes) = (total(0), total(1));
+
+        let weights = counts
+            .into_iter()
+            .map(|(gram, [no, yes])| {
+                let weight = ((yes as f64 + alpha) / total_yes).ln() - ((no as f64 + alpha) / total_no).ln();
+                (gram, weight)
+            })
+            .collect();
+
+        Ok(Self {
+            version: MODEL_VERSION,
+            ngrams,
+            relevant_examples,
+            irrelevant_examples,
+            prior: (relevant_examples as f64 / irrelevant_examples as f64).ln(),
+            weights,
+        })
+    }
+
+    /// Number of n-grams the model knows.
+    pub fn vocabulary(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn predict(&self, text: &str) -> Prediction {
+        let mut found: Vec<(&str, f64)> = features(text, self.ngrams)
+            .iter()
+            .filter_map(|gram| self.weights.get_key_value(gram.as_str()))
+            .map(|(gram, weight)| (gram.as_str(), *weight))
+            .collect();
+        let log_odds = self.prior + found.iter().map(|(_, weight)| weight).sum::<f64>();
+        let probability = 1.0 / (1.0 + (-log_odds).exp());
+
+        // Evidence for the side the model came down on.
+        let sign = if probability >= 0.5 { 1.0 } else { -1.0 };
+        found.retain(|(_, weight)| weight * sign > 0.0);
+        found.sort_by(|a, b| (b.1 * sign).total_cmp(&(a.1 * sign)).then(a.0.cmp(b.0)));
+        let evidence = found.iter().take(EVIDENCE_NGRAMS).map(|(gram, _)| gram.to_string()).collect();
+
+        Prediction { probability, evidence }
+    }
+
+    /// Write the model to `path` via a temp file and a rename.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("creating model directory {}", dir.display()))?;
+        }
+        let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+        let mut file = File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("failed to read relevance model {}", path.display()))?;
+        let model: RelevanceModel = serde_json::from_slice(&bytes)
+            .with_context(|| format!("{} is not a relevance model", path.display()))?;
+        if model.version != MODEL_VERSION {
+            bail!(
+                "relevance model {} is version {} (expected {}); retrain it",
+                path.display(),
+                model.version,
+                MODEL_VERSION
+            );
+        }
+        Ok(model)
+    }
+}
+
+/// The distinct n-grams of `text`: lowercase runs of letters and digits,
+/// and every run of up to `ngrams` of them joined by spaces.
+fn features(text: &str, ngrams: usize) -> HashSet<String> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut grams = HashSet::new();
+    for n in 1..=ngrams {
+        for window in words.windows(n) {
+            grams.insert(window.join(" "));
+        }
+    }
+    grams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<Example> {
+        let relevant = [
+            "Acme Trucking LLC filed a voluntary Chapter 11 petition",
+            "Motor carrier Big Rig Inc filed Chapter 7 and ceased operations",
+            "Freight broker Load Masters filed for Chapter 11 bankruptcy protection",
+            "Reefer carrier Cold Chain LLC filed a Chapter 11 petition in Delaware",
+        ];
+        let irrelevant = [
+            "Proof of claim filed by Acme Trucking in the Chapter 11 case of ABC Retail",
+            "Acme Trucking denies rumors and is not filing for bankruptcy",
+            "Creditor Big Rig Inc filed a proof of claim against Pizza Co",
+            "Load Masters named to top freight broker list, denies bankruptcy rumors",
+        ];
+        relevant
+            .iter()
+            .map(|text| Example { text: text.to_string(), relevant: true })
+            .chain(irrelevant.iter().map(|text| Example { text: text.to_string(), relevant: false }))
+            .collect()
+    }
+
+    #[test]
+    fn test_model_learns_from_labels() {
+        let model = RelevanceModel::train(&corpus(), &TrainOptions::default()).unwrap();
+        assert_eq!((model.relevant_examples, model.irrelevant_examples), (4, 4));
+
+        let filing = model.predict("Hotshot Haulers LLC filed a voluntary Chapter 11 petition");
+        let claim = model.predict("Proof of claim filed by Hotshot Haulers in the Chapter 11 case");
+        assert!(filing.probability > 0.5, "{:?}", filing);
+        assert!(claim.probability < 0.5, "{:?}", claim);
+        assert!(claim.evidence.iter().any(|gram| gram.contains("claim")), "{:?}", claim.evidence);
+    }
+
+    #[test]
+    fn test_training_needs_both_labels() {
+        let only_yes: Vec<Example> = corpus().into_iter().filter(|e| e.relevant).collect();
+        assert!(RelevanceModel::train(&only_yes, &TrainOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_model_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("freight-doom-model-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("relevance.json");
+        let model = RelevanceModel::train(&corpus(), &TrainOptions::default()).unwrap();
+        model.save(&path).unwrap();
+        let loaded = RelevanceModel::load(&path).unwrap();
+        assert_eq!(loaded.vocabulary(), model.vocabulary());
+        for (gram, weight) in &model.weights {
+            assert!((loaded.weights[gram] - weight).abs() < 1e-12, "{}", gram);
+        }
+        let text = "Acme Trucking filed a Chapter 11 petition";
+        assert!((loaded.predict(text).probability - model.predict(text).probability).abs() < 1e-9);
+
+        fs::write(&path, "{\"weights\": {}}").unwrap();
+        assert!(RelevanceModel::load(&path).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_corpus_labels_accept_dashboard_statuses() {
+        let dir = std::env::temp_dir().join(format!("freight-doom-corpus-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("corpus.jsonl");
+        fs::write(
+            &path,
+            concat!(
+                "{\"text\": \"a\", \"label\": true}\n",
+                "\n",
+                "{\"text\": \"b\", \"label\": \"confirmed\"}\n",
+                "{\"text\": \"c\", \"label\": \"false_alarm\"}\n",
+                "{\"text\": \"d\", \"label\": \"investigating\"}\n",
+            ),
+        )
+        .unwrap();
+        let labels: Vec<(String, bool)> = read_corpus(&path)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.text, e.relevant))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![("a".into(), true), ("b".into(), true), ("c".into(), false)]
+        );
+
+        fs::write(&path, "{\"text\": \"a\"}\n").unwrap();
+        let error = format!("{:#}", read_corpus(&path).unwrap_err());
+        assert!(error.contains("line 1"), "{}", error);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,166 @@
+// =============================================================================
+// text_scanner/company.rs — WHAT KIND OF COMPANY IS DYING, EXACTLY
+// =============================================================================
+//
+// Company classification used to pick exactly one of four types by raw hit
+// count, and settle every tie in favour of Carrier. Half the asset-based
+// carriers in the country run a brokerage on the side; all of them came out
+// as plain carriers, and the brokerage arm's shippers never found out.
+//
+// Now each company type in the dictionary is scored on its own, from the
+// weight of its keywords in the text (`class_half_weight` in [scoring]),
+// and `classify` returns every type that scores at least `class_min_score`,
+// most likely first. Equal scores go to the type the text mentions first.
+//
+// Some scanners know better than any keyword list: FMCSA says outright
+// whether a company holds broker authority. They put what they know on the
+// event as priors, and `combine` folds the text's opinion in on top as
+// independent evidence — a noisy-OR, the same way two sources reporting
+// one case raise its confidence.
+// =============================================================================
+
+use crate::models::{ClassScore, CompanyClassification};
+
+use super::Keywords;
+
+/// Every company type the text suggests, most likely first.
+pub fn classify(keywords: &Keywords, text: &str) -> Vec<ClassScore> {
+    let mut found: Vec<(usize, ClassScore)> = keywords
+        .classes
+        .iter()
+        .filter_map(|(classification, set)| {
+            let matches = set.find(text);
+            let first = matches.first()?.start;
+            let score = keywords.model.class_score(matches.iter().map(|m| m.weight).sum());
+            if score < keywords.model.class_min_score {
+                return None;
+            }
+            let mut evidence: Vec<String> = Vec::new();
+            for m in &matches {
+                if !evidence.contains(&m.keyword) {
+                    evidence.push(m.keyword.clone());
+                }
+            }
+            Some((first, ClassScore { classification: classification.clone(), score, evidence }))
+        })
+        .collect();
+    found.sort_by_key(|(first, _)| *first);
+
+    let mut classes: Vec<ClassScore> = found.into_iter().map(|(_, class)| class).collect();
+    rank(&mut classes);
+    classes
+}
+
+/// The headline classification: the most likely type, or Unclassified.
+pub fn primary(classes: &[ClassScore]) -> CompanyClassification {
+    classes
+        .first()
+        .map_or(CompanyClassification::Unclassified, |class| class.classification.clone())
+}
+
+/// `priors` and `found` as one ranked list. A type in both gets
+/// 1 - (1 - prior)(1 - found) and the evidence of each; on equal scores the
+/// priors come first.
+pub fn combine(priors: &[ClassScore], found: &[ClassScore]) -> Vec<ClassScore> {
+    let mut combined: Vec<ClassScore> = Vec::new();
+    for class in priors.iter().chain(found) {
+        let score = class.score.clamp(0.0, 1.0);
+        match combined.iter_mut().find(|c| c.classification == class.classification) {
+            Some(existing) => {
+                existing.score = 1.0 - (1.0 - existing.score) * (1.0 - score);
+                for evidence in &class.evidence {
+                    if !existing.evidence.contains(evidence) {
+                        existing.evidence.push(evidence.clone());
+                    }
+                }
+            }
+            None => combined.push(ClassScore { score, ..class.clone() }),
+        }
+    }
+    rank(&mut combined);
+    combined
+}
+
+/// Highest score first. The sort is stable, so ties keep their order.
+fn rank(classes: &mut [ClassScore]) {
+    classes.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CompanyClassification::*;
+
+    fn labels(classes: &[ClassScore]) -> Vec<CompanyClassification> {
+        classes.iter().map(|class| class.classification.clone()).collect()
+    }
+
+    #[test]
+    fn test_each_type_is_recognised() {
+        // (text, the type it should lead with)
+        let cases = [
+            ("XYZ Trucking, a motor carrier with a fleet of 200 tractors", Carrier),
+            ("Fast Lane, a freight broker with broker authority and a brokerage desk", Broker),
+            ("Central States, a less-than-truckload carrier with a terminal network of 40 service centers", Ltl),
+            ("Prairie Haul ran dry van and flatbed truckload lanes over the road", Truckload),
+            ("Harbor Dray, a drayage company pulling chassis from the marine terminal", Drayage),
+            ("an intermodal marketing company moving double stack boxes off the rail ramp", Intermodal),
+            ("a final mile home delivery contractor and delivery service partner", LastMile),
+            ("a digital freight marketplace doing load matching for shippers", FreightTech),
+            ("a frozen food manufacturer that shut its factory", Shipper),
+            ("Global Cargo, a freight forwarder and NVOCC handling ocean freight", FreightForwarder),
+        ];
+        for (text, expected) in cases {
+            let classes = classify(&Keywords::builtin(), text);
+            assert_eq!(primary(&classes), expected, "{:?} → {:?}", text, classes);
+        }
+    }
+
+    #[test]
+    fn test_carrier_with_a_brokerage_arm_gets_both() {
+        let text = "XYZ Trucking, a motor carrier with a fleet of tractors and a driver pool, \
+                    also ran a freight brokerage with its own broker authority";
+        let classes = classify(&Keywords::builtin(), text);
+        assert_eq!(labels(&classes)[..2], [Carrier, Broker]);
+        assert!(classes[0].score > classes[1].score);
+        assert!(classes[1].score >= 0.5, "{:?}", classes);
+        assert_eq!(classes[1].evidence, vec!["brokerage".to_string(), "broker authority".to_string()]);
+    }
+
+    #[test]
+    fn test_ties_go_to_the_first_mentioned() {
+        let keywords = Keywords::builtin();
+        let broker_first = classify(&keywords, "a freight broker that became a motor carrier");
+        let carrier_first = classify(&keywords, "a motor carrier that became a freight broker");
+        assert_eq!(broker_first[0].score, broker_first[1].score);
+        assert_eq!(labels(&broker_first), vec![Broker, Carrier]);
+        assert_eq!(labels(&carrier_first), vec![Carrier, Broker]);
+    }
+
+    #[test]
+    fn test_weak_hints_are_not_labels() {
+        // "port" and "shipper" are worth half a keyword each: not enough.
+        let classes = classify(&Keywords::builtin(), "the shipper's goods sat in port");
+        assert!(classes.is_empty(), "{:?}", classes);
+        assert_eq!(primary(&classes), Unclassified);
+    }
+
+    #[test]
+    fn test_priors_combine_with_the_text() {
+        let prior = |classification, score: f64, evidence: &str| ClassScore {
+            classification,
+            score,
+            evidence: vec![evidence.to_string()],
+        };
+        let priors = vec![prior(Broker, 0.95, "broker authority")];
+        let found = vec![prior(Carrier, 0.75, "motor carrier"), prior(Broker, 0.5, "brokerage")];
+
+        let combined = combine(&priors, &found);
+        assert_eq!(labels(&combined), vec![Broker, Carrier]);
+        assert!((combined[0].score - 0.975).abs() < 1e-9);
+        assert_eq!(combined[0].evidence, vec!["broker authority".to_string(), "brokerage".to_string()]);
+        assert_eq!(combined[1].score, 0.75);
+
+        assert_eq!(combine(&[], &found), vec![found[0].clone(), found[1].clone()]);
+    }
+}
@@ -0,0 +1,387 @@
+// =============================================================================
+// text_scanner/context.rs — READING THE WORDS AROUND THE WORDS
+// =============================================================================
+//
+// Keyword counting can't tell these apart:
+//
+//   "XYZ Trucking filed for Chapter 11"
+//   "XYZ Trucking is NOT in bankruptcy and denies rumors of Chapter 11"
+//   "XYZ Trucking may file for Chapter 11 if the lender walks"
+//   "Claim filed by XYZ Trucking in the Chapter 11 case of ABC Retail"
+//
+// Only the first is a bankrupt trucking company. The second is a press
+// release, the third a rumour, and in the fourth the trucking company is
+// the one that's owed money.
+//
+// So for every keyword match we read a few words either side, within the
+// same clause, and look for cues:
+//
+// - negation ("not", "denies", "never", "without") on a bankruptcy term
+// - hypotheticals ("may", "could", "considering", "risk of") on one
+// - creditor roles ("creditor", "claim filed by", "owed to") around a
+//   freight term, versus debtor roles ("debtor")
+//
+// A bankruptcy that's only ever negated or hypothetical, or a freight
+// company that only ever shows up as a creditor, scales the confidence
+// down, and the reason goes into `ScanResult::context`.
+// =============================================================================
+
+use serde::Serialize;
+
+use super::MatchSpan;
+
+/// Words read before a keyword.
+const BEFORE_WINDOW: usize = 6;
+
+/// Words read after a keyword.
+const AFTER_WINDOW: usize = 4;
+
+/// Confidence multiplier when every bankruptcy mention is negated.
+pub const NEGATED_FACTOR: f64 = 0.2;
+
+/// Confidence multiplier when every bankruptcy mention is hypothetical.
+pub const HYPOTHETICAL_FACTOR: f64 = 0.5;
+
+/// Confidence multiplier when the freight company is only ever a creditor.
+pub const CREDITOR_FACTOR: f64 = 0.3;
+
+/// Cues before a bankruptcy term that deny it.
+const NEGATION_CUES: &[&str] = &[
+    "not", "never", "neither", "nor", "deny", "denies", "denied", "isn't", "wasn't", "hasn't",
+    "haven't", "doesn't", "didn't", "won't", "no plans", "no intention", "rather than",
+    "instead of", "avoid", "avoided", "averted", "without",
+];
+
+/// Cues after a bankruptcy term that deny it: "bankruptcy rumors are unfounded".
+const POST_NEGATION_CUES: &[&str] = &["denied", "false", "unfounded", "untrue"];
+
+/// Cues before a bankruptcy term that make it a maybe.
+const HYPOTHETICAL_CUES: &[&str] = &[
+    "may", "might", "could", "would", "should", "if", "whether", "unless", "consider",
+    "considering", "contemplate", "contemplating", "explore", "exploring", "weighing",
+    "potential", "possible", "possibly", "risk of", "threat of", "threatened", "prepare",
+    "preparing", "rumor", "rumors", "rumored", "speculation",
+];
+
+/// Cues near a freight term that make its company the one owed money. Not
+/// "proof of claim" on its own: every petition's docket entry announces the
+/// deadline for them.
+const CREDITOR_CUES: &[&str] = &[
+    "creditor", "creditors", "claimant", "claimants", "claim filed by", "owed to", "owes",
+    "payable to", "vendor", "vendors",
+];
+
+/// Cues near a freight term that make its company the one in bankruptcy.
+const DEBTOR_CUES: &[&str] = &["debtor", "debtors", "debtor in possession"];
+
+/// Words that start a new clause, so a cue on one side doesn't reach the
+/// other: "not a creditor, but the debtor filed for chapter 11".
+const CLAUSE_BREAKS: &[&str] = &["but", "however", "although", "though", "whereas"];
+
+/// Words that end in a period without ending a sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "no", "ch", "inc", "co", "corp", "llc", "ltd", "lp", "v", "vs", "u.s", "st", "jr", "bankr",
+];
+
+/// Abbreviations that end a company's name, and often the sentence with it:
+/// "Filed by Acme Trucking, Inc. Proof of Claim due by 10/1/2024." A period
+/// after one of these ends the sentence if the next word is capitalised.
+const COMPANY_SUFFIXES: &[&str] = &["inc", "co", "corp", "llc", "ltd", "lp"];
+
+/// What the context said about the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ContextKind {
+    /// Every bankruptcy mention is denied.
+    Negated,
+    /// Every bankruptcy mention is a maybe.
+    Hypothetical,
+    /// The freight company is a creditor in someone else's bankruptcy.
+    CreditorRole,
+}
+
+impl ContextKind {
+    /// The kind's name in a score breakdown and in the [scoring] table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContextKind::Negated => "negated",
+            ContextKind::Hypothetical => "hypothetical",
+            ContextKind::CreditorRole => "creditor_role",
+        }
+    }
+
+    /// What the confidence is multiplied by, unless the scoring model
+    /// says otherwise.
+    pub fn factor(&self) -> f64 {
+        match self {
+            ContextKind::Negated => NEGATED_FACTOR,
+            ContextKind::Hypothetical => HYPOTHETICAL_FACTOR,
+            ContextKind::CreditorRole => CREDITOR_FACTOR,
+        }
+    }
+}
+
+/// A confidence adjustment and the words that caused it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextAdjustment {
+    pub kind: ContextKind,
+    pub factor: f64,
+    /// One entry per keyword the cue applied to, e.g. `"denies" → "chapter 11"`.
+    pub evidence: Vec<String>,
+}
+
+/// A word of the scanned text, lowercased, with possessive endings dropped.
+struct Token {
+    word: String,
+    start: usize,
+    /// Punctuation after this word ends the clause.
+    ends_clause: bool,
+    /// Written with a capital first letter ("May" the month, not "may").
+    capitalized: bool,
+}
+
+/// How a freight mention relates to the bankruptcy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Debtor,
+    Creditor,
+}
+
+/// Read the context of every match and return the adjustments that apply,
+/// in the order they should be multiplied in.
+pub fn analyze(text: &str, matches: &[MatchSpan], is_bankruptcy_term: impl Fn(&str) -> bool) -> Vec<ContextAdjustment> {
+    let tokens = tokenize(text);
+    let mut adjustments = Vec::new();
+
+    // Negation and hypotheticals, on bankruptcy terms. "Creditor" and
+    // "debtor" are roles, not claims that anyone is bankrupt.
+    let mut asserted = 0;
+    let mut negated = Vec::new();
+    let mut hypothetical = Vec::new();
+    for span in matches.iter().filter(|m| is_bankruptcy_term(&m.keyword)) {
+        if matches!(span.keyword.as_str(), "creditor" | "debtor") {
+            continue;
+        }
+        let (before, after) = window(&tokens, span);
+        if let Some(cue) = find_cue(&before, NEGATION_CUES).or_else(|| find_cue(&after, POST_NEGATION_CUES)) {
+            negated.push(evidence(cue, &span.keyword));
+        } else if let Some(cue) = find_cue(&before, HYPOTHETICAL_CUES) {
+            hypothetical.push(evidence(cue, &span.keyword));
+        } else {
+            asserted += 1;
+        }
+    }
+    if asserted == 0 && !negated.is_empty() {
+        negated.extend(hypothetical);
+        adjustments.push(adjustment(ContextKind::Negated, negated));
+    } else if asserted == 0 && !hypothetical.is_empty() {
+        adjustments.push(adjustment(ContextKind::Hypothetical, hypothetical));
+    }
+
+    // Roles, on freight terms. One freight mention as the debtor is enough
+    // to keep the score.
+    let mut creditor = Vec::new();
+    let mut debtor = false;
+    for span in matches.iter().filter(|m| !is_bankruptcy_term(&m.keyword)) {
+        let (before, after) = window(&tokens, span);
+        match role(&before, &after) {
+            Some((Role::Creditor, cue)) => creditor.push(evidence(cue, &span.keyword)),
+            Some((Role::Debtor, _)) => debtor = true,
+            None => {}
+        }
+    }
+    if !creditor.is_empty() && !debtor {
+        adjustments.push(adjustment(ContextKind::CreditorRole, creditor));
+    }
+
+    adjustments
+}
+
+fn adjustment(kind: ContextKind, evidence: Vec<String>) -> ContextAdjustment {
+    ContextAdjustment {
+        kind,
+        factor: kind.factor(),
+        evidence,
+    }
+}
+
+fn evidence(cue: &str, keyword: &str) -> String {
+    format!("{:?} → {:?}", cue, keyword)
+}
+
+/// Split `text` into words, noting where clauses end.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_alphanumeric() {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, next)) = chars.peek() {
+                if next.is_alphanumeric() || next == '\'' || next == '’' {
+                    end = i + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let raw = &text[start..end];
+            let word = raw.to_lowercase().replace('’', "'");
+            let word = word.trim_end_matches("'s").trim_end_matches('\'').to_string();
+            tokens.push(Token {
+                capitalized: c.is_uppercase(),
+                word,
+                start,
+                ends_clause: false,
+            });
+            continue;
+        }
+
+        let Some(last) = tokens.last_mut() else { continue };
+        let next_capitalized = || {
+            text[start + c.len_utf8()..].trim_start().chars().next().is_some_and(char::is_uppercase)
+        };
+        let sentence_end = c == '.'
+            && chars.peek().is_none_or(|(_, next)| next.is_whitespace())
+            && (!ABBREVIATIONS.contains(&last.word.as_str())
+                || COMPANY_SUFFIXES.contains(&last.word.as_str()) && next_capitalized());
+        if matches!(c, ';' | '!' | '?') || sentence_end {
+            last.ends_clause = true;
+        }
+    }
+    tokens
+}
+
+/// The words before (nearest first) and after (nearest first) `span`,
+/// within its clause.
+fn window<'a>(tokens: &'a [Token], span: &MatchSpan) -> (Vec<&'a Token>, Vec<&'a Token>) {
+    let first = tokens.partition_point(|t| t.start < span.start);
+    let after_start = tokens.partition_point(|t| t.start < span.end);
+
+    let mut before = Vec::new();
+    for token in tokens[..first].iter().rev().take(BEFORE_WINDOW) {
+        if token.ends_clause || CLAUSE_BREAKS.contains(&token.word.as_str()) {
+            break;
+        }
+        before.push(token);
+    }
+
+    let mut after = Vec::new();
+    if first < after_start && tokens[after_start - 1].ends_clause {
+        return (before, after);
+    }
+    for token in tokens[after_start..].iter().take(AFTER_WINDOW) {
+        if CLAUSE_BREAKS.contains(&token.word.as_str()) {
+            break;
+        }
+        after.push(token);
+        if token.ends_clause {
+            break;
+        }
+    }
+    (before, after)
+}
+
+/// The first cue from `cues` found in `words` (nearest first), with its
+/// distance from the keyword in words.
+fn find_cue_at(words: &[&Token], cues: &[&'static str], reversed: bool) -> Option<(usize, &'static str)> {
+    cues.iter()
+        .filter_map(|cue| {
+            let mut parts: Vec<&str> = cue.split(' ').collect();
+            if reversed {
+                parts.reverse();
+            }
+            (0..words.len())
+                .find(|&i| {
+                    words.len() - i >= parts.len()
+                        && parts.iter().enumerate().all(|(j, part)| {
+                            let token = words[i + j];
+                            token.word == *part && !(token.word == "may" && token.capitalized)
+                        })
+                })
+                .map(|i| (i, *cue))
+        })
+        .min_by_key(|(distance, _)| *distance)
+}
+
+/// A cue before the keyword. `words` is nearest first, so multi-word cues
+/// are matched back to front.
+fn find_cue(words: &[&Token], cues: &[&'static str]) -> Option<&'static str> {
+    find_cue_at(words, cues, true).map(|(_, cue)| cue)
+}
+
+/// Whichever role cue sits closest to the keyword, on either side.
+/// Debtor wins ties.
+fn role(before: &[&Token], after: &[&Token]) -> Option<(Role, &'static str)> {
+    let nearest = |cues| {
+        [find_cue_at(before, cues, true), find_cue_at(after, cues, false)]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(distance, _)| *distance)
+    };
+    match (nearest(DEBTOR_CUES), nearest(CREDITOR_CUES)) {
+        (Some((d, cue)), Some((c, _))) if d <= c => Some((Role::Debtor, cue)),
+        (_, Some((_, cue))) => Some((Role::Creditor, cue)),
+        (Some((_, cue)), None) => Some((Role::Debtor, cue)),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::scan_text;
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<ContextKind> {
+        scan_text(text).context.iter().map(|a| a.kind).collect()
+    }
+
+    #[test]
+    fn test_context_corpus() {
+        use ContextKind::*;
+        let cases: &[(&str, &[ContextKind])] = &[
+            ("XYZ Trucking, a motor carrier, filed for Chapter 11 bankruptcy", &[]),
+            ("XYZ Trucking is NOT in bankruptcy and denies rumors of Chapter 11", &[Negated]),
+            ("XYZ Freight said bankruptcy rumors were unfounded", &[Negated]),
+            ("XYZ Trucking has no plans to seek chapter 11 protection", &[Negated]),
+            ("The freight carrier may file for Chapter 11 if the lender walks", &[Hypothetical]),
+            ("Analysts see a risk of bankruptcy at the trucking company", &[Hypothetical]),
+            ("On May 3, XYZ Trucking filed for Chapter 11", &[]),
+            ("Claim filed by XYZ Freight in the Chapter 11 case of ABC Retail", &[CreditorRole]),
+            ("Proof of Claim filed by XYZ Freight in the Chapter 11 case of ABC Retail", &[CreditorRole]),
+            ("Unsecured creditor XYZ Trucking objected to the Chapter 11 plan of ABC Retail", &[CreditorRole]),
+            ("ABC Retail owes XYZ Freight $2 million in its chapter 11 case", &[CreditorRole]),
+            ("The Debtor, XYZ Trucking, listed its creditors in the chapter 11 petition", &[]),
+            ("XYZ Trucking is not a creditor, but the debtor trucking company filed chapter 11", &[]),
+            (
+                "The trucking company denied it was insolvent; creditors may force a chapter 7",
+                &[Negated],
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(kinds(text), *expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_claims_deadline_in_a_petition_is_not_a_creditor_role() {
+        let petition = "Chapter 11 Voluntary Petition for Non-Individuals. Fee Amount $1738 \
+                        Filed by Acme Trucking, Inc. Proof of Claim due by 10/1/2024.";
+        let result = scan_text(petition);
+        assert!(result.context.is_empty(), "{:?}", result.context);
+        assert!(result.confidence >= 0.3, "{}", result.confidence);
+
+        // The suffix still doesn't end the sentence mid-name.
+        let creditor = "Claim filed by XYZ Freight Co. in the chapter 11 case of ABC Retail";
+        assert_eq!(kinds(creditor), [ContextKind::CreditorRole]);
+    }
+
+    #[test]
+    fn test_context_lowers_confidence_and_says_why() {
+        let filing = scan_text("XYZ Trucking, a motor carrier, filed for Chapter 11 bankruptcy");
+        let denial = scan_text("XYZ Trucking, a motor carrier, is not in Chapter 11 bankruptcy");
+        assert!(denial.confidence < filing.confidence * 0.5);
+        assert_eq!(denial.context.len(), 1);
+        assert_eq!(denial.context[0].factor, NEGATED_FACTOR);
+        assert!(denial.context[0].evidence.contains(&"\"not\" → \"chapter 11\"".to_string()));
+    }
+}
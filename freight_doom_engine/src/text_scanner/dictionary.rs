@@ -0,0 +1,374 @@
+// =============================================================================
+// dictionary.rs — THE VOCABULARY, NOW EDITABLE BY PEOPLE WHO KNOW TRUCKS
+// =============================================================================
+//
+// The keyword lists used to be `LazyLock` statics, which meant teaching the
+// engine that "hotshot" is a kind of trucking took a pull request, a review,
+// a release and a deploy. Our analysts know more about freight slang than
+// our compiler does, so the vocabulary is now a TOML file (`keywords.toml`
+// is the built-in one) with a weight on every term.
+//
+// A file is parsed and validated as a whole: an empty term, the same term
+// twice in one table, or a term that's both [freight] and [bankruptcy] gets
+// the whole file rejected with every problem listed, not just the first.
+// So does a [scoring] weight below zero (see `scoring`).
+//
+// `run_reloader` watches the file's modification time. A new version that
+// passes validation is compiled and swapped in whole; one that doesn't is
+// logged and ignored, and the scanners carry on with the vocabulary they
+// had. A typo at 4:55 on a Friday doesn't take detection down with it.
+// =============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::models::CompanyClassification;
+
+use super::scoring::ScoringModel;
+use super::{Keywords, SharedKeywords};
+
+/// The built-in dictionary, compiled into the binary.
+pub const BUILTIN: &str = include_str!("keywords.toml");
+
+/// Keyword categories, one table each in the dictionary file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Freight,
+    Bankruptcy,
+    HighSignal,
+    Carrier,
+    Broker,
+    Tpl,
+    Forwarder,
+    Ltl,
+    Truckload,
+    Drayage,
+    Intermodal,
+    LastMile,
+    FreightTech,
+    Shipper,
+}
+
+impl Category {
+    pub const ALL: [Category; 14] = [
+        Category::Freight,
+        Category::Bankruptcy,
+        Category::HighSignal,
+        Category::Carrier,
+        Category::Broker,
+        Category::Tpl,
+        Category::Forwarder,
+        Category::Ltl,
+        Category::Truckload,
+        Category::Drayage,
+        Category::Intermodal,
+        Category::LastMile,
+        Category::FreightTech,
+        Category::Shipper,
+    ];
+
+    /// The table name in the dictionary file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Freight => "freight",
+            Category::Bankruptcy => "bankruptcy",
+            Category::HighSignal => "high_signal",
+            Category::Carrier => "carrier",
+            Category::Broker => "broker",
+            Category::Tpl => "tpl",
+            Category::Forwarder => "forwarder",
+            Category::Ltl => "ltl",
+            Category::Truckload => "truckload",
+            Category::Drayage => "drayage",
+            Category::Intermodal => "intermodal",
+            Category::LastMile => "last_mile",
+            Category::FreightTech => "freight_tech",
+            Category::Shipper => "shipper",
+        }
+    }
+
+    /// The company type the category's terms point to, for the categories
+    /// that are company types.
+    pub fn classification(self) -> Option<CompanyClassification> {
+        match self {
+            Category::Freight | Category::Bankruptcy | Category::HighSignal => None,
+            Category::Carrier => Some(CompanyClassification::Carrier),
+            Category::Broker => Some(CompanyClassification::Broker),
+            Category::Tpl => Some(CompanyClassification::ThirdPartyLogistics),
+            Category::Forwarder => Some(CompanyClassification::FreightForwarder),
+            Category::Ltl => Some(CompanyClassification::Ltl),
+            Category::Truckload => Some(CompanyClassification::Truckload),
+            Category::Drayage => Some(CompanyClassification::Drayage),
+            Category::Intermodal => Some(CompanyClassification::Intermodal),
+            Category::LastMile => Some(CompanyClassification::LastMile),
+            Category::FreightTech => Some(CompanyClassification::FreightTech),
+            Category::Shipper => Some(CompanyClassification::Shipper),
+        }
+    }
+}
+
+/// A keyword dictionary: term → weight for every category. Terms are kept
+/// as written in the file; `terms` hands them out normalised.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dictionary {
+    #[serde(default)]
+    freight: BTreeMap<String, f64>,
+    #[serde(default)]
+    bankruptcy: BTreeMap<String, f64>,
+    #[serde(default)]
+    high_signal: BTreeMap<String, f64>,
+    #[serde(default)]
+    carrier: BTreeMap<String, f64>,
+    #[serde(default)]
+    broker: BTreeMap<String, f64>,
+    #[serde(default)]
+    tpl: BTreeMap<String, f64>,
+    #[serde(default)]
+    forwarder: BTreeMap<String, f64>,
+    #[serde(default)]
+    ltl: BTreeMap<String, f64>,
+    #[serde(default)]
+    truckload: BTreeMap<String, f64>,
+    #[serde(default)]
+    drayage: BTreeMap<String, f64>,
+    #[serde(default)]
+    intermodal: BTreeMap<String, f64>,
+    #[serde(default)]
+    last_mile: BTreeMap<String, f64>,
+    #[serde(default)]
+    freight_tech: BTreeMap<String, f64>,
+    #[serde(default)]
+    shipper: BTreeMap<String, f64>,
+    #[serde(default)]
+    scoring: ScoringModel,
+}
+
+impl Dictionary {
+    /// The dictionary in `keywords.toml`.
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN).expect("the built-in keyword dictionary is invalid")
+    }
+
+    /// Parse and validate a dictionary in TOML.
+    pub fn parse(text: &str) -> Result<Self> {
+        let dictionary: Dictionary = toml::from_str(text).context("keyword dictionary is not valid TOML")?;
+        dictionary.validate().context("invalid keyword dictionary")?;
+        Ok(dictionary)
+    }
+
+    /// Read, parse and validate a dictionary file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read keyword dictionary {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("rejected keyword dictionary {}", path.display()))
+    }
+
+    fn table(&self, category: Category) -> &BTreeMap<String, f64> {
+        match category {
+            Category::Freight => &self.freight,
+            Category::Bankruptcy => &self.bankruptcy,
+            Category::HighSignal => &self.high_signal,
+            Category::Carrier => &self.carrier,
+            Category::Broker => &self.broker,
+            Category::Tpl => &self.tpl,
+            Category::Forwarder => &self.forwarder,
+            Category::Ltl => &self.ltl,
+            Category::Truckload => &self.truckload,
+            Category::Drayage => &self.drayage,
+            Category::Intermodal => &self.intermodal,
+            Category::LastMile => &self.last_mile,
+            Category::FreightTech => &self.freight_tech,
+            Category::Shipper => &self.shipper,
+        }
+    }
+
+    /// The terms in `category`, normalised (lowercase, single spaces, `*`
+    /// kept), with their weights.
+    pub fn terms(&self, category: Category) -> impl Iterator<Item = (String, f64)> + '_ {
+        self.table(category)
+            .iter()
+            .map(|(term, weight)| (normalize(term), *weight))
+    }
+
+    /// The feature weights in the [scoring] table.
+    pub fn scoring(&self) -> &ScoringModel {
+        &self.scoring
+    }
+
+    /// Every problem with the dictionary, so an analyst fixes them in one
+    /// go rather than one reload at a time.
+    fn validate(&self) -> Result<()> {
+        let mut problems: Vec<String> = Vec::new();
+        let mut normalized: HashMap<(&str, String), &str> = HashMap::new();
+
+        for category in Category::ALL {
+            let table = category.name();
+            for (term, weight) in self.table(category) {
+                let key = normalize(term);
+                let word = key.trim_end_matches('*');
+                if word.is_empty() {
+                    problems.push(format!("[{}] has an empty term {:?}", table, term));
+                    continue;
+                }
+                if word.contains('*') {
+                    problems.push(format!("[{}] {:?}: `*` only goes at the end of a term", table, term));
+                }
+                if !weight.is_finite() || *weight <= 0.0 {
+                    problems.push(format!("[{}] {:?} has weight {}; weights must be above zero", table, term, weight));
+                }
+                if let Some(first) = normalized.insert((table, key), term) {
+                    problems.push(format!("[{}] lists {:?} twice (also as {:?})", table, term, first));
+                }
+            }
+        }
+
+        for (term, _) in self.terms(Category::Freight) {
+            if self.terms(Category::Bankruptcy).any(|(other, _)| other == term) {
+                problems.push(format!("{:?} is in both [freight] and [bankruptcy]", term));
+            }
+        }
+        for (term, _) in self.terms(Category::HighSignal) {
+            let relevant = |category| self.terms(category).any(|(other, _)| other == term);
+            if !relevant(Category::Freight) && !relevant(Category::Bankruptcy) {
+                problems.push(format!(
+                    "[high_signal] {:?} isn't in [freight] or [bankruptcy], so it can never match",
+                    term
+                ));
+            }
+        }
+        if self.freight.is_empty() && self.bankruptcy.is_empty() {
+            problems.push("[freight] and [bankruptcy] are both empty".to_string());
+        }
+        self.scoring.validate(&mut problems);
+
+        if !problems.is_empty() {
+            bail!("{} problem(s): {}", problems.len(), problems.join("; "));
+        }
+        Ok(())
+    }
+}
+
+/// Lowercase, trimmed, runs of whitespace collapsed to one space — the
+/// form the automaton matches, so "Dry  Van" and "dry van" are one term.
+fn normalize(term: &str) -> String {
+    term.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Load the dictionary at `path` and swap it into `keywords`. Returns how
+/// many relevance terms it has.
+pub fn load_into(path: &Path, keywords: &SharedKeywords) -> Result<usize> {
+    let compiled = Keywords::compile(&Dictionary::load(path)?)?;
+    let terms = compiled.len();
+    keywords.set(compiled);
+    Ok(terms)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Re-read the dictionary at `path` into `keywords` whenever its
+/// modification time changes, until shutdown. A version that fails
+/// validation is logged and skipped.
+pub async fn run_reloader(
+    path: PathBuf,
+    keywords: SharedKeywords,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut loaded = modified(&path);
+    let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+    ticker.tick().await;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let current = modified(&path);
+                if current == loaded {
+                    continue;
+                }
+                loaded = current;
+                match load_into(&path, &keywords) {
+                    Ok(terms) => info!(path = %path.display(), terms, "📖 Keyword dictionary reloaded"),
+                    Err(e) => error!(
+                        error = format!("{:#}", e),
+                        "📖 Keyword dictionary change rejected — keeping the previous vocabulary"
+                    ),
+                }
+            }
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejection(text: &str) -> String {
+        format!("{:#}", Dictionary::parse(text).unwrap_err())
+    }
+
+    #[test]
+    fn test_builtin_dictionary_is_valid() {
+        let dictionary = Dictionary::builtin();
+        assert!(dictionary.terms(Category::Freight).any(|(term, _)| term == "motor carrier"));
+        assert!(dictionary.terms(Category::Bankruptcy).any(|(term, _)| term == "bankrupt*"));
+    }
+
+    #[test]
+    fn test_bad_dictionaries_are_rejected() {
+        // (dictionary, something the error must mention)
+        let cases: &[(&str, &str)] = &[
+            ("[freight]\n\"\" = 1.0", "empty term"),
+            ("[freight]\n\"  \" = 1.0", "empty term"),
+            ("[freight]\n\"*\" = 1.0", "empty term"),
+            ("[freight]\n\"hotshot\" = 1.0\n\"Hotshot\" = 2.0", "twice"),
+            ("[freight]\n\"dry van\" = 1.0\n\"dry  van\" = 1.0", "twice"),
+            ("[freight]\n\"reefer\" = 0.0", "above zero"),
+            ("[freight]\n\"reefer\" = -1.5", "above zero"),
+            ("[freight]\n\"bank*rupt\" = 1.0", "only goes at the end"),
+            ("[freight]\n\"trucking\" = 1.0\n[bankruptcy]\n\"Trucking\" = 1.0", "both [freight] and [bankruptcy]"),
+            ("[freight]\n\"trucking\" = 1.0\n[high_signal]\n\"hotshot\" = 1.0", "can never match"),
+            ("[carrier]\n\"fleet\" = 1.0", "both empty"),
+            ("[carriers]\n\"fleet\" = 1.0", "not valid TOML"),
+            ("[freight]\n\"reefer\" = \"heavy\"", "not valid TOML"),
+        ];
+        for (text, expected) in cases {
+            let error = rejection(text);
+            assert!(error.contains(expected), "{:?} → {:?}", text, error);
+        }
+    }
+
+    #[test]
+    fn test_every_problem_is_reported_at_once() {
+        let error = rejection("[freight]\n\"\" = 1.0\n\"hotshot\" = 0.0\n\"HOTSHOT\" = 1.0");
+        assert!(error.starts_with("invalid keyword dictionary: 3 problem(s)"), "{}", error);
+    }
+
+    #[test]
+    fn test_load_names_the_file_it_rejected() {
+        let path = std::env::temp_dir().join(format!("freight-doom-keywords-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "[freight]\n\"hotshot\" = 1.0\n\"expedited\" = 1.0\n").unwrap();
+        let dictionary = Dictionary::load(&path).unwrap();
+        assert_eq!(dictionary.terms(Category::Freight).count(), 2);
+
+        std::fs::write(&path, "[freight]\n\"\" = 1.0\n").unwrap();
+        let error = format!("{:#}", Dictionary::load(&path).unwrap_err());
+        assert!(error.contains(&path.display().to_string()), "{}", error);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,558 @@
+// =============================================================================
+// text_scanner/evaluation.rs — THE REPORT CARD
+// =============================================================================
+//
+// Every change to the keyword lists or the scoring weights has been judged
+// the same way: somebody ran five hand-written assertions, squinted at a
+// filing or two, and said "feels better". Feelings are not a metric.
+//
+// This runs the scanner over a labelled corpus — the same JSONL the
+// classifier trains on, with two optional extras per line:
+//
+//   {"text": "...", "label": true, "source": "pacer", "classification": "Carrier"}
+//   {"text": "...", "label": true, "classification": ["Carrier", "Broker"]}
+//
+// and grades it the way the pipeline would use it: a text is flagged when
+// it gets past `quick_freight_check` and scores at least the confidence
+// threshold. Out come precision, recall and F1 (overall and per source), a
+// threshold sweep for FREIGHT_DOOM_MIN_CONFIDENCE, a confusion matrix for
+// the headline company classification, precision and recall for every
+// company type the scanner lists, and every text the scanner got wrong.
+// (Where a corpus line names several types, the first is the headline.)
+//
+// The report is plain text in a fixed order with fixed decimals, so that
+// pasting the before and after into a review is a diff, not an essay.
+// Run it with `freight_doom_engine eval <corpus.jsonl>`.
+// =============================================================================
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::models::CompanyClassification;
+
+use super::classifier::{Label, RelevanceModel};
+use super::{quick_freight_check_with, scan_text_with, Keywords};
+
+/// Thresholds the sweep reports, as twentieths: 0.00, 0.05, … 1.00.
+const SWEEP_STEPS: u32 = 20;
+
+/// How much of a text the list of mistakes quotes.
+const QUOTE_CHARS: usize = 72;
+
+/// The classifications, in the order the report lists them.
+const CLASSES: [CompanyClassification; 12] = [
+    CompanyClassification::Carrier,
+    CompanyClassification::Broker,
+    CompanyClassification::ThirdPartyLogistics,
+    CompanyClassification::FreightForwarder,
+    CompanyClassification::Ltl,
+    CompanyClassification::Truckload,
+    CompanyClassification::Drayage,
+    CompanyClassification::Intermodal,
+    CompanyClassification::LastMile,
+    CompanyClassification::FreightTech,
+    CompanyClassification::Shipper,
+    CompanyClassification::Unclassified,
+];
+
+/// One labelled text to grade the scanner on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// Line of the corpus file it came from.
+    pub line: usize,
+    pub text: String,
+    /// Where the text came from ("pacer", "courtlistener", "edgar"), if
+    /// the corpus says.
+    pub source: Option<String>,
+    pub relevant: bool,
+    /// The company types the text should get, headline first. Empty if
+    /// nobody checked.
+    pub classifications: Vec<CompanyClassification>,
+}
+
+/// A corpus line as written.
+#[derive(Deserialize)]
+struct CaseLine {
+    text: String,
+    label: Label,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    classification: Option<Classifications>,
+}
+
+/// One company type, or several.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Classifications {
+    One(CompanyClassification),
+    Many(Vec<CompanyClassification>),
+}
+
+/// Read a labelled JSONL corpus, skipping blank lines and texts nobody has
+/// triaged.
+pub fn read_cases(path: &Path) -> Result<Vec<Case>> {
+    let text = fs::read_to_string(path).with_context(|| format!("failed to read corpus {}", path.display()))?;
+    let mut cases = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed: CaseLine = serde_json::from_str(line).with_context(|| {
+            format!(
+                "{} line {}: expected {{\"text\": ..., \"label\": ..., \"source\"?: ..., \"classification\"?: ...}}",
+                path.display(),
+                number + 1
+            )
+        })?;
+        if let Some(relevant) = parsed.label.relevant() {
+            cases.push(Case {
+                line: number + 1,
+                text: parsed.text,
+                source: parsed.source.map(|source| source.to_ascii_lowercase()),
+                relevant,
+                classifications: match parsed.classification {
+                    Some(Classifications::One(classification)) => vec![classification],
+                    Some(Classifications::Many(classifications)) => classifications,
+                    None => Vec::new(),
+                },
+            });
+        }
+    }
+    Ok(cases)
+}
+
+/// What the scanner made of one case.
+#[derive(Debug, Clone)]
+pub struct Graded {
+    pub case: Case,
+    /// The scan's confidence, or `None` if `quick_freight_check` turned
+    /// the text away before it was scanned.
+    pub confidence: Option<f64>,
+    /// The company types the scanner listed, most likely first.
+    pub classifications: Vec<CompanyClassification>,
+}
+
+impl Graded {
+    /// Whether the pipeline would publish this text at `threshold`.
+    pub fn flagged(&self, threshold: f64) -> bool {
+        self.confidence.is_some_and(|confidence| confidence >= threshold)
+    }
+
+    /// The scanner's headline classification.
+    pub fn classification(&self) -> CompanyClassification {
+        self.classifications.first().cloned().unwrap_or(CompanyClassification::Unclassified)
+    }
+}
+
+/// Flagged-versus-labelled counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Confusion {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+}
+
+impl Confusion {
+    fn add(&mut self, flagged: bool, relevant: bool) {
+        match (flagged, relevant) {
+            (true, true) => self.true_positives += 1,
+            (true, false) => self.false_positives += 1,
+            (false, true) => self.false_negatives += 1,
+            (false, false) => self.true_negatives += 1,
+        }
+    }
+
+    /// Share of flagged texts that were relevant. `None` if nothing was
+    /// flagged.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// Share of relevant texts that were flagged. `None` if nothing was
+    /// relevant.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+
+    /// Harmonic mean of precision and recall.
+    pub fn f1(&self) -> Option<f64> {
+        f1(self.precision()?, self.recall()?)
+    }
+
+    pub fn flagged(&self) -> usize {
+        self.true_positives + self.false_positives
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+fn f1(precision: f64, recall: f64) -> Option<f64> {
+    (precision + recall > 0.0).then(|| 2.0 * precision * recall / (precision + recall))
+}
+
+/// The scanner's grades on a whole corpus.
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    /// The confidence threshold the headline numbers are for.
+    pub threshold: f64,
+    /// Every case, in corpus order.
+    pub graded: Vec<Graded>,
+}
+
+/// Scan every case with `keywords` and `model`, in parallel, and grade
+/// the results against `threshold`.
+pub fn evaluate(keywords: &Keywords, model: Option<&RelevanceModel>, cases: Vec<Case>, threshold: f64) -> Evaluation {
+    let graded = cases
+        .into_par_iter()
+        .map(|case| {
+            if !quick_freight_check_with(keywords, &case.text) {
+                return Graded { case, confidence: None, classifications: Vec::new() };
+            }
+            let result = scan_text_with(keywords, model, &case.text);
+            let classifications = result.classifications.into_iter().map(|class| class.classification).collect();
+            Graded { case, confidence: Some(result.confidence), classifications }
+        })
+        .collect();
+    Evaluation { threshold, graded }
+}
+
+impl Evaluation {
+    /// Relevance counts at `threshold`.
+    pub fn confusion(&self, threshold: f64) -> Confusion {
+        confusion(self.graded.iter(), threshold)
+    }
+
+    /// Relevance counts at the evaluation's threshold, per source.
+    pub fn by_source(&self) -> BTreeMap<&str, Confusion> {
+        let mut sources: BTreeMap<&str, Confusion> = BTreeMap::new();
+        for graded in &self.graded {
+            let source = graded.case.source.as_deref().unwrap_or("-");
+            sources.entry(source).or_default().add(graded.flagged(self.threshold), graded.case.relevant);
+        }
+        sources
+    }
+
+    /// Counts of expected (rows) against predicted (columns) headline
+    /// classifications, in `CLASSES` order, over the cases that have one.
+    pub fn classification_matrix(&self) -> [[usize; CLASSES.len()]; CLASSES.len()] {
+        let mut matrix = [[0; CLASSES.len()]; CLASSES.len()];
+        for graded in &self.graded {
+            if let Some(expected) = graded.case.classifications.first() {
+                matrix[class_index(expected)][class_index(&graded.classification())] += 1;
+            }
+        }
+        matrix
+    }
+
+    /// Listed-versus-expected counts for every company type, in `CLASSES`
+    /// order, over the cases that have classifications: a type the scanner
+    /// lists counts as flagged, one the corpus names as relevant.
+    pub fn class_confusion(&self) -> [Confusion; CLASSES.len()] {
+        let mut counts = [Confusion::default(); CLASSES.len()];
+        for graded in self.graded.iter().filter(|g| !g.case.classifications.is_empty()) {
+            for (class, counts) in CLASSES.iter().zip(counts.iter_mut()) {
+                counts.add(graded.classifications.contains(class), graded.case.classifications.contains(class));
+            }
+        }
+        counts
+    }
+}
+
+fn confusion<'a>(graded: impl Iterator<Item = &'a Graded>, threshold: f64) -> Confusion {
+    let mut counts = Confusion::default();
+    for graded in graded {
+        counts.add(graded.flagged(threshold), graded.case.relevant);
+    }
+    counts
+}
+
+fn class_index(classification: &CompanyClassification) -> usize {
+    CLASSES.iter().position(|class| class == classification).unwrap_or(CLASSES.len() - 1)
+}
+
+/// A number to three places, or "-" when there isn't one.
+fn metric(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{:.3}", value))
+}
+
+/// The start of a text, on one line.
+fn quote(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(QUOTE_CHARS) {
+        Some((end, _)) => format!("{}…", &flat[..end]),
+        None => flat,
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relevant = self.graded.iter().filter(|g| g.case.relevant).count();
+        writeln!(f, "texts      {} ({} relevant, {} not)", self.graded.len(), relevant, self.graded.len() - relevant)?;
+        writeln!(f, "threshold  {:.2}", self.threshold)?;
+
+        let counts = self.confusion(self.threshold);
+        writeln!(f)?;
+        writeln!(f, "== relevance ==")?;
+        writeln!(f, "{:<14} {:>8} {:>8}", "", "flagged", "passed")?;
+        writeln!(f, "{:<14} {:>8} {:>8}", "relevant", counts.true_positives, counts.false_negatives)?;
+        writeln!(f, "{:<14} {:>8} {:>8}", "not relevant", counts.false_positives, counts.true_negatives)?;
+        writeln!(f, "precision  {}", metric(counts.precision()))?;
+        writeln!(f, "recall     {}", metric(counts.recall()))?;
+        writeln!(f, "f1         {}", metric(counts.f1()))?;
+
+        writeln!(f)?;
+        writeln!(f, "== by source ==")?;
+        writeln!(f, "{:<14} {:>6} {:>9} {:>7} {:>7}", "source", "texts", "precision", "recall", "f1")?;
+        for (source, counts) in self.by_source() {
+            let texts = counts.flagged() + counts.false_negatives + counts.true_negatives;
+            writeln!(
+                f,
+                "{:<14} {:>6} {:>9} {:>7} {:>7}",
+                source,
+                texts,
+                metric(counts.precision()),
+                metric(counts.recall()),
+                metric(counts.f1())
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "== threshold sweep ==")?;
+        writeln!(f, "{:<9} {:>7} {:>9} {:>7} {:>7}", "threshold", "flagged", "precision", "recall", "f1")?;
+        for step in 0..=SWEEP_STEPS {
+            let threshold = step as f64 / SWEEP_STEPS as f64;
+            let counts = self.confusion(threshold);
+            let current = if (threshold - self.threshold).abs() < 1e-9 { "  <- threshold" } else { "" };
+            writeln!(
+                f,
+                "{:<9.2} {:>7} {:>9} {:>7} {:>7}{}",
+                threshold,
+                counts.flagged(),
+                metric(counts.precision()),
+                metric(counts.recall()),
+                metric(counts.f1()),
+                current
+            )?;
+        }
+
+        let matrix = self.classification_matrix();
+        let labelled: usize = matrix.iter().flatten().sum();
+        writeln!(f)?;
+        writeln!(f, "== classification ==")?;
+        if labelled == 0 {
+            writeln!(f, "no texts have a \"classification\" label")?;
+        } else {
+            // Only the types this corpus and this scanner ever mention, or
+            // twelve columns of zeros bury the ones that matter.
+            let classes = self.class_confusion();
+            let shown: Vec<usize> = (0..CLASSES.len())
+                .filter(|&i| matrix[i].iter().sum::<usize>() + matrix.iter().map(|row| row[i]).sum::<usize>() > 0
+                    || classes[i].flagged() + classes[i].false_negatives > 0)
+                .collect();
+
+            let correct: usize = (0..CLASSES.len()).map(|i| matrix[i][i]).sum();
+            writeln!(f, "headline   {} of {} right ({})", correct, labelled, metric(ratio(correct, labelled)))?;
+            write!(f, "{:<18}", "expected \\ got")?;
+            for &j in &shown {
+                write!(f, " {:>12}", CLASSES[j].to_string())?;
+            }
+            writeln!(f)?;
+            for &i in &shown {
+                write!(f, "{:<18}", CLASSES[i].to_string())?;
+                for &j in &shown {
+                    write!(f, " {:>12}", matrix[i][j])?;
+                }
+                writeln!(f)?;
+            }
+
+            writeln!(f, "{:<18} {:>6} {:>6} {:>9} {:>7} {:>7}", "listed types", "listed", "named", "precision", "recall", "f1")?;
+            for &i in &shown {
+                let counts = &classes[i];
+                writeln!(
+                    f,
+                    "{:<18} {:>6} {:>6} {:>9} {:>7} {:>7}",
+                    CLASSES[i].to_string(),
+                    counts.flagged(),
+                    counts.true_positives + counts.false_negatives,
+                    metric(counts.precision()),
+                    metric(counts.recall()),
+                    metric(counts.f1())
+                )?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "== mistakes ==")?;
+        for graded in &self.graded {
+            let confidence = graded.confidence.map_or_else(|| "skip".to_string(), |c| format!("{:.2}", c));
+            let flagged = graded.flagged(self.threshold);
+            if flagged != graded.case.relevant {
+                let verdict = if flagged { "false alarm" } else { "missed" };
+                writeln!(f, "line {:<5} {:<19} {:>5}  {}", graded.case.line, verdict, confidence, quote(&graded.case.text))?;
+            }
+            if let Some(expected) = graded.case.classifications.first().filter(|&c| *c != graded.classification()) {
+                let verdict = format!("{} as {}", expected, graded.classification());
+                writeln!(f, "line {:<5} {:<19} {:>5}  {}", graded.case.line, verdict, confidence, quote(&graded.case.text))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn case(line: usize, text: &str, relevant: bool, source: &str, classifications: &[CompanyClassification]) -> Case {
+        Case {
+            line,
+            text: text.to_string(),
+            source: Some(source.to_string()),
+            relevant,
+            classifications: classifications.to_vec(),
+        }
+    }
+
+    fn corpus() -> Vec<Case> {
+        vec![
+            case(
+                1,
+                "XYZ Trucking, a motor carrier with USDOT 12345, filed for Chapter 11 bankruptcy",
+                true,
+                "pacer",
+                &[CompanyClassification::Carrier],
+            ),
+            case(
+                2,
+                "Fast Lane Brokerage, a freight broker with broker authority, filed a Chapter 7 petition",
+                true,
+                "courtlistener",
+                &[CompanyClassification::Broker, CompanyClassification::Carrier],
+            ),
+            case(3, "Acme Freight reported record quarterly revenue", false, "edgar", &[]),
+            case(4, "The weather is nice today", false, "edgar", &[]),
+            case(5, "Our freight trucking carrier logistics business entered chapter 11", false, "pacer", &[]),
+        ]
+    }
+
+    #[test]
+    fn test_confusion_metrics() {
+        // (tp, fp, fn, tn, precision, recall, f1)
+        let cases = [
+            (8, 2, 2, 88, Some(0.8), Some(0.8), Some(0.8)),
+            (1, 0, 3, 0, Some(1.0), Some(0.25), Some(0.4)),
+            (0, 0, 5, 5, None, Some(0.0), None),
+            (0, 4, 0, 6, Some(0.0), None, None),
+            (0, 1, 1, 0, Some(0.0), Some(0.0), None),
+        ];
+        for (tp, fp, fn_, tn, precision, recall, f1) in cases {
+            let counts = Confusion { true_positives: tp, false_positives: fp, false_negatives: fn_, true_negatives: tn };
+            let close = |a: Option<f64>, b: Option<f64>| match (a, b) {
+                (Some(a), Some(b)) => (a - b).abs() < 1e-9,
+                (a, b) => a == b,
+            };
+            assert!(close(counts.precision(), precision), "{:?}", counts);
+            assert!(close(counts.recall(), recall), "{:?}", counts);
+            assert!(close(counts.f1(), f1), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_grades_like_the_pipeline() {
+        let evaluation = evaluate(&Keywords::builtin(), None, corpus(), 0.3);
+
+        // The weather never reaches the scanner.
+        assert_eq!(evaluation.graded[3].confidence, None);
+        assert!(!evaluation.graded[3].flagged(0.0));
+
+        let counts = evaluation.confusion(0.3);
+        assert_eq!(
+            counts,
+            Confusion { true_positives: 2, false_positives: 2, false_negatives: 0, true_negatives: 1 }
+        );
+        assert_eq!(evaluation.confusion(1.01).flagged(), 0);
+        assert_eq!(evaluation.by_source()["edgar"].true_negatives, 1);
+        assert_eq!(evaluation.by_source()["pacer"].false_positives, 1);
+
+        let matrix = evaluation.classification_matrix();
+        assert_eq!(matrix[class_index(&CompanyClassification::Carrier)][class_index(&CompanyClassification::Carrier)], 1);
+        assert_eq!(matrix[class_index(&CompanyClassification::Broker)][class_index(&CompanyClassification::Broker)], 1);
+
+        // The broker's trucks were expected and never mentioned.
+        let classes = evaluation.class_confusion();
+        let carrier = classes[class_index(&CompanyClassification::Carrier)];
+        assert_eq!((carrier.true_positives, carrier.false_negatives), (1, 1));
+    }
+
+    #[test]
+    fn test_report_is_stable_and_names_the_mistakes() {
+        let report = evaluate(&Keywords::builtin(), None, corpus(), 0.3).to_string();
+        assert_eq!(report, evaluate(&Keywords::builtin(), None, corpus(), 0.3).to_string());
+
+        for expected in [
+            "texts      5 (2 relevant, 3 not)",
+            "threshold  0.30",
+            "precision  0.500",
+            "recall     1.000",
+            "headline   2 of 2 right (1.000)",
+            "Carrier                 1      2     1.000   0.500   0.667",
+            "0.30            4     0.500   1.000   0.667  <- threshold",
+        ] {
+            assert!(report.contains(expected), "missing {:?} in\n{}", expected, report);
+        }
+        let mistakes = report.split("== mistakes ==").nth(1).unwrap();
+        assert_eq!(mistakes.trim().lines().count(), 2, "{}", mistakes);
+        assert!(mistakes.contains("line 3     false alarm"), "{}", mistakes);
+        assert!(mistakes.contains("line 5     false alarm"), "{}", mistakes);
+    }
+
+    #[test]
+    fn test_read_cases() {
+        let path = std::env::temp_dir().join(format!("freight_doom_eval_{}.jsonl", std::process::id()));
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(file, r#"{{"text": "Acme Trucking filed", "label": "confirmed", "source": "PACER", "classification": "Carrier"}}"#).unwrap();
+        writeln!(file).unwrap();
+        writeln!(file, r#"{{"text": "Claim by Acme Trucking", "label": false}}"#).unwrap();
+        writeln!(file, r#"{{"text": "Acme Brokerage filed", "label": true, "classification": ["Broker", "Carrier"]}}"#).unwrap();
+        writeln!(file, r#"{{"text": "Not looked at yet", "label": "new"}}"#).unwrap();
+        drop(file);
+
+        let cases = read_cases(&path).unwrap();
+        assert_eq!(
+            cases,
+            vec![
+                case(1, "Acme Trucking filed", true, "pacer", &[CompanyClassification::Carrier]),
+                Case {
+                    line: 3,
+                    text: "Claim by Acme Trucking".to_string(),
+                    source: None,
+                    relevant: false,
+                    classifications: vec![],
+                },
+                Case {
+                    line: 4,
+                    text: "Acme Brokerage filed".to_string(),
+                    source: None,
+                    relevant: true,
+                    classifications: vec![CompanyClassification::Broker, CompanyClassification::Carrier],
+                },
+            ]
+        );
+
+        fs::write(&path, r#"{"text": "x", "label": true, "classification": "Airline"}"#).unwrap();
+        let error = format!("{:#}", read_cases(&path).unwrap_err());
+        assert!(error.contains("line 1"), "{}", error);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,679 @@
+// =============================================================================
+// text_scanner.rs — THE SIMD-ACCELERATED TEXT ANNIHILATOR
+// =============================================================================
+//
+// This module is where we do the actual "is this a logistics company
+// bankruptcy?" determination. And we do it FAST. How fast? We use:
+//
+// 1. Aho-Corasick algorithm — multi-pattern matching that scans text
+//    for ALL keywords simultaneously in a single pass. O(n + m) where
+//    n is the text length and m is the total number of matches.
+//    Built on a finite automaton. This is how antivirus scanners work.
+//    We're using antivirus-grade technology to find bankrupt trucking
+//    companies. Let that sink in.
+//
+// 2. memchr — SIMD-accelerated byte scanning. Uses SSE2/AVX2/NEON
+//    vector instructions to scan memory at speeds that would make
+//    a for loop weep. We use it for fast preliminary checks before
+//    firing up the full Aho-Corasick automaton.
+//
+// 3. Rayon parallel iterators — when we have multiple texts to scan,
+//    we parallelize across all available CPU cores. Because leaving
+//    cores idle while there are bankruptcies to detect is practically
+//    criminal negligence.
+//
+// Is SIMD-accelerated text scanning overkill for parsing a few RSS feeds?
+// The answer is yes, and we wouldn't have it any other way.
+//
+// Every keyword must sit on word boundaries, so "tia" doesn't fire on
+// "initial", "semi" on "seminar" or "pod" on "podcast". A keyword may be
+// followed by a plural or possessive ending ("carriers", "debtor's"), and
+// one ending in `*` matches any word it starts ("bankrupt*" → "bankruptcy",
+// "bankrupted").
+//
+// The keywords themselves live in a `dictionary` — `keywords.toml` unless
+// FREIGHT_DOOM_KEYWORDS points somewhere else — with a weight and a
+// category on every term, recompiled and swapped in when the file changes.
+//
+// Then `context` reads the words around each match, so a denial, a rumour,
+// or a trucking company that's merely owed money doesn't score like a
+// filing, and a `classifier` trained on the analysts' triage, if one is
+// loaded, gets its say.
+// =============================================================================
+
+use aho_corasick::AhoCorasick;
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use tracing::debug;
+
+use crate::models::{ClassScore, CompanyClassification, ScoreContribution};
+
+pub mod classifier;
+pub mod company;
+pub mod context;
+pub mod dictionary;
+pub mod evaluation;
+pub mod scoring;
+
+pub use classifier::RelevanceModel;
+pub use context::{ContextAdjustment, ContextKind};
+pub use dictionary::{Category, Dictionary};
+pub use scoring::ScoringModel;
+
+/// A keyword list compiled into one Aho-Corasick automaton, with the word
+/// boundary rules applied on top.
+struct KeywordSet {
+    automaton: AhoCorasick,
+    /// Lowercase keyword text, `*` removed, by automaton pattern index.
+    keywords: Vec<String>,
+    /// Whether the keyword ended in `*` and may run on into a longer word.
+    prefix: Vec<bool>,
+    /// How much each keyword counts for, by automaton pattern index.
+    weights: Vec<f64>,
+}
+
+impl KeywordSet {
+    fn new(terms: impl IntoIterator<Item = (String, f64)>) -> Result<Self> {
+        let (specs, weights): (Vec<String>, Vec<f64>) = terms.into_iter().unzip();
+        let keywords: Vec<String> = specs
+            .iter()
+            .map(|spec| spec.trim_end_matches('*').to_lowercase())
+            .collect();
+        let prefix = specs.iter().map(|spec| spec.ends_with('*')).collect();
+        let automaton = AhoCorasick::builder()
+            .ascii_case_insensitive(true)
+            .build(&keywords)
+            .context("Failed to build Aho-Corasick automaton — the keywords are invalid somehow")?;
+        Ok(Self { automaton, keywords, prefix, weights })
+    }
+
+    fn len(&self) -> usize {
+        self.keywords.len()
+    }
+
+    /// Every whole-word keyword match in `text`, left to right. Where two
+    /// matches overlap ("motor carrier" and "carrier"), the longer wins.
+    fn find(&self, text: &str) -> Vec<MatchSpan> {
+        let bytes = text.as_bytes();
+        let mut found: Vec<MatchSpan> = self
+            .automaton
+            .find_overlapping_iter(text)
+            .filter_map(|m| {
+                let index = m.pattern().as_usize();
+                if m.start() > 0 && is_word_byte(bytes[m.start() - 1]) {
+                    return None;
+                }
+                let end = if self.prefix[index] {
+                    m.end() + bytes[m.end()..].iter().take_while(|b| is_word_byte(**b)).count()
+                } else {
+                    m.end() + word_ending_len(&bytes[m.end()..])
+                };
+                if end < bytes.len() && is_word_byte(bytes[end]) {
+                    return None;
+                }
+                Some(MatchSpan {
+                    keyword: self.keywords[index].clone(),
+                    weight: self.weights[index],
+                    start: m.start(),
+                    end,
+                })
+            })
+            .collect();
+
+        // Leftmost, then longest span, then the most specific keyword
+        // ("bankruptcy" over "bankrupt*" for the same word).
+        found.sort_by(|a, b| {
+            a.start
+                .cmp(&b.start)
+                .then(b.end.cmp(&a.end))
+                .then(b.keyword.len().cmp(&a.keyword.len()))
+        });
+        let mut spans: Vec<MatchSpan> = Vec::with_capacity(found.len());
+        for span in found {
+            if spans.last().is_none_or(|last| span.start >= last.end) {
+                spans.push(span);
+            }
+        }
+        spans
+    }
+}
+
+/// Letters and digits. Everything else — spaces, punctuation, dashes,
+/// non-ASCII — separates words.
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric()
+}
+
+/// Length of a plural or possessive ending at the start of `rest`: "s",
+/// "es", "'s". Zero if there isn't one.
+fn word_ending_len(rest: &[u8]) -> usize {
+    ["'s", "es", "s"]
+        .iter()
+        .find(|ending| {
+            rest.len() >= ending.len() && rest[..ending.len()].eq_ignore_ascii_case(ending.as_bytes())
+        })
+        .map_or(0, |ending| ending.len())
+}
+
+/// A `Dictionary` compiled into automata: the freight and bankruptcy terms
+/// in one (a finite state machine that matches ALL of them in a single pass
+/// through the text — the algorithmic equivalent of reading a page and
+/// circling every suspicious word at the same time), and one per company
+/// type. Replaced whole on reload, so no scan ever sees half of an old
+/// vocabulary and half of a new one.
+pub struct Keywords {
+    relevance: KeywordSet,
+    bankruptcy: HashMap<String, f64>,
+    high_signal: HashMap<String, f64>,
+    /// One automaton per company type, in dictionary order.
+    classes: Vec<(CompanyClassification, KeywordSet)>,
+    /// Freight terms that the memchr prefilters don't know about, if the
+    /// dictionary isn't the built-in one ("hotshot" contains no "truck").
+    prefilter: Option<AhoCorasick>,
+    /// Feature weights from the dictionary's [scoring] table.
+    model: ScoringModel,
+}
+
+impl Keywords {
+    /// Compile a validated dictionary.
+    pub fn compile(dictionary: &Dictionary) -> Result<Self> {
+        let key = |(term, weight): (String, f64)| (term.trim_end_matches('*').to_string(), weight);
+        let freight: Vec<(String, f64)> = dictionary.terms(Category::Freight).collect();
+        let prefilter = AhoCorasick::builder()
+            .ascii_case_insensitive(true)
+            .build(freight.iter().map(|(term, _)| term.trim_end_matches('*')))
+            .context("Failed to build the freight prefilter")?;
+
+        Ok(Self {
+            relevance: KeywordSet::new(freight.into_iter().chain(dictionary.terms(Category::Bankruptcy)))?,
+            bankruptcy: dictionary.terms(Category::Bankruptcy).map(key).collect(),
+            high_signal: dictionary.terms(Category::HighSignal).map(key).collect(),
+            classes: Category::ALL
+                .into_iter()
+                .filter_map(|category| Some((category, category.classification()?)))
+                .map(|(category, classification)| Ok((classification, KeywordSet::new(dictionary.terms(category))?)))
+                .collect::<Result<_>>()?,
+            prefilter: Some(prefilter),
+            model: dictionary.scoring().clone(),
+        })
+    }
+
+    /// The built-in dictionary, compiled.
+    pub fn builtin() -> Self {
+        Self {
+            prefilter: None,
+            ..Self::compile(&Dictionary::builtin()).expect("the built-in keyword dictionary doesn't compile")
+        }
+    }
+
+    /// Number of freight and bankruptcy terms.
+    pub fn len(&self) -> usize {
+        self.relevance.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.relevance.len() == 0
+    }
+
+    /// Whether `keyword` (as it appears in a `MatchSpan`) is a bankruptcy
+    /// term rather than a freight one.
+    pub fn is_bankruptcy_term(&self, keyword: &str) -> bool {
+        self.bankruptcy.contains_key(keyword)
+    }
+
+    /// Whether `text` mentions any freight term of this dictionary that the
+    /// memchr prefilters would miss.
+    fn prefilter_matches(&self, text: &str) -> bool {
+        self.prefilter.as_ref().is_some_and(|prefilter| prefilter.is_match(text))
+    }
+}
+
+/// The built-in vocabulary, for callers with no pipeline to ask.
+static BUILTIN_KEYWORDS: LazyLock<Keywords> = LazyLock::new(Keywords::builtin);
+
+/// A pipeline's vocabulary. Clones share it: `set` swaps it for all of
+/// them, for every scan that starts after it returns. Readers clone the
+/// `Arc` and let go of the lock straight away, so a reload never waits on
+/// a scan.
+#[derive(Clone)]
+pub struct SharedKeywords(Arc<RwLock<Arc<Keywords>>>);
+
+impl SharedKeywords {
+    pub fn new(keywords: Keywords) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(keywords))))
+    }
+
+    /// The vocabulary in use right now.
+    pub fn get(&self) -> Arc<Keywords> {
+        self.0.read().clone()
+    }
+
+    pub fn set(&self, keywords: Keywords) {
+        *self.0.write() = Arc::new(keywords);
+    }
+}
+
+impl Default for SharedKeywords {
+    fn default() -> Self {
+        Self::new(Keywords::builtin())
+    }
+}
+
+/// Where a keyword matched: byte offsets into the scanned text, covering
+/// any plural ending or, for `*` keywords, the rest of the word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchSpan {
+    /// The keyword as listed, lowercase, without its `*`.
+    pub keyword: String,
+    /// The keyword's weight in the dictionary.
+    pub weight: f64,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Result of scanning a text for freight/bankruptcy relevance.
+#[derive(Debug, Clone)]
+pub struct ScanResult {
+    /// Confidence score from 0.0 to 1.0
+    pub confidence: f64,
+    /// How many freight keywords were found
+    pub freight_keyword_hits: usize,
+    /// How many bankruptcy keywords were found
+    pub bankruptcy_keyword_hits: usize,
+    /// Total unique keywords matched
+    pub total_matches: usize,
+    /// Classification of the company type: the first of `classifications`
+    pub classification: CompanyClassification,
+    /// Every company type the text suggests, most likely first
+    pub classifications: Vec<ClassScore>,
+    /// The keywords that were matched (for debugging/logging)
+    pub matched_keywords: Vec<String>,
+    /// Every freight keyword match, in order of appearance
+    pub matches: Vec<MatchSpan>,
+    /// Why the words around the matches lowered the confidence, if they did
+    pub context: Vec<ContextAdjustment>,
+    /// What each scoring feature added to the confidence; sums to it
+    pub contributions: Vec<ScoreContribution>,
+}
+
+impl ScanResult {
+    fn empty() -> Self {
+        Self {
+            confidence: 0.0,
+            freight_keyword_hits: 0,
+            bankruptcy_keyword_hits: 0,
+            total_matches: 0,
+            classification: CompanyClassification::Unclassified,
+            classifications: vec![],
+            matched_keywords: vec![],
+            matches: vec![],
+            context: vec![],
+            contributions: vec![],
+        }
+    }
+}
+
+/// Scan a text for freight/logistics bankruptcy relevance.
+///
+/// This is the main entry point for text analysis. It runs the
+/// Aho-Corasick automaton over the text and calculates a confidence
+/// score based on keyword density and variety.
+///
+/// The confidence scoring algorithm (weights in `ScoringModel`):
+/// - Base score from keyword variety and density (matches / text_length_in_words)
+/// - Bonus for having both freight AND bankruptcy keywords (cross-domain signal)
+/// - Bonus for specific high-signal keywords like "chapter 11" or "motor carrier"
+/// - Score capped at 1.0, then scaled down by whatever `context` found
+///
+/// `ScanResult::contributions` says how much each of those added.
+///
+/// A text mentioning "chapter 11 trucking company freight carrier" would
+/// score very high. A text mentioning "freight" once in a 10,000 word
+/// document would score very low.
+///
+/// This one uses the built-in vocabulary and no relevance model; a pipeline
+/// scans with its own through `scan_text_with`.
+pub fn scan_text(text: &str) -> ScanResult {
+    scan_text_with(&BUILTIN_KEYWORDS, None, text)
+}
+
+/// `scan_text` against a particular vocabulary and relevance model rather
+/// than the built-in ones. Each term counts for its weight: in keyword
+/// variety and density, in the high-signal bonus, and in the company
+/// classification. The model, if any, is blended in by `model_weight`.
+pub fn scan_text_with(keywords: &Keywords, model: Option<&RelevanceModel>, text: &str) -> ScanResult {
+    if text.is_empty() {
+        return ScanResult::empty();
+    }
+
+    // SIMD-accelerated preliminary check using memchr.
+    // If the text doesn't contain common bytes from our keywords,
+    // we can skip the full Aho-Corasick scan entirely.
+    // This is the "bouncer at the door" check.
+    let has_potential = memchr::memmem::find(text.as_bytes(), b"freight").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"truck").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"carrier").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"bankrupt").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"chapter").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"logistics").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"Freight").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"Truck").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"Carrier").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"Bankrupt").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"FREIGHT").is_some()
+        || memchr::memmem::find(text.as_bytes(), b"TRUCK").is_some()
+        || keywords.prefilter_matches(text);
+
+    if !has_potential {
+        return ScanResult::empty();
+    }
+
+    // Full Aho-Corasick scan — find ALL matching keywords in a single pass
+    let matches = keywords.relevance.find(text);
+
+    let total_matches = matches.len();
+    if total_matches == 0 {
+        return ScanResult::empty();
+    }
+
+    // Collect unique matched keywords, with their weights
+    let mut unique: Vec<(&str, f64)> = matches.iter().map(|m| (m.keyword.as_str(), m.weight)).collect();
+    unique.sort_by(|a, b| a.0.cmp(b.0));
+    unique.dedup_by(|a, b| a.0 == b.0);
+    let matched_keywords: Vec<String> = unique.iter().map(|(k, _)| k.to_string()).collect();
+
+    // Count freight vs bankruptcy keyword hits
+    let bankruptcy_keyword_hits = matched_keywords
+        .iter()
+        .filter(|k| keywords.is_bankruptcy_term(k))
+        .count();
+
+    let freight_keyword_hits = matches
+        .iter()
+        .filter(|m| !keywords.is_bankruptcy_term(&m.keyword))
+        .count();
+
+    // Calculate word count for density scoring
+    let word_count = text.split_whitespace().count().max(1) as f64;
+
+    // What the scoring model weighs: keyword variety and density, whether
+    // freight AND bankruptcy terms are both here, and high-signal terms
+    let first = |bankruptcy: bool| {
+        matches
+            .iter()
+            .find(|m| keywords.is_bankruptcy_term(&m.keyword) == bankruptcy)
+            .map(|m| m.keyword.clone())
+    };
+    let high_signal_keywords: Vec<String> = matched_keywords
+        .iter()
+        .filter(|k| keywords.high_signal.contains_key(k.as_str()))
+        .cloned()
+        .collect();
+    let features = scoring::Features {
+        variety: unique.iter().map(|(_, weight)| weight).sum::<f64>() / keywords.len() as f64,
+        density: matches.iter().map(|m| m.weight).sum::<f64>() / word_count,
+        keywords: matched_keywords.clone(),
+        cross_domain: first(false).zip(first(true)),
+        high_signal: high_signal_keywords.iter().map(|k| keywords.high_signal[k.as_str()]).sum(),
+        high_signal_keywords,
+        model: model.map(|model| model.predict(text)),
+    };
+
+    // Denials, maybes, and creditors talking about someone else's case
+    let mut context = context::analyze(text, &matches, |k| keywords.is_bankruptcy_term(k));
+    let (confidence, contributions) = keywords.model.score(&features, &mut context);
+
+    // Classify the company type — every type the text suggests, ranked
+    let classifications = company::classify(keywords, text);
+    let classification = company::primary(&classifications);
+
+    debug!(
+        total_matches = total_matches,
+        unique_keywords = matched_keywords.len(),
+        freight_hits = freight_keyword_hits,
+        bankruptcy_hits = bankruptcy_keyword_hits,
+        confidence = format!("{:.3}", confidence),
+        classification = %classification,
+        context = ?context.iter().map(|a| a.kind).collect::<Vec<_>>(),
+        "Text scan complete"
+    );
+
+    ScanResult {
+        confidence,
+        freight_keyword_hits,
+        bankruptcy_keyword_hits,
+        total_matches,
+        classification,
+        classifications,
+        matched_keywords,
+        matches,
+        context,
+        contributions,
+    }
+}
+
+/// Batch-scan multiple texts in parallel using Rayon.
+///
+/// When you have N texts to scan and M CPU cores, why not use all M cores?
+/// This function takes a slice of text strings and returns scan results
+/// for all of them, processed in parallel.
+///
+/// For 4 texts on an 8-core machine, each text gets its own thread.
+/// For 1000 texts, Rayon's work-stealing scheduler distributes them
+/// efficiently. It's like having a fleet of trucks delivering packages,
+/// except the packages are keyword match results and the trucks are
+/// CPU threads. And some of the packages contain bankruptcy filings.
+pub fn batch_scan(texts: &[&str]) -> Vec<ScanResult> {
+    texts.par_iter().map(|text| scan_text(text)).collect()
+}
+
+/// Quick check if a text contains ANY freight-related keywords.
+/// Uses memchr SIMD scanning for maximum speed.
+/// Returns true if the text is worth a full scan.
+///
+/// This is the "should I even bother?" function. If this returns false,
+/// the text is definitely not about a freight bankruptcy. If it returns
+/// true, we need to do a full scan to be sure. Freight terms from a custom
+/// dictionary that none of these byte patterns would catch get their own
+/// automaton pass.
+pub fn quick_freight_check(text: &str) -> bool {
+    quick_freight_check_with(&BUILTIN_KEYWORDS, text)
+}
+
+/// `quick_freight_check` against a particular vocabulary.
+pub fn quick_freight_check_with(keywords: &Keywords, text: &str) -> bool {
+    let bytes = text.as_bytes();
+    // Check for common freight-related byte patterns using SIMD
+    memchr::memmem::find(bytes, b"freight").is_some()
+        || memchr::memmem::find(bytes, b"Freight").is_some()
+        || memchr::memmem::find(bytes, b"FREIGHT").is_some()
+        || memchr::memmem::find(bytes, b"truck").is_some()
+        || memchr::memmem::find(bytes, b"Truck").is_some()
+        || memchr::memmem::find(bytes, b"carrier").is_some()
+        || memchr::memmem::find(bytes, b"Carrier").is_some()
+        || memchr::memmem::find(bytes, b"logistics").is_some()
+        || memchr::memmem::find(bytes, b"Logistics").is_some()
+        || memchr::memmem::find(bytes, b"3pl").is_some()
+        || memchr::memmem::find(bytes, b"3PL").is_some()
+        || memchr::memmem::find(bytes, b"broker").is_some()
+        || memchr::memmem::find(bytes, b"Broker").is_some()
+        || keywords.prefilter_matches(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_text_returns_zero_confidence() {
+        let result = scan_text("");
+        assert_eq!(result.confidence, 0.0);
+    }
+
+    #[test]
+    fn test_irrelevant_text_returns_zero() {
+        let result = scan_text("The quick brown fox jumps over the lazy dog");
+        assert_eq!(result.confidence, 0.0);
+    }
+
+    #[test]
+    fn test_freight_bankruptcy_text_scores_high() {
+        let text = "XYZ Trucking Company, a motor carrier with USDOT number 12345, \
+                    has filed for Chapter 11 bankruptcy protection. The freight carrier \
+                    operated a fleet of 200 trucks and employed 500 CDL drivers. \
+                    The bankruptcy filing was submitted to the court on January 15.";
+        let result = scan_text(text);
+        assert!(result.confidence > 0.5);
+        assert!(result.freight_keyword_hits > 0);
+        assert!(result.bankruptcy_keyword_hits > 0);
+    }
+
+    #[test]
+    fn test_classification_carrier() {
+        let text = "ABC Motor Carrier with CDL drivers and a fleet of tractors and trailers";
+        let result = scan_text(text);
+        assert_eq!(result.classification, CompanyClassification::Carrier);
+    }
+
+    #[test]
+    fn test_batch_scan_parallel() {
+        let texts = vec![
+            "freight trucking bankruptcy chapter 11",
+            "the cat sat on the mat",
+            "logistics carrier filing chapter 7",
+        ];
+        let results = batch_scan(&texts);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].confidence > 0.0);
+        assert_eq!(results[1].confidence, 0.0);
+        assert!(results[2].confidence > 0.0);
+    }
+
+    #[test]
+    fn test_quick_freight_check() {
+        assert!(quick_freight_check("This is about freight"));
+        assert!(quick_freight_check("A trucking company"));
+        assert!(!quick_freight_check("The weather is nice today"));
+    }
+
+    #[test]
+    fn test_keywords_need_word_boundaries() {
+        // (text, keyword that must NOT match)
+        let false_positives: &[(&str, &str)] = &[
+            ("initial disclosures were served", "tia"),
+            ("a seminar on restructuring", "semi"),
+            ("the CEO's podcast", "pod"),
+            ("trading under the symbol ACME", "bol"),
+            ("the ooltlewah terminal", "ltl"),
+            ("potential tractorbeam", "tractor"),
+            ("data retention policy", "ata"),
+            ("annual filing of form 10-K", "filing"),
+            ("pursuant to the petitioner's request", "petition"),
+        ];
+        for (text, keyword) in false_positives {
+            let result = scan_text(&format!("freight {}", text));
+            assert!(
+                !result.matched_keywords.iter().any(|k| k == keyword),
+                "{:?} matched {:?}: {:?}",
+                text,
+                keyword,
+                result.matched_keywords
+            );
+        }
+
+        // (text, keyword that MUST match)
+        let true_positives: &[(&str, &str)] = &[
+            ("a TIA member broker", "tia"),
+            ("forty semi trucks", "semi"),
+            ("signed POD and BOL", "pod"),
+            ("signed POD and BOL", "bol"),
+            ("an LTL carrier", "ltl"),
+            ("the debtors' carriers", "carrier"),
+            ("the debtor's trailers", "debtor"),
+            ("the company went bankrupt", "bankrupt"),
+            ("it was bankrupted by fuel costs", "bankrupt"),
+            ("filed for bankruptcy", "bankruptcy"),
+            ("(freight)", "freight"),
+            ("third-party logistics", "third-party logistics"),
+        ];
+        for (text, keyword) in true_positives {
+            let result = scan_text(&format!("freight {}", text));
+            assert!(
+                result.matched_keywords.iter().any(|k| k == keyword),
+                "{:?} missed {:?}: {:?}",
+                text,
+                keyword,
+                result.matched_keywords
+            );
+        }
+    }
+
+    #[test]
+    fn test_match_spans_cover_the_matched_words() {
+        let text = "Acme Trucking, a motor carrier, went bankrupt; its carriers' trucks";
+        let result = scan_text(text);
+        let spans: Vec<(&str, &str)> = result
+            .matches
+            .iter()
+            .map(|m| (m.keyword.as_str(), &text[m.start..m.end]))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("trucking", "Trucking"),
+                ("motor carrier", "motor carrier"),
+                ("bankrupt", "bankrupt"),
+                ("carrier", "carriers"),
+            ]
+        );
+        assert_eq!(result.total_matches, 4);
+    }
+
+    fn custom(extra: &str) -> Keywords {
+        let text = format!("{}\n{}", dictionary::BUILTIN, extra)
+            .replace("[freight]\n", "[freight]\n\"hotshot\" = 1.0\n\"reefer lanes\" = 1.0\n");
+        Keywords::compile(&Dictionary::parse(&text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_custom_dictionary_terms_are_found() {
+        let keywords = custom("");
+        let text = "Lone Star Hotshot LLC ran reefer lanes until its Chapter 7 petition";
+        let result = scan_text_with(&keywords, None, text);
+        assert!(result.matched_keywords.contains(&"hotshot".to_string()), "{:?}", result.matched_keywords);
+        assert!(result.matched_keywords.contains(&"reefer lanes".to_string()));
+        assert!(result.confidence > 0.3, "{}", result.confidence);
+
+        // The built-in vocabulary has never heard of either.
+        let builtin = scan_text_with(&Keywords::builtin(), None, text);
+        assert!(!builtin.matched_keywords.contains(&"hotshot".to_string()));
+        assert!(keywords.prefilter_matches("HOTSHOT"));
+        assert!(!Keywords::builtin().prefilter_matches("HOTSHOT"));
+    }
+
+    #[test]
+    fn test_weights_decide_the_classification() {
+        let text = "a freight broker with a fleet of trucks and a driver pool";
+        let classes = company::classify(&Keywords::builtin(), text);
+        assert_eq!(company::primary(&classes), CompanyClassification::Carrier);
+        assert_eq!(classes[1].classification, CompanyClassification::Broker);
+
+        let heavy_broker = Dictionary::parse(
+            &dictionary::BUILTIN.replace("[broker]\n\"freight broker\" = 1.0", "[broker]\n\"freight broker\" = 3.0"),
+        )
+        .unwrap();
+        let keywords = Keywords::compile(&heavy_broker).unwrap();
+        assert_eq!(company::primary(&company::classify(&keywords, text)), CompanyClassification::Broker);
+    }
+
+    #[test]
+    fn test_classification_ignores_words_inside_words() {
+        // "tia" in "initial", "driver" in "drivers" is fine, "import" in
+        // "important" is not.
+        let text = "An important initial hearing for the freight drivers";
+        assert_eq!(company::primary(&company::classify(&BUILTIN_KEYWORDS, text)), CompanyClassification::Carrier);
+    }
+}
@@ -0,0 +1,357 @@
+// =============================================================================
+// text_scanner/scoring.rs — SHOWING OUR WORK
+// =============================================================================
+//
+// The confidence score used to be four bonuses with their constants baked
+// into `scan_text`, and the only explanation anyone got was the list of
+// keywords that matched. "0.73" is not an answer when an analyst asks why
+// a frozen-pizza distributor is on the dashboard.
+//
+// Now every feature has a weight in a `ScoringModel`, which lives in the
+// keyword dictionary's [scoring] table and reloads with it, and `score`
+// returns what each feature added:
+//
+//   keyword_variety   distinct keywords, by weight, over the dictionary size
+//   keyword_density   keyword weight per word of text
+//   cross_domain      freight AND bankruptcy terms in the same text
+//   high_signal       terms like "motor carrier" or "chapter 11"
+//   cap               whatever took the sum past 1.0, taken back off
+//   model             what blending in the trained relevance model moved
+//   negated, hypothetical, creditor_role
+//                     what the context multipliers took away
+//
+// The contributions always add up to the confidence. If they don't, the
+// dashboard is lying, and we'd rather the tests caught that than a shipper.
+// =============================================================================
+
+use serde::Deserialize;
+
+use crate::models::ScoreContribution;
+
+use super::classifier::Prediction;
+use super::context::{ContextAdjustment, ContextKind};
+
+/// The weight of every scoring feature. The defaults are the constants the
+/// scanner has always used.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringModel {
+    /// Points per unit of keyword variety (matched weight / dictionary size).
+    pub variety_weight: f64,
+    /// Most points keyword variety can add.
+    pub variety_cap: f64,
+    /// Points per unit of keyword density (keyword weight / word count).
+    pub density_weight: f64,
+    /// Most points keyword density can add.
+    pub density_cap: f64,
+    /// Points for freight and bankruptcy terms in the same text.
+    pub cross_domain: f64,
+    /// Points per unit of high-signal weight matched.
+    pub high_signal_weight: f64,
+    /// Most points high-signal terms can add.
+    pub high_signal_cap: f64,
+    /// Share of the score given to the trained relevance model, when one is
+    /// loaded: 0 ignores it, 1 ignores the keywords.
+    pub model_weight: f64,
+    /// Multiplier when every bankruptcy mention is denied.
+    pub negated: f64,
+    /// Multiplier when every bankruptcy mention is a maybe.
+    pub hypothetical: f64,
+    /// Multiplier when the freight company is only ever a creditor.
+    pub creditor_role: f64,
+    /// Company-type keyword weight at which a type scores 0.5. More weight
+    /// climbs toward 1.0 without reaching it.
+    pub class_half_weight: f64,
+    /// Company types scoring less than this aren't listed at all.
+    pub class_min_score: f64,
+}
+
+impl Default for ScoringModel {
+    fn default() -> Self {
+        Self {
+            variety_weight: 4.0,
+            variety_cap: 0.4,
+            density_weight: 30.0,
+            density_cap: 0.3,
+            cross_domain: 0.2,
+            high_signal_weight: 0.05,
+            high_signal_cap: 0.1,
+            model_weight: 0.5,
+            negated: ContextKind::Negated.factor(),
+            hypothetical: ContextKind::Hypothetical.factor(),
+            creditor_role: ContextKind::CreditorRole.factor(),
+            class_half_weight: 1.0,
+            class_min_score: 0.5,
+        }
+    }
+}
+
+/// What the scanner measured, before any weighting.
+#[derive(Debug, Clone, Default)]
+pub struct Features {
+    /// Weight of the distinct keywords matched, over the dictionary size.
+    pub variety: f64,
+    /// Weight of every keyword match, over the word count.
+    pub density: f64,
+    /// Distinct keywords matched.
+    pub keywords: Vec<String>,
+    /// A freight keyword and a bankruptcy keyword, if the text has both.
+    pub cross_domain: Option<(String, String)>,
+    /// High-signal weight matched, and the terms that matched.
+    pub high_signal: f64,
+    pub high_signal_keywords: Vec<String>,
+    /// The trained relevance model's opinion, if one is loaded.
+    pub model: Option<Prediction>,
+}
+
+impl ScoringModel {
+    /// Add every problem with the model's weights to `problems`.
+    pub fn validate(&self, problems: &mut Vec<String>) {
+        let weights = [
+            ("variety_weight", self.variety_weight),
+            ("variety_cap", self.variety_cap),
+            ("density_weight", self.density_weight),
+            ("density_cap", self.density_cap),
+            ("cross_domain", self.cross_domain),
+            ("high_signal_weight", self.high_signal_weight),
+            ("high_signal_cap", self.high_signal_cap),
+        ];
+        for (name, weight) in weights {
+            if !weight.is_finite() || weight < 0.0 {
+                problems.push(format!("[scoring] {} is {}; it must be zero or more", name, weight));
+            }
+        }
+        let factors = [
+            ("model_weight", self.model_weight),
+            ("negated", self.negated),
+            ("hypothetical", self.hypothetical),
+            ("creditor_role", self.creditor_role),
+            ("class_min_score", self.class_min_score),
+        ];
+        for (name, factor) in factors {
+            if !(0.0..=1.0).contains(&factor) {
+                problems.push(format!("[scoring] {} is {}; it must be between 0 and 1", name, factor));
+            }
+        }
+        if !self.class_half_weight.is_finite() || self.class_half_weight <= 0.0 {
+            problems.push(format!(
+                "[scoring] class_half_weight is {}; it must be above zero",
+                self.class_half_weight
+            ));
+        }
+    }
+
+    /// How sure `weight` worth of a company type's keywords makes us that
+    /// the company is that type: 0.5 at `class_half_weight`, approaching 1.0
+    /// as the weight grows.
+    pub fn class_score(&self, weight: f64) -> f64 {
+        weight / (weight + self.class_half_weight)
+    }
+
+    /// The multiplier for a kind of context.
+    pub fn context_factor(&self, kind: ContextKind) -> f64 {
+        match kind {
+            ContextKind::Negated => self.negated,
+            ContextKind::Hypothetical => self.hypothetical,
+            ContextKind::CreditorRole => self.creditor_role,
+        }
+    }
+
+    /// The confidence for `features`, scaled by `context`, and what each
+    /// feature contributed to it. Sets each adjustment's factor to this
+    /// model's.
+    pub fn score(&self, features: &Features, context: &mut [ContextAdjustment]) -> (f64, Vec<ScoreContribution>) {
+        let mut contributions: Vec<ScoreContribution> = Vec::new();
+        let mut add = |feature: &str, value: f64, weight: f64, points: f64, evidence: Vec<String>| {
+            if points != 0.0 {
+                contributions.push(ScoreContribution {
+                    feature: feature.to_string(),
+                    value,
+                    weight,
+                    points,
+                    evidence,
+                });
+            }
+            points
+        };
+
+        let mut confidence = 0.0;
+        confidence += add(
+            "keyword_variety",
+            features.variety,
+            self.variety_weight,
+            (features.variety * self.variety_weight).min(self.variety_cap),
+            features.keywords.clone(),
+        );
+        confidence += add(
+            "keyword_density",
+            features.density,
+            self.density_weight,
+            (features.density * self.density_weight).min(self.density_cap),
+            Vec::new(),
+        );
+        if let Some((freight, bankruptcy)) = &features.cross_domain {
+            confidence += add(
+                "cross_domain",
+                1.0,
+                self.cross_domain,
+                self.cross_domain,
+                vec![freight.clone(), bankruptcy.clone()],
+            );
+        }
+        confidence += add(
+            "high_signal",
+            features.high_signal,
+            self.high_signal_weight,
+            (features.high_signal * self.high_signal_weight).min(self.high_signal_cap),
+            features.high_signal_keywords.clone(),
+        );
+        if confidence > 1.0 {
+            add("cap", confidence, 1.0, 1.0 - confidence, Vec::new());
+            confidence = 1.0;
+        }
+
+        if let Some(prediction) = &features.model {
+            let blended = (1.0 - self.model_weight) * confidence + self.model_weight * prediction.probability;
+            add(
+                "model",
+                prediction.probability,
+                self.model_weight,
+                blended - confidence,
+                prediction.evidence.clone(),
+            );
+            confidence = blended;
+        }
+
+        for adjustment in context.iter_mut() {
+            adjustment.factor = self.context_factor(adjustment.kind);
+            let before = confidence;
+            confidence *= adjustment.factor;
+            add(
+                adjustment.kind.name(),
+                1.0,
+                adjustment.factor,
+                confidence - before,
+                adjustment.evidence.clone(),
+            );
+        }
+
+        (confidence, contributions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{scan_text, scan_text_with, Dictionary, Keywords};
+    use super::*;
+
+    fn total(contributions: &[ScoreContribution]) -> f64 {
+        contributions.iter().map(|c| c.points).sum()
+    }
+
+    #[test]
+    fn test_contributions_add_up_to_the_confidence() {
+        let texts = [
+            "XYZ Trucking, a motor carrier with USDOT 12345, filed for Chapter 11 bankruptcy",
+            "freight trucking carrier logistics drayage intermodal chapter 11 chapter 7 motor carrier 3pl",
+            "XYZ Trucking is not in Chapter 11 bankruptcy",
+            "Proof of claim filed by XYZ Trucking in the Chapter 11 case of ABC Retail",
+            "freight",
+            "The quick brown fox jumps over the lazy dog",
+        ];
+        for text in texts {
+            let result = scan_text(text);
+            assert!(
+                (total(&result.contributions) - result.confidence).abs() < 1e-9,
+                "{:?}: {} vs {:?}",
+                text,
+                result.confidence,
+                result.contributions
+            );
+        }
+    }
+
+    #[test]
+    fn test_breakdown_explains_the_score() {
+        let result = scan_text("XYZ Trucking is not in Chapter 11 bankruptcy");
+        let features: Vec<&str> = result.contributions.iter().map(|c| c.feature.as_str()).collect();
+        assert_eq!(
+            features,
+            vec!["keyword_variety", "keyword_density", "cross_domain", "high_signal", "negated"]
+        );
+
+        let cross = &result.contributions[2];
+        assert_eq!(cross.evidence, vec!["trucking".to_string(), "chapter 11".to_string()]);
+        assert_eq!(result.contributions[3].evidence, vec!["chapter 11".to_string()]);
+        let negated = &result.contributions[4];
+        assert!(negated.points < 0.0);
+        assert_eq!(negated.weight, 0.2);
+    }
+
+    #[test]
+    fn test_model_weights_come_from_the_dictionary() {
+        let text = "XYZ Trucking filed for Chapter 11 bankruptcy";
+        let tuned = Dictionary::parse(
+            &super::super::dictionary::BUILTIN
+                .replace("cross_domain = 0.2", "cross_domain = 0.5")
+                .replace("negated = 0.2", "negated = 0.0"),
+        )
+        .unwrap();
+        let keywords = Keywords::compile(&tuned).unwrap();
+
+        let default = scan_text_with(&Keywords::builtin(), None, text);
+        let result = scan_text_with(&keywords, None, text);
+        assert!((result.confidence - default.confidence - 0.3).abs() < 1e-9);
+
+        let denial = scan_text_with(&keywords, None, "XYZ Trucking is not in Chapter 11 bankruptcy");
+        assert_eq!(denial.confidence, 0.0);
+    }
+
+    #[test]
+    fn test_model_is_blended_in_and_explained() {
+        use super::super::classifier::{Example, RelevanceModel, TrainOptions};
+
+        let examples: Vec<Example> = [
+            ("Acme Trucking filed a Chapter 11 petition", true),
+            ("Big Rig Freight filed a Chapter 11 petition", true),
+            ("proof of claim filed by Acme Trucking in a Chapter 11 case", false),
+            ("proof of claim filed by Big Rig Freight in a Chapter 11 case", false),
+        ]
+        .iter()
+        .map(|(text, relevant)| Example { text: text.to_string(), relevant: *relevant })
+        .collect();
+        let model = RelevanceModel::train(&examples, &TrainOptions::default()).unwrap();
+
+        let text = "Proof of claim filed by Hotshot Trucking in the Chapter 11 case of ABC Retail";
+        let keywords = Keywords::builtin();
+        let without = scan_text_with(&keywords, None, text);
+        let with = scan_text_with(&keywords, Some(&model), text);
+        assert!(with.confidence < without.confidence);
+
+        let blend = with.contributions.iter().find(|c| c.feature == "model").unwrap();
+        assert!(blend.value < 0.5);
+        assert_eq!(blend.weight, 0.5);
+        assert!(!blend.evidence.is_empty());
+        assert!(blend.evidence.iter().all(|gram| text.to_lowercase().contains(gram.as_str())), "{:?}", blend.evidence);
+        assert!((total(&with.contributions) - with.confidence).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bad_weights_are_rejected() {
+        // ([scoring] line, something the error must mention)
+        let cases = [
+            ("cross_domain = -0.2", "cross_domain is -0.2"),
+            ("negated = 1.5", "between 0 and 1"),
+            ("model_weight = 2.0", "between 0 and 1"),
+            ("density_weight = nan", "density_weight is NaN"),
+            ("bonus = 1.0", "not valid TOML"),
+            ("class_half_weight = 0.0", "class_half_weight is 0"),
+            ("class_min_score = 1.5", "between 0 and 1"),
+        ];
+        for (scoring, expected) in cases {
+            let text = format!("[freight]\n\"freight\" = 1.0\n[scoring]\n{}\n", scoring);
+            let error = format!("{:#}", Dictionary::parse(&text).unwrap_err());
+            assert!(error.contains(expected), "{} → {}", scoring, error);
+        }
+    }
+}
@@ -86,7 +86,7 @@ export default class extends Controller {
       // Also fetch metrics
       let metricsData = null
       try {
-        const metricsResponse = await fetch(`${this.urlValue}/metrics`, {
+        const metricsResponse = await fetch(`${this.urlValue}/metrics.json`, {
           signal: AbortSignal.timeout(2000)
         })
         if (metricsResponse.ok) {
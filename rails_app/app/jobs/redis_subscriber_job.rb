@@ -45,6 +45,16 @@ class RedisSubscriberJob < ApplicationJob
     data = JSON.parse(raw_message)
     Rails.logger.info "[DOOM TRACKER] Processing event: #{data['company_name'] || data['companyName'] || 'unknown'}"
 
+    # A conversion, dismissal or plan confirmation on a case we already
+    # have: update its row, never add one.
+    return apply_case_update(data) if (data["event_type"] || data["eventType"]) == "CaseUpdate"
+
+    # Every source that reports a bankruptcy publishes an event with the
+    # same case_id. The first one makes the row; the rest corroborate it.
+    case_id = data["case_id"] || data["caseId"]
+    existing = case_id.present? && BankruptcyEvent.find_by(case_id: case_id)
+    return corroborate(existing, data) if existing
+
     # Normalize keys from Rust (snake_case or camelCase)
     event = BankruptcyEvent.create!(
       company_name:     data["company_name"] || data["companyName"],
@@ -57,7 +67,10 @@ class RedisSubscriberJob < ApplicationJob
       confidence_score: (data["confidence_score"] || data["confidenceScore"] || 0.0).to_f,
       raw_data:         data,
       detected_at:      parse_timestamp(data["detected_at"] || data["detectedAt"]),
-      status:           "new"
+      status:           "new",
+      case_id:          case_id,
+      case_number:      data["case_number"] || data["caseNumber"],
+      corroborating_sources: sources_of(data)
     )
 
     Rails.logger.info "[DOOM TRACKER] Created event ##{event.id}: #{event.company_name} (Chapter #{event.chapter})"
@@ -75,6 +88,63 @@ class RedisSubscriberJob < ApplicationJob
     Rails.logger.error "[DOOM TRACKER] Error processing event: #{e.message}"
   end
 
+  # Another source reported a case we already have a row for: list the
+  # source, take the engine's combined confidence, and fill in anything the
+  # first source didn't know. The analyst's status is left alone, and so is
+  # the first detection's raw_data (its score breakdown, classifications and
+  # case updates); each corroborating payload is appended to
+  # raw_data["corroborations"].
+  def corroborate(event, data)
+    raw = event.raw_data.is_a?(Hash) ? event.raw_data : {}
+    event.update!(
+      confidence_score: [event.confidence_score.to_f, (data["confidence_score"] || data["confidenceScore"]).to_f].max,
+      corroborating_sources: (Array(event.corroborating_sources) + sources_of(data)).uniq,
+      dot_number:  event.dot_number.presence || data["dot_number"] || data["dotNumber"],
+      mc_number:   event.mc_number.presence || data["mc_number"] || data["mcNumber"],
+      filing_date: event.filing_date || parse_date(data["filing_date"] || data["filingDate"]),
+      court:       event.court.presence || data["court"],
+      chapter:     event.chapter || parse_chapter(data["chapter"]),
+      raw_data:    raw.merge("corroborations" => Array(raw["corroborations"]) + [data])
+    )
+
+    Rails.logger.info "[DOOM TRACKER] Corroborated event ##{event.id}: #{event.company_name} (#{event.corroborating_sources.join(", ")})"
+    BankruptcyEventsChannel.broadcast_event(event)
+  end
+
+  # Apply a CaseUpdate to the case's row, found by case_id or court + case
+  # number. The update's history is kept in raw_data["case_updates"], and
+  # affiliates that joined the case are added to raw_data["affiliates"]; the
+  # rest of the detection's raw_data stays as it was.
+  def apply_case_update(data)
+    update = data["case_update"] || data["caseUpdate"] || {}
+    case_id = data["case_id"] || data["caseId"]
+    case_number = data["case_number"] || data["caseNumber"]
+    event = (BankruptcyEvent.find_by(case_id: case_id) if case_id.present?) ||
+            (BankruptcyEvent.find_by(court: data["court"], case_number: case_number) if case_number.present?)
+
+    unless event
+      Rails.logger.warn "[DOOM TRACKER] Case update for unknown case #{case_id || case_number || update['case_key']}; ignored"
+      return
+    end
+
+    raw = event.raw_data.is_a?(Hash) ? event.raw_data : {}
+    event.update!(
+      chapter:     parse_chapter(update["chapter"]) || event.chapter,
+      case_status: update["status"].to_s.underscore.presence || event.case_status,
+      raw_data:    raw.merge(
+        "case_updates" => Array(raw["case_updates"]) + [update],
+        "affiliates"   => (Array(raw["affiliates"]) + Array(data["affiliates"])).uniq
+      )
+    )
+
+    Rails.logger.info "[DOOM TRACKER] Updated event ##{event.id}: #{event.company_name} is now #{event.case_status} (Chapter #{event.chapter})"
+    BankruptcyEventsChannel.broadcast_event(event)
+  end
+
+  def sources_of(data)
+    Array(data["corroborating_sources"] || data["corroboratingSources"]).presence || Array(data["source"]).compact
+  end
+
   def parse_date(value)
     return nil if value.blank?
     Date.parse(value.to_s)
@@ -84,8 +154,9 @@ class RedisSubscriberJob < ApplicationJob
 
   def parse_chapter(value)
     return nil if value.blank?
-    ch = value.to_i
-    [7, 11, 13, 15].include?(ch) ? ch : nil
+    # The engine sends "Chapter7", "Chapter11SubchapterV", ...; take the number.
+    ch = value.to_s[/\d+/].to_i
+    [7, 9, 11, 12, 13, 15].include?(ch) ? ch : nil
   end
 
   def parse_timestamp(value)
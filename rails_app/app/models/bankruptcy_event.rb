@@ -29,7 +29,7 @@ class BankruptcyEvent < ApplicationRecord
     greater_than_or_equal_to: 0.0,
     less_than_or_equal_to: 1.0
   }, allow_nil: true
-  validates :chapter, inclusion: { in: [7, 11, 13, 15] }, allow_nil: true
+  validates :chapter, inclusion: { in: [7, 9, 11, 12, 13, 15] }, allow_nil: true
 
   # ---------------------------------------------------------------------------
   # Scopes
@@ -129,6 +129,29 @@ class BankruptcyEvent < ApplicationRecord
     end
   end
 
+  # What each scoring feature added to the confidence score, largest effect
+  # first, as the engine reported it in `score_breakdown`. Empty for events
+  # from engines that predate it.
+  def score_breakdown
+    entries = raw_data.is_a?(Hash) ? Array(raw_data["score_breakdown"]) : []
+    entries
+      .select { |entry| entry.is_a?(Hash) && entry["feature"].present? }
+      .sort_by { |entry| -entry["points"].to_f.abs }
+  end
+
+  # Every type of company the engine thinks this is, most likely first, as
+  # [label, percentage] pairs from `classifications`. Events from engines
+  # that predate the list get their one `classification`, unscored.
+  def company_types
+    data = raw_data.is_a?(Hash) ? raw_data : {}
+    entries = Array(data["classifications"]).select { |entry| entry.is_a?(Hash) && entry["classification"].present? }
+    if entries.empty?
+      single = data["classification"]
+      return single.present? && single != "Unclassified" ? [[company_type_label(single), nil]] : []
+    end
+    entries.map { |entry| [company_type_label(entry["classification"]), (entry["score"].to_f * 100).round] }
+  end
+
   def time_ago_detected
     return "Unknown" unless detected_at
     seconds = (Time.current - detected_at).to_i
@@ -155,6 +178,14 @@ class BankruptcyEvent < ApplicationRecord
 
   private
 
+  def company_type_label(value)
+    case value
+    when "ThirdPartyLogistics" then "3PL"
+    when "Ltl" then "LTL"
+    else value.to_s.titleize
+    end
+  end
+
   def broadcast_new_event
     # Broadcast to the Turbo Stream channel for live dashboard updates
     broadcast_prepend_to(
@@ -76,6 +76,7 @@ end
 
 def safe_parse_chapter(value)
   return nil if value.blank?
-  ch = value.to_i
-  [7, 11, 13, 15].include?(ch) ? ch : nil
+  # The engine sends "Chapter7", "Chapter11SubchapterV", ...; take the number.
+  ch = value.to_s[/\d+/].to_i
+  [7, 9, 11, 12, 13, 15].include?(ch) ? ch : nil
 end
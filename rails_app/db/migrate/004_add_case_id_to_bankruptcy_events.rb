@@ -0,0 +1,10 @@
+class AddCaseIdToBankruptcyEvents < ActiveRecord::Migration[7.1]
+  # The engine publishes one event per source that reports a bankruptcy,
+  # all sharing a case_id. One row per case, with the sources listed on it.
+  def change
+    add_column :bankruptcy_events, :case_id, :string
+    add_column :bankruptcy_events, :corroborating_sources, :json, default: []
+
+    add_index :bankruptcy_events, :case_id, unique: true
+  end
+end
@@ -0,0 +1,11 @@
+class AddCaseLifecycleToBankruptcyEvents < ActiveRecord::Migration[7.1]
+  # CaseUpdate events from the engine (conversions, dismissals, plan
+  # confirmations) update the case's row rather than adding one. They find
+  # it by case_id, or by court + case number.
+  def change
+    add_column :bankruptcy_events, :case_number, :string
+    add_column :bankruptcy_events, :case_status, :string, default: "open"
+
+    add_index :bankruptcy_events, [:court, :case_number]
+  end
+end
@@ -10,7 +10,7 @@
 #
 # It's strongly recommended that you check this file into your version control system.
 
-ActiveRecord::Schema[7.2].define(version: 3) do
+ActiveRecord::Schema[7.2].define(version: 5) do
   create_table "alert_subscriptions", force: :cascade do |t|
     t.string "email", null: false
     t.string "carrier_type_filter"
@@ -37,8 +37,14 @@ ActiveRecord::Schema[7.2].define(version: 3) do
     t.string "status", default: "new", null: false
     t.datetime "created_at", null: false
     t.datetime "updated_at", null: false
+    t.string "case_id"
+    t.json "corroborating_sources", default: []
+    t.string "case_number"
+    t.string "case_status", default: "open"
+    t.index ["case_id"], name: "index_bankruptcy_events_on_case_id", unique: true
     t.index ["chapter"], name: "index_bankruptcy_events_on_chapter"
     t.index ["company_name"], name: "index_bankruptcy_events_on_company_name"
+    t.index ["court", "case_number"], name: "index_bankruptcy_events_on_court_and_case_number"
     t.index ["detected_at"], name: "index_bankruptcy_events_on_detected_at"
     t.index ["dot_number"], name: "index_bankruptcy_events_on_dot_number"
     t.index ["filing_date"], name: "index_bankruptcy_events_on_filing_date"